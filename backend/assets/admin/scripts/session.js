@@ -0,0 +1,97 @@
+/**
+ * Keeps the admin panel signed in. Access tokens are short-lived and the
+ * panel only knows about `auth_token`, so shortly before it expires this
+ * trades the stored refresh token for a new pair. A refused refresh signs
+ * the panel out, and its next request leads back to the login page.
+ */
+(function () {
+  var ACCESS_KEY = 'auth_token';
+  var REFRESH_KEY = 'refresh_token';
+  // Refresh this long before the access token expires
+  var MARGIN_MS = 60 * 1000;
+  var CHECK_EVERY_MS = 30 * 1000;
+  var refreshing = false;
+
+  function store(session) {
+    if (session && session.token && session.refresh_token) {
+      localStorage.setItem(ACCESS_KEY, session.token);
+      localStorage.setItem(REFRESH_KEY, session.refresh_token);
+    }
+  }
+
+  function expiresAt(token) {
+    try {
+      var payload = token.split('.')[1].replace(/-/g, '+').replace(/_/g, '/');
+      return JSON.parse(atob(payload)).exp * 1000;
+    } catch (e) {
+      return 0;
+    }
+  }
+
+  function isDue() {
+    var token = localStorage.getItem(ACCESS_KEY);
+    return !!token && !!localStorage.getItem(REFRESH_KEY) && expiresAt(token) - Date.now() <= MARGIN_MS;
+  }
+
+  function refresh() {
+    // Another tab may have refreshed while this one waited for the lock
+    if (!isDue()) {
+      return Promise.resolve();
+    }
+    return fetch('/api/auth/refresh', {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ refresh_token: localStorage.getItem(REFRESH_KEY) }),
+    })
+      .then(function (response) {
+        if (response.ok) {
+          return response.json().then(store);
+        }
+        if (response.status === 401) {
+          localStorage.removeItem(ACCESS_KEY);
+          localStorage.removeItem(REFRESH_KEY);
+        }
+      })
+      .catch(function () {});
+  }
+
+  // Refresh tokens are single use, and presenting a spent one ends the
+  // session, so tabs take turns
+  function refreshIfDue() {
+    if (!isDue()) {
+      return;
+    }
+    if (navigator.locks) {
+      navigator.locks.request('auth-refresh', refresh);
+    } else if (!refreshing) {
+      refreshing = true;
+      refresh().then(function () {
+        refreshing = false;
+      });
+    }
+  }
+
+  // The panel's own sign-in form keeps only the access token; take the
+  // refresh token from the same response
+  var open = XMLHttpRequest.prototype.open;
+  XMLHttpRequest.prototype.open = function (method, url) {
+    if (/\/api\/auth\/login$/.test(String(url))) {
+      this.addEventListener('load', function () {
+        if (this.status === 200) {
+          try {
+            store(JSON.parse(this.responseText));
+          } catch (e) {}
+        }
+      });
+    }
+    return open.apply(this, arguments);
+  };
+
+  refreshIfDue();
+  setInterval(refreshIfDue, CHECK_EVERY_MS);
+  document.addEventListener('visibilitychange', function () {
+    if (document.visibilityState === 'visible') {
+      refreshIfDue();
+    }
+  });
+})();
@@ -1,5 +1,11 @@
 use axum::{http::StatusCode, response::Html};
 
+#[utoipa::path(
+    get,
+    path = "/login",
+    tag = "admin",
+    responses((status = 200, description = "Admin sign-in page", body = String, content_type = "text/html"))
+)]
 pub async fn admin_login_page() -> Result<Html<String>, StatusCode> {
     let html = include_str!("../../assets/login.html");
     Ok(Html(html.to_string()))
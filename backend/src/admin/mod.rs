@@ -2,7 +2,7 @@ pub mod login;
 
 use axum::{
     extract::State,
-    http::{HeaderMap, StatusCode},
+    http::{header, HeaderMap},
     response::{Html, IntoResponse},
     Json,
 };
@@ -10,21 +10,43 @@ use async_graphql::{http::GraphQLPlaygroundConfig, http::playground_source};
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 use sea_orm_pro::{ConfigParser, JsonCfg};
 use tracing::error;
-use crate::{auth, graphql, AppState};
+use crate::{
+    auth::{self, AuthUser, Role},
+    error::{ApiError, ErrorResponse},
+    graphql::{self, OperationKind, PublicReader},
+    AppState,
+};
 
 pub use login::admin_login_page;
 
-pub async fn admin_panel_config() -> Result<Json<JsonCfg>, (StatusCode, &'static str)> {
+#[utoipa::path(
+    get,
+    path = "/api/admin/config",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Admin panel configuration", body = serde_json::Value),
+        (status = 500, description = "Configuration could not be loaded", body = ErrorResponse),
+    )
+)]
+pub async fn admin_panel_config(
+    State(state): State<AppState>,
+) -> Result<Json<JsonCfg>, ApiError> {
     let config = ConfigParser::new()
-        .load_config("pro_admin")
+        .load_config(&state.config.admin_config_path)
         .map_err(|e| {
             error!("Failed to load admin config: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load admin config")
+            ApiError::internal("Failed to load admin config")
         })?;
     
     Ok(Json(config))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/graphql",
+    tag = "graphql",
+    responses((status = 200, description = "GraphQL playground", body = String, content_type = "text/html"))
+)]
 pub async fn graphql_playground() -> impl IntoResponse {
     let config = GraphQLPlaygroundConfig::new("/api/graphql")
         .with_header("Authorization", "Bearer TOKEN_HERE");
@@ -37,29 +59,56 @@ pub async fn graphql_playground() -> impl IntoResponse {
     Html(html)
 }
 
+/// Runs a GraphQL request against the admin schema. See the playground at
+/// `GET /api/graphql` for the schema itself.
+#[utoipa::path(
+    post,
+    path = "/api/graphql",
+    tag = "graphql",
+    request_body(content = serde_json::Value, description = "`{query, operationName, variables}`"),
+    security((), ("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "GraphQL response; field errors are reported in `errors`", body = serde_json::Value),
+        (status = 400, description = "Invalid GraphQL document", body = ErrorResponse),
+        (status = 401, description = "Mutation without a valid token", body = ErrorResponse),
+        (status = 403, description = "Role too low for the mutation", body = ErrorResponse),
+    )
+)]
 pub async fn graphql_handler(
     State(state): State<AppState>,
     headers: HeaderMap,
     req: GraphQLRequest,
-) -> Result<GraphQLResponse, (StatusCode, &'static str)> {
+) -> Result<GraphQLResponse, ApiError> {
     let request = req.into_inner();
-    
-    // Allow introspection queries without authentication
-    let is_introspection = request.query.contains("__schema") || request.query.contains("__type");
-    
-    let (depth, complexity) = if is_introspection {
-        (None, None)
-    } else {
-        (Some(10), Some(1000))
-    };
+    let operation = graphql::classify(&request.query, request.operation_name.as_deref())
+        .ok_or_else(|| ApiError::bad_request("Invalid GraphQL document"))?;
 
-    let schema = graphql::schema(state.db.clone(), depth, complexity)
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to create GraphQL schema"))?;
-
-    if !is_introspection {
-        auth::check_user_auth(&headers)?;
+    // Introspection only reads the schema, so it needs no auth and gets looser limits
+    if operation.kind == OperationKind::Introspection {
+        return Ok(state.graphql.introspection.execute(request).await.into());
     }
+    let schema = &state.graphql.limited;
+
+    // Anyone may query; guards limit those below `editor` to published content
+    let Some(min_role) = operation.required_role() else {
+        let user = if headers.contains_key(header::AUTHORIZATION) {
+            Some(AuthUser::try_from(auth::check_user_auth(&headers, &state.config, &state.db).await?)?)
+        } else {
+            None
+        };
+        let request = match user {
+            Some(user) if user.role >= Role::Editor => request.data(user),
+            Some(user) => request.data(user).data(PublicReader),
+            None => request.data(PublicReader),
+        };
+        return Ok(schema.execute(request).await.into());
+    };
 
-    let result = schema.execute(request).await;
-    Ok(result.into())
+    // Mutations need the role of the most protected entity they touch
+    let user = AuthUser::try_from(auth::check_user_auth(&headers, &state.config, &state.db).await?)?;
+    if user.role < min_role {
+        return Err(ApiError::forbidden("Insufficient permissions"));
+    }
+    // Post mutations credit the revisions they write to `user` themselves
+    Ok(schema.execute(request.data(user)).await.into())
 }
@@ -0,0 +1,471 @@
+use chrono::{Duration as ChronoDuration, NaiveDate, Utc};
+use sea_orm::{
+    sea_query::OnConflict, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr,
+    EntityTrait, PaginatorTrait, QueryFilter, Set, Statement, TransactionTrait,
+};
+use sha2::{Digest, Sha256};
+use std::{fmt, net::IpAddr, str::FromStr, time::Duration};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+use crate::entities::{blog_views, view_salts};
+
+const RETENTION_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Lowercase User-Agent fragments of crawlers, link unfurlers and scripted clients.
+const BOT_MARKERS: &[&str] = &[
+    "bot",
+    "crawl",
+    "spider",
+    "slurp",
+    "scrape",
+    "headless",
+    "lighthouse",
+    "facebookexternalhit",
+    "embedly",
+    "preview",
+    "monitor",
+    "curl/",
+    "wget/",
+    "python-requests",
+    "python-urllib",
+    "go-http-client",
+    "okhttp",
+    "java/",
+    "libwww",
+    "httpclient",
+];
+
+/// Requests without a User-Agent are treated as automated too.
+pub fn is_bot(user_agent: Option<&str>) -> bool {
+    match user_agent.map(str::trim) {
+        None | Some("") => true,
+        Some(ua) => {
+            let ua = ua.to_ascii_lowercase();
+            BOT_MARKERS.iter().any(|marker| ua.contains(marker))
+        }
+    }
+}
+
+/// Identifies a visitor for one day without storing who they are.
+pub fn visitor_hash(salt: &str, ip: IpAddr, user_agent: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(ip.to_string().as_bytes());
+    hasher.update(user_agent.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Where a view came from, as reported by the page that was viewed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewSource {
+    pub referrer_host: Option<String>,
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+}
+
+impl ViewSource {
+    /// Referrers on one of `own_hosts` are dropped, so moving around the site
+    /// counts as a direct visit rather than a referral from ourselves.
+    pub fn new(
+        referrer: Option<&str>,
+        utm_source: Option<&str>,
+        utm_medium: Option<&str>,
+        utm_campaign: Option<&str>,
+        own_hosts: &[String],
+    ) -> Self {
+        Self {
+            referrer_host: referrer
+                .and_then(referrer_host)
+                .filter(|host| !own_hosts.contains(host)),
+            utm_source: utm_value(utm_source),
+            utm_medium: utm_value(utm_medium),
+            utm_campaign: utm_value(utm_campaign),
+        }
+    }
+}
+
+/// Lowercased host of an absolute URL, without port or a leading `www.`.
+pub fn referrer_host(url: &str) -> Option<String> {
+    let (_, rest) = url.trim().split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = match host.strip_prefix('[') {
+        // IPv6 literal, e.g. `[::1]:8080`
+        Some(v6) => v6.split(']').next()?,
+        None => host.split(':').next()?,
+    };
+    let host = host.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    (!host.is_empty() && host.len() <= 100).then(|| host.to_string())
+}
+
+fn utm_value(value: Option<&str>) -> Option<String> {
+    let value = value?.trim().to_lowercase();
+    (!value.is_empty()).then(|| value.chars().take(100).collect())
+}
+
+/// Coarse client details parsed from a User-Agent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientInfo {
+    /// `desktop`, `mobile` or `tablet`
+    pub device: &'static str,
+    pub browser: &'static str,
+    pub os: &'static str,
+}
+
+/// Order matters: Chromium-based browsers also claim Chrome and Safari, and
+/// iOS claims to be "like Mac OS X".
+pub fn parse_user_agent(user_agent: &str) -> ClientInfo {
+    let ua = user_agent.to_ascii_lowercase();
+    let has = |needle: &str| ua.contains(needle);
+
+    let os = if has("windows") {
+        "Windows"
+    } else if has("iphone") || has("ipad") || has("ipod") {
+        "iOS"
+    } else if has("android") {
+        "Android"
+    } else if has("cros") {
+        "ChromeOS"
+    } else if has("mac os x") || has("macintosh") {
+        "macOS"
+    } else if has("linux") {
+        "Linux"
+    } else {
+        "Other"
+    };
+
+    let browser = if has("edg/") || has("edga/") || has("edgios/") {
+        "Edge"
+    } else if has("opr/") || has("opera") {
+        "Opera"
+    } else if has("samsungbrowser") {
+        "Samsung Internet"
+    } else if has("firefox/") || has("fxios/") {
+        "Firefox"
+    } else if has("chrome/") || has("crios/") || has("chromium/") {
+        "Chrome"
+    } else if has("safari/") {
+        "Safari"
+    } else {
+        "Other"
+    };
+
+    let device = if has("ipad") || has("tablet") || (has("android") && !has("mobile")) {
+        "tablet"
+    } else if has("mobi") || has("iphone") || has("ipod") {
+        "mobile"
+    } else {
+        "desktop"
+    };
+
+    ClientInfo { device, browser, os }
+}
+
+/// Attributes views can be broken down by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewDimension {
+    Referrer,
+    UtmSource,
+    UtmMedium,
+    UtmCampaign,
+    Device,
+    Browser,
+    Os,
+}
+
+impl ViewDimension {
+    pub const ALL: [ViewDimension; 7] = [
+        ViewDimension::Referrer,
+        ViewDimension::UtmSource,
+        ViewDimension::UtmMedium,
+        ViewDimension::UtmCampaign,
+        ViewDimension::Device,
+        ViewDimension::Browser,
+        ViewDimension::Os,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViewDimension::Referrer => "referrer",
+            ViewDimension::UtmSource => "utm_source",
+            ViewDimension::UtmMedium => "utm_medium",
+            ViewDimension::UtmCampaign => "utm_campaign",
+            ViewDimension::Device => "device",
+            ViewDimension::Browser => "browser",
+            ViewDimension::Os => "os",
+        }
+    }
+
+    /// `blog_views` column holding the dimension.
+    fn column(&self) -> &'static str {
+        match self {
+            ViewDimension::Referrer => "referrer_host",
+            other => other.as_str(),
+        }
+    }
+
+    /// Value reported for views that lack the dimension.
+    pub fn missing_label(&self) -> &'static str {
+        match self {
+            ViewDimension::Referrer => "(direct)",
+            ViewDimension::UtmSource | ViewDimension::UtmMedium | ViewDimension::UtmCampaign => "(none)",
+            _ => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for ViewDimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ViewDimension {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ViewDimension::ALL
+            .into_iter()
+            .find(|d| d.as_str() == s)
+            .ok_or(())
+    }
+}
+
+/// The salt for `day`, created on first use.
+async fn daily_salt(db: &DatabaseConnection, day: NaiveDate) -> Result<String, DbErr> {
+    let candidate = view_salts::ActiveModel {
+        day: Set(day),
+        salt: Set(format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        )),
+    };
+    // Concurrent first views of the day race here; whichever insert wins is used
+    view_salts::Entity::insert(candidate)
+        .on_conflict(OnConflict::column(view_salts::Column::Day).do_nothing().to_owned())
+        .exec_without_returning(db)
+        .await?;
+    view_salts::Entity::find_by_id(day)
+        .one(db)
+        .await?
+        .map(|s| s.salt)
+        .ok_or_else(|| DbErr::RecordNotFound("view salt".to_string()))
+}
+
+/// Records a view of `post_id` unless it comes from a bot or the same visitor
+/// viewed the post within `dedup_window` (`Config::view_dedup_window_minutes`).
+/// Returns whether it was counted.
+pub async fn record_view(
+    db: &DatabaseConnection,
+    dedup_window: ChronoDuration,
+    post_id: i32,
+    ip: IpAddr,
+    user_agent: Option<&str>,
+    source: &ViewSource,
+) -> Result<bool, DbErr> {
+    let Some(user_agent) = user_agent.filter(|ua| !is_bot(Some(ua))) else {
+        return Ok(false);
+    };
+    let now = Utc::now();
+    let salt = daily_salt(db, now.date_naive()).await?;
+    let hash = visitor_hash(&salt, ip, user_agent);
+    // Views from before midnight were hashed with an earlier day's salt
+    let earlier = view_salts::Entity::find()
+        .filter(view_salts::Column::Day.gte((now - dedup_window).date_naive()))
+        .filter(view_salts::Column::Day.lt(now.date_naive()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|s| visitor_hash(&s.salt, ip, user_agent));
+
+    let recent = blog_views::Entity::find()
+        .filter(blog_views::Column::PostId.eq(post_id))
+        .filter(blog_views::Column::VisitorHash.is_in(earlier.chain([hash.clone()])))
+        .filter(blog_views::Column::Timestamp.gt(now - dedup_window))
+        .count(db)
+        .await?;
+    if recent > 0 {
+        return Ok(false);
+    }
+
+    let client = parse_user_agent(user_agent);
+    blog_views::Entity::insert(blog_views::ActiveModel {
+        post_id: Set(post_id),
+        visitor_hash: Set(Some(hash)),
+        timestamp: Set(now),
+        referrer_host: Set(source.referrer_host.clone()),
+        utm_source: Set(source.utm_source.clone()),
+        utm_medium: Set(source.utm_medium.clone()),
+        utm_campaign: Set(source.utm_campaign.clone()),
+        device: Set(Some(client.device.to_string())),
+        browser: Set(Some(client.browser.to_string())),
+        os: Set(Some(client.os.to_string())),
+        ..Default::default()
+    })
+    .exec(db)
+    .await?;
+    Ok(true)
+}
+
+/// Id of the last `blog_views` row counted into `blog_view_daily`.
+pub async fn rollup_watermark<C>(db: &C) -> Result<i32, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let row = db
+        .query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT last_view_id FROM blog_view_rollup_state WHERE id = 1",
+        ))
+        .await?;
+    match row {
+        Some(row) => row.try_get("", "last_view_id"),
+        None => Ok(0),
+    }
+}
+
+/// Adds views recorded since the last run to `blog_view_daily`,
+/// `blog_view_dimension_daily` and `blogs.views_count`, and returns how many
+/// were added. Each raw row is
+/// counted exactly once, however often this runs.
+pub async fn roll_up_views(db: &DatabaseConnection) -> Result<u64, DbErr> {
+    let txn = db.begin().await?;
+    let from = rollup_watermark(&txn).await?;
+    let to: i32 = txn
+        .query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT COALESCE(MAX(id), 0) AS id FROM blog_views",
+        ))
+        .await?
+        .map(|row| row.try_get("", "id"))
+        .transpose()?
+        .unwrap_or(0);
+    if to <= from {
+        return Ok(0);
+    }
+
+    let range =
+        |sql: &str| Statement::from_sql_and_values(DbBackend::Sqlite, sql, [from.into(), to.into()]);
+    txn.execute(range(
+        "INSERT INTO blog_view_daily (post_id, day, views)
+         SELECT post_id, substr(timestamp, 1, 10), COUNT(*)
+         FROM blog_views WHERE id > ?1 AND id <= ?2
+         GROUP BY post_id, substr(timestamp, 1, 10)
+         ON CONFLICT (post_id, day) DO UPDATE SET views = views + excluded.views",
+    ))
+    .await?;
+    // Views recorded before sources were captured have no `device` and stay out of breakdowns
+    for dimension in ViewDimension::ALL {
+        let value = format!("COALESCE({}, '{}')", dimension.column(), dimension.missing_label());
+        txn.execute(range(&format!(
+            "INSERT INTO blog_view_dimension_daily (post_id, day, dimension, value, views)
+             SELECT post_id, substr(timestamp, 1, 10), '{dimension}', {value}, COUNT(*)
+             FROM blog_views WHERE id > ?1 AND id <= ?2 AND device IS NOT NULL
+             GROUP BY post_id, substr(timestamp, 1, 10), {value}
+             ON CONFLICT (post_id, day, dimension, value) DO UPDATE SET views = views + excluded.views",
+        )))
+        .await?;
+    }
+    txn.execute(range(
+        "UPDATE blogs SET views_count = views_count + (
+             SELECT COUNT(*) FROM blog_views v WHERE v.post_id = blogs.id AND v.id > ?1 AND v.id <= ?2
+         )
+         WHERE id IN (SELECT post_id FROM blog_views WHERE id > ?1 AND id <= ?2)",
+    ))
+    .await?;
+    let added: i64 = txn
+        .query_one(range("SELECT COUNT(*) AS n FROM blog_views WHERE id > ?1 AND id <= ?2"))
+        .await?
+        .map(|row| row.try_get("", "n"))
+        .transpose()?
+        .unwrap_or(0);
+    txn.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO blog_view_rollup_state (id, last_view_id) VALUES (1, ?1)
+         ON CONFLICT (id) DO UPDATE SET last_view_id = excluded.last_view_id",
+        [to.into()],
+    ))
+    .await?;
+    txn.commit().await?;
+    Ok(added as u64)
+}
+
+/// Runs `roll_up_views` every `period` (`Config::analytics_rollup_interval_secs`).
+pub fn spawn_rollup(db: DatabaseConnection, period: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(e) = roll_up_views(&db).await {
+                error!("View rollup failed: {}", e);
+            }
+        }
+    })
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RetentionReport {
+    pub salts_deleted: u64,
+    pub views_deleted: u64,
+}
+
+/// Drops salts from previous days once `dedup_window` has moved past them,
+/// making their hashes unlinkable, and rolled-up view rows older than
+/// `retention_days` when set. Rows are only purged once rolled up, so counts
+/// are unaffected.
+pub async fn apply_retention(
+    db: &DatabaseConnection,
+    dedup_window: ChronoDuration,
+    retention_days: Option<i64>,
+) -> Result<RetentionReport, DbErr> {
+    let now = Utc::now();
+    let salts = view_salts::Entity::delete_many()
+        .filter(view_salts::Column::Day.lt((now - dedup_window).date_naive()))
+        .exec(db)
+        .await?;
+
+    let views_deleted = match retention_days {
+        Some(days) => {
+            blog_views::Entity::delete_many()
+                .filter(blog_views::Column::Timestamp.lt(now - ChronoDuration::days(days)))
+                .filter(blog_views::Column::Id.lte(rollup_watermark(db).await?))
+                .exec(db)
+                .await?
+                .rows_affected
+        }
+        None => 0,
+    };
+
+    Ok(RetentionReport {
+        salts_deleted: salts.rows_affected,
+        views_deleted,
+    })
+}
+
+/// Runs `apply_retention` hourly, starting immediately, with
+/// `Config::view_dedup_window_minutes` and `Config::view_retention_days`.
+pub fn spawn_retention(
+    db: DatabaseConnection,
+    dedup_window: ChronoDuration,
+    retention_days: Option<i64>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(RETENTION_INTERVAL_SECS));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match apply_retention(&db, dedup_window, retention_days).await {
+                Ok(report) if report == RetentionReport::default() => {}
+                Ok(report) => info!(
+                    "View retention removed {} salt(s) and {} view(s)",
+                    report.salts_deleted, report.views_deleted
+                ),
+                Err(e) => error!("View retention failed: {}", e),
+            }
+        }
+    })
+}
@@ -0,0 +1,307 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{Duration, NaiveDate, Utc};
+use sea_orm::{
+    sea_query::{Alias, Expr},
+    ColumnTrait, Condition, EntityTrait, Order, QueryFilter, QueryOrder, QuerySelect, QueryTrait,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use utoipa::IntoParams;
+use super::{blogs::find_blog, ApiError, ApiResult, ErrorResponse};
+use crate::{
+    analytics::ViewDimension,
+    auth::{roles::forbidden, AuthUser, Role},
+    entities::{blog_view_daily, blog_view_dimension_daily, blogs},
+    AppState,
+};
+
+pub use shared::analytics::{
+    BreakdownItem, BreakdownResponse, Granularity, PostAnalytics, SeriesPoint, TopPost,
+    TopPostsResponse,
+};
+
+const DEFAULT_RANGE_DAYS: i64 = 30;
+/// Longest range a single request may cover, about five years.
+const MAX_RANGE_DAYS: i64 = 1830;
+
+/// Sums daily counts into one point per period from `from` to `to`, including
+/// periods without views so charts need no gap handling.
+pub fn bucket_series(
+    daily: &[(NaiveDate, i64)],
+    from: NaiveDate,
+    to: NaiveDate,
+    granularity: Granularity,
+) -> Vec<SeriesPoint> {
+    let mut totals: HashMap<NaiveDate, i64> = HashMap::new();
+    for (day, views) in daily {
+        if (from..=to).contains(day) {
+            *totals.entry(granularity.period_start(*day)).or_default() += views;
+        }
+    }
+
+    let mut series = Vec::new();
+    let mut period = granularity.period_start(from);
+    while period <= to {
+        series.push(SeriesPoint {
+            period,
+            views: totals.get(&period).copied().unwrap_or(0),
+        });
+        period = granularity.next_period(period);
+    }
+    series
+}
+
+/// Inclusive UTC date range, defaulting to the last 30 days.
+fn resolve_range(from: Option<NaiveDate>, to: Option<NaiveDate>) -> ApiResult<(NaiveDate, NaiveDate)> {
+    let to = to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = from.unwrap_or(to - Duration::days(DEFAULT_RANGE_DAYS - 1));
+    if from > to {
+        return Err(ApiError::bad_request("`from` must not be after `to`"));
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        return Err(ApiError::bad_request("Date range is too long"));
+    }
+    Ok((from, to))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PostAnalyticsParams {
+    /// `YYYY-MM-DD`, inclusive
+    pub from: Option<NaiveDate>,
+    /// `YYYY-MM-DD`, inclusive; defaults to today (UTC)
+    pub to: Option<NaiveDate>,
+    /// `day` (default), `week` or `month`
+    pub granularity: Option<String>,
+}
+
+/// Views of one post over time, from the daily rollup. Available to whoever
+/// may edit the post.
+#[utoipa::path(
+    get,
+    path = "/api/analytics/posts/{id}",
+    tag = "analytics",
+    params(("id" = i32, Path, description = "Post id"), PostAnalyticsParams),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "View series for the post", body = PostAnalytics),
+        (status = 400, description = "Invalid range or granularity", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed to edit the post", body = ErrorResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+    )
+)]
+pub async fn post_analytics(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i32>,
+    Query(params): Query<PostAnalyticsParams>,
+) -> ApiResult<Json<PostAnalytics>> {
+    let granularity = match params.granularity.as_deref() {
+        None | Some("") => Granularity::Day,
+        Some(g) => g
+            .parse()
+            .map_err(|_| ApiError::bad_request("Unknown granularity"))?,
+    };
+    let (from, to) = resolve_range(params.from, params.to)?;
+    let blog = find_blog(&state, id).await?;
+    if !auth_user.can_edit_owned_by(blog.author_id) {
+        return Err(forbidden());
+    }
+
+    let daily: Vec<(NaiveDate, i64)> = blog_view_daily::Entity::find()
+        .select_only()
+        .column(blog_view_daily::Column::Day)
+        .column(blog_view_daily::Column::Views)
+        .filter(blog_view_daily::Column::PostId.eq(blog.id))
+        .filter(blog_view_daily::Column::Day.between(from, to))
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+
+    let series = bucket_series(&daily, from, to, granularity);
+    Ok(Json(PostAnalytics {
+        post_id: blog.id,
+        title: blog.title,
+        slug: blog.slug,
+        granularity,
+        from,
+        to,
+        total: series.iter().map(|p| p.views).sum(),
+        series,
+    }))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TopPostsParams {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// 1 to 50, default 10
+    pub limit: Option<u64>,
+}
+
+/// Most viewed posts within the range, for authors and above. Authors see
+/// published posts and their own; editors and admins see every post.
+#[utoipa::path(
+    get,
+    path = "/api/analytics/posts/top",
+    tag = "analytics",
+    params(TopPostsParams),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Most viewed posts", body = TopPostsResponse),
+        (status = 400, description = "Invalid range", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Needs `author` or above", body = ErrorResponse),
+    )
+)]
+pub async fn top_posts(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(params): Query<TopPostsParams>,
+) -> ApiResult<Json<TopPostsResponse>> {
+    auth_user.require(Role::Author)?;
+    let (from, to) = resolve_range(params.from, params.to)?;
+    let limit = params.limit.unwrap_or(10).clamp(1, 50);
+
+    let mut query = blog_view_daily::Entity::find()
+        .filter(blog_view_daily::Column::Day.between(from, to));
+    if auth_user.role < Role::Editor {
+        let visible = blogs::Entity::find()
+            .select_only()
+            .column(blogs::Column::Id)
+            .filter(
+                Condition::any()
+                    .add(blogs::live())
+                    .add(blogs::Column::AuthorId.eq(auth_user.id)),
+            )
+            .into_query();
+        query = query.filter(blog_view_daily::Column::PostId.in_subquery(visible));
+    }
+
+    let ranked: Vec<(i32, i64)> = query
+        .select_only()
+        .column(blog_view_daily::Column::PostId)
+        .column_as(blog_view_daily::Column::Views.sum(), "views")
+        .group_by(blog_view_daily::Column::PostId)
+        .order_by(Expr::col(Alias::new("views")), Order::Desc)
+        .order_by_asc(blog_view_daily::Column::PostId)
+        .limit(limit)
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+
+    let posts: HashMap<i32, blogs::Model> = blogs::Entity::find()
+        .filter(blogs::Column::Id.is_in(ranked.iter().map(|(id, _)| *id)))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|post| (post.id, post))
+        .collect();
+
+    let items = ranked
+        .into_iter()
+        .filter_map(|(id, views)| {
+            posts.get(&id).map(|post| TopPost {
+                id,
+                title: post.title.clone(),
+                slug: post.slug.clone(),
+                views,
+            })
+        })
+        .collect();
+
+    Ok(Json(TopPostsResponse { from, to, items }))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BreakdownParams {
+    /// `referrer`, `utm_source`, `utm_medium`, `utm_campaign`, `device`, `browser` or `os`
+    pub dimension: Option<String>,
+    /// Limit to one post; site-wide otherwise
+    pub post_id: Option<i32>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// 1 to 50, default 10
+    pub limit: Option<u64>,
+}
+
+/// Views grouped by where they came from or the client used, most common
+/// first. A single post's breakdown is available to whoever may edit it; the
+/// site-wide one to authors and above.
+#[utoipa::path(
+    get,
+    path = "/api/analytics/breakdown",
+    tag = "analytics",
+    params(BreakdownParams),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Views per value of the dimension", body = BreakdownResponse),
+        (status = 400, description = "Missing or unknown dimension, or invalid range", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed to see these views", body = ErrorResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+    )
+)]
+pub async fn view_breakdown(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(params): Query<BreakdownParams>,
+) -> ApiResult<Json<BreakdownResponse>> {
+    let dimension: ViewDimension = params
+        .dimension
+        .as_deref()
+        .ok_or_else(|| ApiError::bad_request("`dimension` is required"))?
+        .parse()
+        .map_err(|_| ApiError::bad_request("Unknown dimension"))?;
+    let (from, to) = resolve_range(params.from, params.to)?;
+    let limit = params.limit.unwrap_or(10).clamp(1, 50);
+
+    let mut query = blog_view_dimension_daily::Entity::find()
+        .filter(blog_view_dimension_daily::Column::Dimension.eq(dimension.as_str()))
+        .filter(blog_view_dimension_daily::Column::Day.between(from, to));
+    match params.post_id {
+        Some(id) => {
+            let blog = find_blog(&state, id).await?;
+            if !auth_user.can_edit_owned_by(blog.author_id) {
+                return Err(forbidden());
+            }
+            query = query.filter(blog_view_dimension_daily::Column::PostId.eq(blog.id));
+        }
+        None => auth_user.require(Role::Author)?,
+    }
+
+    let total: Option<i64> = query
+        .clone()
+        .select_only()
+        .column_as(blog_view_dimension_daily::Column::Views.sum(), "views")
+        .into_tuple()
+        .one(&state.db)
+        .await?
+        .flatten();
+    let items: Vec<(String, i64)> = query
+        .select_only()
+        .column(blog_view_dimension_daily::Column::Value)
+        .column_as(blog_view_dimension_daily::Column::Views.sum(), "views")
+        .group_by(blog_view_dimension_daily::Column::Value)
+        .order_by(Expr::col(Alias::new("views")), Order::Desc)
+        .order_by_asc(blog_view_dimension_daily::Column::Value)
+        .limit(limit)
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(BreakdownResponse {
+        dimension: dimension.to_string(),
+        post_id: params.post_id,
+        from,
+        to,
+        total: total.unwrap_or(0),
+        items: items
+            .into_iter()
+            .map(|(value, views)| BreakdownItem { value, views })
+            .collect(),
+    }))
+}
@@ -1,110 +1,245 @@
-use axum::{extract::State, http::{StatusCode, HeaderMap}, Json};
-use sea_orm::{EntityTrait, ColumnTrait, QueryFilter, ActiveModelTrait, Set};
-use serde_json::{json, Value};
+use axum::{extract::State, http::StatusCode, Json};
+use sea_orm::{EntityTrait, ColumnTrait, QueryFilter, ActiveModelTrait, Set, SqlErr};
+use validator::Validate;
+use super::{ApiError, ApiResult, ErrorResponse};
 use crate::{
-    auth::{LoginRequest, LoginResponse, UserResponse, create_jwt},
+    auth::{
+        AuthUser, ChangePasswordRequest, LoginRequest, LoginResponse, RefreshRequest,
+        RegisterRequest, Role, UserResponse, hash_password, sessions, verify_password,
+    },
     entities::{users, Users},
     AppState,
 };
 
+fn user_response(user: users::Model) -> UserResponse {
+    UserResponse {
+        role: Role::from_db(&user.role),
+        id: user.id,
+        email: user.email,
+        name: user.name,
+    }
+}
+
+fn login_response(tokens: sessions::Tokens, user: users::Model) -> LoginResponse {
+    LoginResponse {
+        token: tokens.access_token,
+        expires_in: tokens.expires_in,
+        refresh_token: tokens.refresh_token,
+        user: user_response(user),
+    }
+}
+
+/// Starts a new session for `user`.
+async fn sign_in(state: &AppState, user: users::Model) -> ApiResult<LoginResponse> {
+    let tokens = sessions::start(&state.db, &user, &state.config).await?;
+    Ok(login_response(tokens, user))
+}
+
+fn hash_error(_: bcrypt::BcryptError) -> ApiError {
+    ApiError::internal("Failed to hash password")
+}
+
+/// The account matching `ADMIN_EMAIL` becomes an admin; others start as
+/// readers.
+#[utoipa::path(
+    post,
+    path = "/api/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "Account created and signed in", body = LoginResponse),
+        (status = 409, description = "Email already registered", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse),
+    )
+)]
+pub async fn register(
+    State(state): State<AppState>,
+    Json(request): Json<RegisterRequest>,
+) -> ApiResult<(StatusCode, Json<LoginResponse>)> {
+    request.validate()?;
+
+    let existing = Users::find()
+        .filter(users::Column::Email.eq(&request.email))
+        .one(&state.db)
+        .await?;
+    if existing.is_some() {
+        return Err(ApiError::conflict("Email already registered"));
+    }
+
+    let role = if state.config.admin_email.as_deref() == Some(request.email.as_str()) {
+        Role::Admin
+    } else {
+        Role::Reader
+    };
+    let password_hash = hash_password(&request.password).await.map_err(hash_error)?;
+    let now = chrono::Utc::now();
+    let user = users::ActiveModel {
+        email: Set(request.email),
+        name: Set(request.name),
+        password_hash: Set(Some(password_hash)),
+        role: Set(role.to_string()),
+        created_at: Set(Some(now)),
+        updated_at: Set(Some(now)),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await
+    .map_err(|e| match e.sql_err() {
+        // Lost a race with another registration for the same email
+        Some(SqlErr::UniqueConstraintViolation(_)) => ApiError::conflict("Email already registered"),
+        _ => e.into(),
+    })?;
+
+    Ok((StatusCode::CREATED, Json(sign_in(&state, user).await?)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Signed in", body = LoginResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+    )
+)]
 pub async fn user_login(
     State(state): State<AppState>,
     Json(login_request): Json<LoginRequest>,
-) -> Result<Json<LoginResponse>, (StatusCode, Json<Value>)> {
-    // Demo login via environment-configured credentials
-    let expected_email = std::env::var("DEMO_LOGIN_EMAIL").unwrap_or_default();
-    let expected_password = std::env::var("DEMO_LOGIN_PASSWORD").unwrap_or_default();
-    if !expected_email.is_empty()
-        && !expected_password.is_empty()
-        && login_request.email == expected_email
-        && login_request.password == expected_password
-    {
-        // Try to find existing user, or create demo user
-        let user = Users::find()
-            .filter(users::Column::Email.eq(&login_request.email))
-            .one(&state.db)
-            .await
-            .map_err(|_| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Database error"})),
-                )
-            })?;
-
-        let user = match user {
-            Some(user) => user,
-            None => {
-                // Create demo user if it doesn't exist
-                let new_user = users::ActiveModel {
-                    email: Set(login_request.email.clone()),
-                    name: Set("Demo User".to_string()),
-                    ..Default::default()
-                };
-
-                new_user.insert(&state.db)
-                    .await
-                    .map_err(|_| {
-                        (
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            Json(json!({"error": "Failed to create user"})),
-                        )
-                    })?
-            }
-        };
-
-        let token = create_jwt(&user.id.to_string()).map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to create token"})),
-            )
-        })?;
-
-        Ok(Json(LoginResponse {
-            token,
-            user: UserResponse {
-                id: user.id,
-                email: user.email,
-                name: user.name,
-            },
-        }))
-    } else {
-        Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid credentials"})),
-        ))
+) -> ApiResult<Json<LoginResponse>> {
+    let user = Users::find()
+        .filter(users::Column::Email.eq(&login_request.email))
+        .one(&state.db)
+        .await?;
+
+    // Unknown emails and accounts without a password hash (created before
+    // registration existed) are checked against a dummy hash, so they fail
+    // as slowly as a wrong password
+    let hash = user.as_ref().and_then(|user| user.password_hash.as_deref());
+    if !verify_password(&login_request.password, hash).await {
+        return Err(ApiError::unauthorized("Invalid credentials"));
     }
+    let user = user.ok_or(ApiError::unauthorized("Invalid credentials"))?;
+
+    Ok(Json(sign_in(&state, user).await?))
 }
 
-pub async fn current_user(
+/// Rotates the session: the refresh token is spent and a new pair returned.
+/// Presenting a spent refresh token revokes its session.
+#[utoipa::path(
+    post,
+    path = "/api/auth/refresh",
+    tag = "auth",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "New access and refresh tokens", body = LoginResponse),
+        (status = 401, description = "Refresh token invalid, expired or already used", body = ErrorResponse),
+    )
+)]
+pub async fn refresh_session(
     State(state): State<AppState>,
-    headers: HeaderMap,
-) -> Result<Json<UserResponse>, (StatusCode, Json<Value>)> {
-    let claims = crate::auth::check_user_auth(&headers)
-        .map_err(|(status, msg)| (status, Json(json!({"error": msg}))))?;
-    let user_id: i32 = claims.sub.parse().map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": "Invalid user ID"})),
-        )
-    })?;
+    Json(request): Json<RefreshRequest>,
+) -> ApiResult<Json<LoginResponse>> {
+    let (tokens, user) = sessions::refresh(&state.db, &request.refresh_token, &state.config).await?;
+    Ok(Json(login_response(tokens, user)))
+}
+
+/// Ends the session of a refresh token; unknown or already exchanged tokens are ignored.
+#[utoipa::path(
+    post,
+    path = "/api/auth/logout",
+    tag = "auth",
+    request_body = RefreshRequest,
+    responses(
+        (status = 204, description = "Signed out"),
+    )
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    Json(request): Json<RefreshRequest>,
+) -> ApiResult<StatusCode> {
+    sessions::end(&state.db, &request.refresh_token).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Ends every session of the caller, including the current one.
+#[utoipa::path(
+    post,
+    path = "/api/auth/logout-all",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Signed out on all devices"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    )
+)]
+pub async fn logout_all(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> ApiResult<StatusCode> {
+    sessions::end_all(&state.db, auth_user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Ends every session of the user, since one may belong to whoever learned
+/// the old password, and signs the caller in again.
+#[utoipa::path(
+    put,
+    path = "/api/user/password",
+    tag = "auth",
+    request_body = ChangePasswordRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Password changed; tokens for a new session", body = LoginResponse),
+        (status = 401, description = "Missing or invalid token, or wrong current password", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse),
+    )
+)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(request): Json<ChangePasswordRequest>,
+) -> ApiResult<Json<LoginResponse>> {
+    request.validate()?;
 
-    let user = Users::find_by_id(user_id)
+    let user = Users::find_by_id(auth_user.id)
         .one(&state.db)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-        })?
-        .ok_or((
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "User not found"})),
-        ))?;
-
-    Ok(Json(UserResponse {
-        id: user.id,
-        email: user.email,
-        name: user.name,
-    }))
+        .await?
+        .ok_or(ApiError::not_found("User not found"))?;
+
+    if !verify_password(&request.current_password, user.password_hash.as_deref()).await {
+        return Err(ApiError::unauthorized("Current password is incorrect"));
+    }
+
+    let password_hash = hash_password(&request.new_password).await.map_err(hash_error)?;
+    let mut user: users::ActiveModel = user.into();
+    user.password_hash = Set(Some(password_hash));
+    user.updated_at = Set(Some(chrono::Utc::now()));
+    let user = user.update(&state.db).await?;
+
+    sessions::end_all(&state.db, user.id).await?;
+    Ok(Json(sign_in(&state, user).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/user/current",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The signed-in user", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "User no longer exists", body = ErrorResponse),
+    )
+)]
+pub async fn current_user(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> ApiResult<Json<UserResponse>> {
+    let user = Users::find_by_id(auth_user.id)
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::not_found("User not found"))?;
+
+    Ok(Json(user_response(user)))
 }
@@ -1,157 +1,750 @@
 use axum::{
-    extract::{Path, Query, State, ConnectInfo},
-    response::IntoResponse,
+    extract::{Path, Query, RawQuery, State, ConnectInfo},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
-use crate::{entities::{blogs, blog_views}, AppState};
+use sea_orm::{
+    sea_query::{Expr, Query as SelectQuery},
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, LoaderTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, Select, Set, TransactionTrait,
+};
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError};
+use super::{
+    client_ip, request_origin, ApiError, ApiResult, ErrorResponse,
+    revisions::{attribute_revisions, revision_watermark},
+    tags::{set_blog_tags, tags_for_blog, TagResponse},
+};
+use crate::{
+    analytics,
+    auth::{preview, roles::forbidden, AuthUser, Role},
+    entities::{blogs::{self, BlogStatus}, blog_slug_history, blog_tags, tags},
+    markdown,
+    slug::{is_reserved, slugify},
+    AppState,
+};
 use std::net::SocketAddr;
 
-#[derive(Debug, Deserialize)]
+pub use shared::blogs::{BlogDetailResponse, BlogListItem, BlogsListResponse};
+
+#[derive(Debug, Deserialize, IntoParams)]
 pub struct ListParams {
     pub page: Option<u64>,
     pub per_page: Option<u64>,
     pub status: Option<String>,
+    /// Tag slug
+    pub tag: Option<String>,
+    /// Category slug
+    pub category: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct BlogListItem {
-    pub id: i32,
-    pub slug: String,
-    pub title: String,
-    pub excerpt: Option<String>,
-    pub status: Option<String>,
-    pub published_at: Option<sea_orm::prelude::DateTimeUtc>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct BlogsListResponse {
-    pub items: Vec<BlogListItem>,
-    pub page: u64,
-    pub per_page: u64,
-    pub total: u64,
-    pub total_pages: u64,
-}
-
+/// Published posts for everyone. Other statuses (or `status=` for all) need
+/// at least `author`, and authors only see their own unpublished posts.
+#[utoipa::path(
+    get,
+    path = "/api/blogs",
+    tag = "blogs",
+    params(ListParams),
+    security((), ("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "One page of posts", body = BlogsListResponse),
+        (status = 400, description = "Unknown status", body = ErrorResponse),
+        (status = 403, description = "Unpublished posts need `author` or above", body = ErrorResponse),
+    )
+)]
 pub async fn list_blogs(
     State(state): State<AppState>,
+    auth_user: Option<AuthUser>,
     Query(params): Query<ListParams>,
-) -> impl IntoResponse {
+) -> ApiResult<Json<BlogsListResponse>> {
     let page = params.page.unwrap_or(1).max(1);
     let per_page = params.per_page.unwrap_or(10).clamp(1, 100);
-    let status = params.status.unwrap_or_else(|| "published".to_string());
+    let status = match params.status.as_deref() {
+        None => Some(BlogStatus::Published),
+        Some("") => None,
+        Some(status) => Some(
+            status
+                .parse::<BlogStatus>()
+                .map_err(|_| ApiError::bad_request("Unknown status"))?,
+        ),
+    };
+
+    let mut query = filtered_blogs(status, params.tag.as_deref(), params.category.as_deref());
+    if status != Some(BlogStatus::Published) {
+        let user = auth_user.ok_or_else(forbidden)?;
+        user.require(Role::Author)?;
+        if user.role < Role::Editor {
+            query = query.filter(
+                Condition::any()
+                    .add(blogs::live())
+                    .add(blogs::Column::AuthorId.eq(user.id)),
+            );
+        }
+    }
+
+    let paginator = query.paginate(&state.db, per_page);
+
+    let total = paginator.num_items().await?;
+    let total_pages = paginator.num_pages().await?;
 
+    let page_idx = page - 1; // SeaORM paginator is 0-based
+    let models = paginator.fetch_page(page_idx).await?;
+    let items = list_items(&state, models).await?;
+
+    Ok(Json(BlogsListResponse {
+        items,
+        page,
+        per_page,
+        total,
+        total_pages,
+    }))
+}
+
+/// Posts matching the list filters, newest first. `None` matches every status,
+/// and `Published` only matches posts that are already due; empty
+/// `tag`/`category` values are ignored.
+pub(crate) fn filtered_blogs(
+    status: Option<BlogStatus>,
+    tag: Option<&str>,
+    category: Option<&str>,
+) -> Select<blogs::Entity> {
     let mut query = blogs::Entity::find()
         .filter(blogs::Column::Slug.is_not_null())
         .order_by_desc(blogs::Column::PublishedAt)
         .order_by_desc(blogs::Column::CreatedAt);
 
-    // Filter by status if provided (defaults to published)
-    if !status.is_empty() {
-        query = query.filter(blogs::Column::Status.eq(status));
+    match status {
+        Some(BlogStatus::Published) => query = query.filter(blogs::live()),
+        Some(status) => query = query.filter(blogs::Column::Status.eq(status.as_str())),
+        None => {}
     }
 
-    let paginator = query.paginate(&state.db, per_page);
+    if let Some(category) = category.filter(|c| !c.is_empty()) {
+        query = query.filter(blogs::Column::Category.eq(category));
+    }
 
-    let total = match paginator.num_items().await {
-        Ok(t) => t as u64,
-        Err(_) => 0,
-    };
-    let total_pages = paginator.num_pages().await.unwrap_or(0) as u64;
+    if let Some(tag) = tag.filter(|t| !t.is_empty()) {
+        let tagged = SelectQuery::select()
+            .column((blog_tags::Entity, blog_tags::Column::BlogId))
+            .from(blog_tags::Entity)
+            .inner_join(
+                tags::Entity,
+                Expr::col((tags::Entity, tags::Column::Id))
+                    .equals((blog_tags::Entity, blog_tags::Column::TagId)),
+            )
+            .and_where(Expr::col((tags::Entity, tags::Column::Slug)).eq(tag))
+            .to_owned();
+        query = query.filter(blogs::Column::Id.in_subquery(tagged));
+    }
 
-    let page_idx = page - 1; // SeaORM paginator is 0-based
-    let models = paginator.fetch_page(page_idx).await.unwrap_or_default();
+    query
+}
+
+/// Builds list items for `models`, batch-loading their tags.
+pub(crate) async fn list_items(
+    state: &AppState,
+    models: Vec<blogs::Model>,
+) -> ApiResult<Vec<BlogListItem>> {
+    let tags = models
+        .load_many_to_many(tags::Entity, blog_tags::Entity, &state.db)
+        .await?;
 
-    let items = models
+    Ok(models
         .into_iter()
-        .map(|m| BlogListItem {
+        .zip(tags)
+        .map(|(m, tags)| BlogListItem {
             id: m.id,
             slug: m.slug,
             title: m.title,
             excerpt: m.excerpt,
             status: m.status,
+            category: m.category,
+            tags: tags.into_iter().map(TagResponse::from).collect(),
             published_at: m.published_at,
         })
-        .collect::<Vec<_>>();
+        .collect())
+}
 
-    Json(BlogsListResponse {
-        items,
-        page,
-        per_page,
-        total,
-        total_pages,
-    })
+fn blog_detail(m: blogs::Model, tags: Vec<TagResponse>) -> BlogDetailResponse {
+    let content_html = m
+        .content_html
+        .unwrap_or_else(|| markdown::render(&m.content));
+    BlogDetailResponse {
+        id: m.id,
+        author_id: m.author_id,
+        title: m.title,
+        slug: m.slug,
+        excerpt: m.excerpt,
+        content: m.content,
+        content_html,
+        status: m.status,
+        category: m.category,
+        tags,
+        published_at: m.published_at,
+        created_at: m.created_at,
+        updated_at: m.updated_at,
+        views_count: m.views_count.max(0) as u64,
+    }
 }
 
-#[derive(Debug, Serialize)]
-pub struct BlogDetailResponse {
-    pub id: i32,
-    pub author_id: i32,
-    pub title: String,
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BlogQuery {
+    /// Signed preview token, see `create_preview_link`
+    pub preview: Option<String>,
+    /// `document.referrer` of the page showing the post. Single-page frontends
+    /// pass it on since the request's own `Referer` is the frontend itself.
+    pub referrer: Option<String>,
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+}
+
+/// Body of the `301` sent for a slug the post no longer uses.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SlugRedirect {
+    /// Current slug of the post
     pub slug: String,
-    pub excerpt: Option<String>,
-    pub content: String,
-    pub status: Option<String>,
-    pub published_at: Option<sea_orm::prelude::DateTimeUtc>,
-    pub created_at: Option<sea_orm::prelude::DateTimeUtc>,
-    pub updated_at: Option<sea_orm::prelude::DateTimeUtc>,
-    pub views_count: u64,
+    pub location: String,
 }
 
+/// Published posts only. A valid preview token shows the post it was issued
+/// for whatever its status, when requested at that post's slug, without
+/// recording a view. Old slugs answer with a `301` pointing at the current one.
+#[utoipa::path(
+    get,
+    path = "/api/blogs/{slug}",
+    tag = "blogs",
+    params(("slug" = String, Path, description = "Current or former slug"), BlogQuery),
+    responses(
+        (status = 200, description = "The post", body = BlogDetailResponse),
+        (status = 301, description = "The slug has changed", body = SlugRedirect),
+        (status = 403, description = "Preview token invalid, expired or for another post", body = ErrorResponse),
+        (status = 404, description = "No published post with this slug", body = ErrorResponse),
+    )
+)]
 pub async fn get_blog_by_slug(
     State(state): State<AppState>,
     Path(slug): Path<String>,
+    Query(query): Query<BlogQuery>,
+    RawQuery(raw_query): RawQuery,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
-) -> impl IntoResponse {
-    match blogs::Entity::find()
-        .filter(blogs::Column::Slug.eq(slug))
-        .one(&state.db)
-        .await
-    {
-        Ok(Some(m)) => {
-            // Record a view (best-effort)
-            let ip = addr.ip().to_string();
-            let view = blog_views::ActiveModel {
-                post_id: sea_orm::ActiveValue::set(m.id),
-                ip_address: sea_orm::ActiveValue::set(ip),
-                timestamp: sea_orm::ActiveValue::set(chrono::Utc::now()),
-                ..Default::default()
-            };
-            let _ = blog_views::Entity::insert(view).exec(&state.db).await;
-
-            // Count total views
-            let views_count = blog_views::Entity::find()
-                .filter(blog_views::Column::PostId.eq(m.id))
-                .count(&state.db)
-                .await
-                .unwrap_or(0);
-
-            let resp = BlogDetailResponse {
-                id: m.id,
-                author_id: m.author_id,
-                title: m.title,
-                slug: m.slug,
-                excerpt: m.excerpt,
-                content: m.content,
-                status: m.status,
-                published_at: m.published_at,
-                created_at: m.created_at,
-                updated_at: m.updated_at,
-                views_count,
-            };
-            Json(resp).into_response()
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    if let Some(token) = query.preview {
+        let invalid = || ApiError::forbidden("Preview link is invalid or has expired");
+        let id = preview::verify_preview_token(&token, &state.config).map_err(|_| invalid())?;
+        let blog = find_blog(&state, id).await?;
+        // A token only opens the post it was issued for, at that post's address
+        if blog.slug != slug {
+            return Err(invalid());
         }
-        Ok(None) => (
-            axum::http::StatusCode::NOT_FOUND,
-            Json(json!({ "error": "Blog not found" })),
-        )
-            .into_response(),
-        Err(_) => (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": "Internal server error" })),
+        return Ok(Json(detail_response(&state, blog).await?).into_response());
+    }
+
+    let not_found = || ApiError::not_found("Blog not found");
+    let found = blogs::Entity::find()
+        .filter(blogs::Column::Slug.eq(&slug))
+        .filter(blogs::live())
+        .one(&state.db)
+        .await?;
+    let Some(m) = found else {
+        let moved = blog_slug_history::Entity::find()
+            .filter(blog_slug_history::Column::Slug.eq(&slug))
+            .find_also_related(blogs::Entity)
+            .filter(blogs::live())
+            .one(&state.db)
+            .await?
+            .and_then(|(_, blog)| blog)
+            .ok_or_else(not_found)?;
+        // Keep `referrer` and `utm_*` so the view is credited after the redirect
+        let location = match raw_query {
+            Some(raw_query) => format!("/api/blogs/{}?{}", moved.slug, raw_query),
+            None => format!("/api/blogs/{}", moved.slug),
+        };
+        return Ok((
+            StatusCode::MOVED_PERMANENTLY,
+            [(header::LOCATION, location.clone())],
+            Json(SlugRedirect {
+                slug: moved.slug,
+                location,
+            }),
         )
-            .into_response(),
+            .into_response());
+    };
+
+    // Record a view (best-effort); bots and repeat visits are skipped
+    let header_value = |name: header::HeaderName| headers.get(name).and_then(|v| v.to_str().ok());
+    let own_hosts: Vec<String> = [state.config.site_url.clone(), request_origin(&headers, &state.config)]
+        .iter()
+        .filter_map(|url| analytics::referrer_host(url))
+        .collect();
+    let source = analytics::ViewSource::new(
+        query.referrer.as_deref().or(header_value(header::REFERER)),
+        query.utm_source.as_deref(),
+        query.utm_medium.as_deref(),
+        query.utm_campaign.as_deref(),
+        &own_hosts,
+    );
+    let _ = analytics::record_view(
+        &state.db,
+        chrono::Duration::minutes(state.config.view_dedup_window_minutes),
+        m.id,
+        client_ip(&headers, addr, &state.config),
+        header_value(header::USER_AGENT),
+        &source,
+    )
+    .await;
+
+    Ok(Json(detail_response(&state, m).await?).into_response())
+}
+
+#[derive(Debug, Default, Deserialize, Validate, ToSchema)]
+pub struct CreatePreviewRequest {
+    /// Defaults to 24 hours
+    #[validate(range(min = 1, max = 168))]
+    pub expires_in_hours: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PreviewLinkResponse {
+    pub token: String,
+    /// Frontend page that renders the preview
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Issues a signed, expiring link that lets anyone holding it read the post.
+#[utoipa::path(
+    post,
+    path = "/api/blogs/{slug}/preview",
+    tag = "blogs",
+    params(("slug" = i32, Path, description = "Post id")),
+    request_body(content = Option<CreatePreviewRequest>),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Preview link created", body = PreviewLinkResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed to edit the post", body = ErrorResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse),
+    )
+)]
+pub async fn create_preview_link(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i32>,
+    payload: Option<Json<CreatePreviewRequest>>,
+) -> ApiResult<(StatusCode, Json<PreviewLinkResponse>)> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    payload.validate()?;
+    let blog = find_blog(&state, id).await?;
+    if !auth_user.can_edit_owned_by(blog.author_id) {
+        return Err(forbidden());
+    }
+
+    let expires_at =
+        chrono::Utc::now() + chrono::Duration::hours(payload.expires_in_hours.unwrap_or(24));
+    let token = preview::create_preview_token(blog.id, expires_at, &state.config)
+        .map_err(|_| ApiError::internal("Failed to create preview link"))?;
+    let url = format!("{}/blogs/{}?preview={}", state.config.site_url, blog.slug, token);
+
+    Ok((
+        StatusCode::CREATED,
+        Json(PreviewLinkResponse {
+            token,
+            url,
+            expires_at,
+        }),
+    ))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateBlogRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub title: String,
+    /// Generated from the title when omitted
+    #[validate(length(min = 1, max = 255), custom(function = "validate_slug"))]
+    pub slug: Option<String>,
+    pub excerpt: Option<String>,
+    #[validate(length(min = 1))]
+    pub content: String,
+    #[validate(custom(function = "validate_status"))]
+    pub status: Option<String>,
+    #[validate(length(max = 64), custom(function = "validate_slug"))]
+    pub category: Option<String>,
+    #[validate(length(max = 20), custom(function = "validate_tags"))]
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Body for `PUT`, which replaces the whole post: omitted `excerpt`,
+/// `category` and `tags` are cleared.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReplaceBlogRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub title: String,
+    #[validate(length(min = 1, max = 255), custom(function = "validate_slug"))]
+    pub slug: String,
+    pub excerpt: Option<String>,
+    #[validate(length(min = 1))]
+    pub content: String,
+    #[validate(custom(function = "validate_status"))]
+    pub status: String,
+    #[validate(length(max = 64), custom(function = "validate_slug"))]
+    pub category: Option<String>,
+    #[validate(length(max = 20), custom(function = "validate_tags"))]
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Keeps the stored date when omitted, as `PATCH` does
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Body for `PATCH`; omitted fields are left unchanged.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateBlogRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub title: Option<String>,
+    #[validate(length(min = 1, max = 255), custom(function = "validate_slug"))]
+    pub slug: Option<String>,
+    pub excerpt: Option<String>,
+    #[validate(length(min = 1))]
+    pub content: Option<String>,
+    #[validate(custom(function = "validate_status"))]
+    pub status: Option<String>,
+    /// An empty string clears the category.
+    #[validate(length(max = 64), custom(function = "validate_slug"))]
+    pub category: Option<String>,
+    /// Replaces the full tag set when present.
+    #[validate(length(max = 20), custom(function = "validate_tags"))]
+    pub tags: Option<Vec<String>>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+fn validate_status(status: &str) -> Result<(), ValidationError> {
+    status
+        .parse::<BlogStatus>()
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("status"))
+}
+
+fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+    let valid = tags
+        .iter()
+        .all(|tag| tag.trim().chars().count() <= 64 && !slugify(tag).is_empty());
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new("tags"))
+    }
+}
+
+fn validate_slug(slug: &str) -> Result<(), ValidationError> {
+    let valid = slug
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && !is_reserved(slug);
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new("slug"))
+    }
+}
+
+/// Whether `slug` is free for the post `exclude_id` (or a new post). Slugs
+/// other posts used to have stay reserved so their old links keep redirecting.
+async fn slug_taken(state: &AppState, slug: &str, exclude_id: Option<i32>) -> ApiResult<bool> {
+    let mut current = blogs::Entity::find().filter(blogs::Column::Slug.eq(slug));
+    let mut previous =
+        blog_slug_history::Entity::find().filter(blog_slug_history::Column::Slug.eq(slug));
+    if let Some(id) = exclude_id {
+        current = current.filter(blogs::Column::Id.ne(id));
+        previous = previous.filter(blog_slug_history::Column::BlogId.ne(id));
+    }
+    Ok(current.count(&state.db).await? > 0
+        || previous.count(&state.db).await? > 0)
+}
+
+async fn ensure_slug_available(
+    state: &AppState,
+    slug: &str,
+    exclude_id: Option<i32>,
+) -> ApiResult<()> {
+    if slug_taken(state, slug, exclude_id).await? {
+        return Err(ApiError::conflict("Slug already in use"));
+    }
+    Ok(())
+}
+
+/// A free slug derived from `title`: `my-post`, then `my-post-2`, `my-post-3`...
+/// Reserved slugs are never free, so a post titled "Search" gets `search-2`.
+async fn unique_slug(state: &AppState, title: &str) -> ApiResult<String> {
+    let mut base = slugify(title);
+    base.truncate(200);
+    let base = match base.trim_end_matches('-') {
+        "" => "post".to_string(),
+        trimmed => trimmed.to_string(),
+    };
+
+    let mut candidate = base.clone();
+    let mut n = 1;
+    while is_reserved(&candidate) || slug_taken(state, &candidate, None).await? {
+        n += 1;
+        candidate = format!("{}-{}", base, n);
+    }
+    Ok(candidate)
+}
+
+/// `BlogStatus::resolve` against the current time, as a 422 when it refuses.
+fn resolve_status(
+    status: BlogStatus,
+    published_at: Option<DateTime<Utc>>,
+) -> ApiResult<(BlogStatus, Option<DateTime<Utc>>)> {
+    status
+        .resolve(published_at, chrono::Utc::now())
+        .map_err(ApiError::unprocessable)
+}
+
+pub(crate) async fn find_blog(state: &AppState, id: i32) -> ApiResult<blogs::Model> {
+    blogs::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::not_found("Blog not found"))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/blogs",
+    tag = "blogs",
+    request_body = CreateBlogRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Post created", body = BlogDetailResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Needs `author` or above", body = ErrorResponse),
+        (status = 409, description = "Slug already in use", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse),
+    )
+)]
+pub async fn create_blog(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<CreateBlogRequest>,
+) -> ApiResult<(StatusCode, Json<BlogDetailResponse>)> {
+    auth_user.require(Role::Author)?;
+    payload.validate()?;
+    let slug = match payload.slug {
+        Some(slug) => {
+            ensure_slug_available(&state, &slug, None).await?;
+            slug
+        }
+        None => unique_slug(&state, &payload.title).await?,
+    };
+
+    let requested = payload
+        .status
+        .as_deref()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(BlogStatus::Draft);
+    let (status, published_at) = resolve_status(requested, payload.published_at)?;
+
+    let txn = state.db.begin().await?;
+    let watermark = revision_watermark(&txn).await?;
+    let model = blogs::ActiveModel {
+        author_id: Set(auth_user.id),
+        title: Set(payload.title),
+        slug: Set(slug),
+        excerpt: Set(payload.excerpt),
+        content: Set(payload.content),
+        status: Set(Some(status.as_str().to_string())),
+        category: Set(payload.category.filter(|c| !c.is_empty())),
+        published_at: Set(published_at),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    let tags = set_blog_tags(&txn, model.id, &payload.tags)
+        .await?;
+    attribute_revisions(&txn, watermark, auth_user.id)
+        .await?;
+    txn.commit().await?;
+
+    let tags = tags.into_iter().map(TagResponse::from).collect();
+    Ok((StatusCode::CREATED, Json(blog_detail(model, tags))))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/blogs/{slug}",
+    tag = "blogs",
+    params(("slug" = i32, Path, description = "Post id")),
+    request_body = ReplaceBlogRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Post replaced", body = BlogDetailResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed to edit the post", body = ErrorResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+        (status = 409, description = "Slug already in use", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse),
+    )
+)]
+pub async fn replace_blog(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<ReplaceBlogRequest>,
+) -> ApiResult<Json<BlogDetailResponse>> {
+    payload.validate()?;
+    let existing = find_blog(&state, id).await?;
+    if !auth_user.can_edit_owned_by(existing.author_id) {
+        return Err(forbidden());
+    }
+    ensure_slug_available(&state, &payload.slug, Some(id)).await?;
+
+    let requested = payload.status.parse().unwrap_or(BlogStatus::Draft);
+    let (status, published_at) =
+        resolve_status(requested, payload.published_at.or(existing.published_at))?;
+    let mut model: blogs::ActiveModel = existing.into();
+    model.title = Set(payload.title);
+    model.slug = Set(payload.slug);
+    model.excerpt = Set(payload.excerpt);
+    model.content = Set(payload.content);
+    model.category = Set(payload.category.filter(|c| !c.is_empty()));
+    model.status = Set(Some(status.as_str().to_string()));
+    model.published_at = Set(published_at);
+
+    save_blog(&state, &auth_user, model, Some(&payload.tags))
+        .await
+        .map(Json)
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/blogs/{slug}",
+    tag = "blogs",
+    params(("slug" = i32, Path, description = "Post id")),
+    request_body = UpdateBlogRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Post updated", body = BlogDetailResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed to edit the post", body = ErrorResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+        (status = 409, description = "Slug already in use", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse),
+    )
+)]
+pub async fn update_blog(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateBlogRequest>,
+) -> ApiResult<Json<BlogDetailResponse>> {
+    payload.validate()?;
+    let existing = find_blog(&state, id).await?;
+    if !auth_user.can_edit_owned_by(existing.author_id) {
+        return Err(forbidden());
+    }
+    if let Some(slug) = &payload.slug {
+        ensure_slug_available(&state, slug, Some(id)).await?;
+    }
+
+    // Only re-run the lifecycle when the status or the date is being changed
+    let schedule = if payload.status.is_some() || payload.published_at.is_some() {
+        let requested = payload
+            .status
+            .as_deref()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| existing.blog_status());
+        Some(resolve_status(requested, payload.published_at.or(existing.published_at))?)
+    } else {
+        None
+    };
+    let mut model: blogs::ActiveModel = existing.into();
+    if let Some(title) = payload.title {
+        model.title = Set(title);
+    }
+    if let Some(slug) = payload.slug {
+        model.slug = Set(slug);
+    }
+    if let Some(excerpt) = payload.excerpt {
+        model.excerpt = Set(Some(excerpt));
+    }
+    if let Some(content) = payload.content {
+        model.content = Set(content);
+    }
+    if let Some(category) = payload.category {
+        model.category = Set(Some(category).filter(|c| !c.is_empty()));
+    }
+    if let Some((status, published_at)) = schedule {
+        model.status = Set(Some(status.as_str().to_string()));
+        model.published_at = Set(published_at);
+    }
+    save_blog(&state, &auth_user, model, payload.tags.as_deref())
+        .await
+        .map(Json)
+}
+
+/// Writes an edited post, replacing its tags when given, and credits the
+/// revisions the edit produced to `editor`.
+async fn save_blog(
+    state: &AppState,
+    editor: &AuthUser,
+    model: blogs::ActiveModel,
+    tags: Option<&[String]>,
+) -> ApiResult<BlogDetailResponse> {
+    let txn = state.db.begin().await?;
+    let watermark = revision_watermark(&txn).await?;
+    let model = model.update(&txn).await?;
+    if let Some(tags) = tags {
+        set_blog_tags(&txn, model.id, tags).await?;
+    }
+    attribute_revisions(&txn, watermark, editor.id)
+        .await?;
+    txn.commit().await?;
+
+    detail_response(state, model).await
+}
+
+/// Detail payload for a post that was just written, without recording a view.
+pub(crate) async fn detail_response(
+    state: &AppState,
+    model: blogs::Model,
+) -> ApiResult<BlogDetailResponse> {
+    let tags = tags_for_blog(&state.db, &model).await?;
+    Ok(blog_detail(model, tags))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/blogs/{slug}",
+    tag = "blogs",
+    params(("slug" = i32, Path, description = "Post id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Post deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed to edit the post", body = ErrorResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_blog(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i32>,
+) -> ApiResult<StatusCode> {
+    let existing = find_blog(&state, id).await?;
+    if !auth_user.can_edit_owned_by(existing.author_id) {
+        return Err(forbidden());
     }
+    blogs::Entity::delete_by_id(existing.id)
+        .exec(&state.db)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
 }
@@ -0,0 +1,418 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError};
+use super::{ApiError, ApiResult, ErrorResponse};
+use crate::{
+    auth::{roles::forbidden, AuthUser, Role},
+    entities::{blogs, comments::{self, CommentStatus}, users},
+    AppState,
+};
+
+/// A published comment with its published replies, oldest first.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CommentResponse {
+    pub id: i32,
+    pub parent_id: Option<i32>,
+    pub author_name: String,
+    pub content: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    #[schema(no_recursion)]
+    pub replies: Vec<CommentResponse>,
+}
+
+impl CommentResponse {
+    fn from_model(model: comments::Model) -> Self {
+        Self {
+            id: model.id,
+            parent_id: model.parent_id,
+            author_name: model.author_name,
+            content: model.content,
+            status: model.status,
+            created_at: model.created_at,
+            replies: Vec::new(),
+        }
+    }
+}
+
+/// How many levels replies nest. `create_comment` posts replies to the deepest
+/// level alongside their parent, and `thread` lists anything deeper flat.
+pub const MAX_COMMENT_DEPTH: usize = 8;
+
+/// Nests comments under their parents, keeping the input order at every
+/// level. Replies whose parent is not in `comments` (e.g. still pending or
+/// marked as spam) are dropped along with their own replies. Below
+/// `MAX_COMMENT_DEPTH` all descendants are listed flat under the last level.
+pub fn thread(comments: Vec<comments::Model>) -> Vec<CommentResponse> {
+    let mut children: HashMap<Option<i32>, Vec<(usize, comments::Model)>> = HashMap::new();
+    for (position, comment) in comments.into_iter().enumerate() {
+        children.entry(comment.parent_id).or_default().push((position, comment));
+    }
+
+    fn flatten(
+        parent: i32,
+        children: &mut HashMap<Option<i32>, Vec<(usize, comments::Model)>>,
+    ) -> Vec<CommentResponse> {
+        let mut descendants = Vec::new();
+        let mut pending = vec![parent];
+        while let Some(id) = pending.pop() {
+            for (position, model) in children.remove(&Some(id)).unwrap_or_default() {
+                pending.push(model.id);
+                descendants.push((position, model));
+            }
+        }
+        descendants.sort_by_key(|(position, _)| *position);
+        descendants.into_iter().map(|(_, model)| CommentResponse::from_model(model)).collect()
+    }
+
+    fn build(
+        parent: Option<i32>,
+        depth: usize,
+        children: &mut HashMap<Option<i32>, Vec<(usize, comments::Model)>>,
+    ) -> Vec<CommentResponse> {
+        children
+            .remove(&parent)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, model)| {
+                let id = model.id;
+                let mut comment = CommentResponse::from_model(model);
+                comment.replies = if depth + 1 < MAX_COMMENT_DEPTH {
+                    build(Some(id), depth + 1, children)
+                } else {
+                    flatten(id, children)
+                };
+                comment
+            })
+            .collect()
+    }
+
+    build(None, 1, &mut children)
+}
+
+/// Level of `comment` in its thread, 1 for a top-level comment, counting no
+/// further than `MAX_COMMENT_DEPTH`.
+async fn comment_depth(state: &AppState, comment: &comments::Model) -> ApiResult<usize> {
+    let mut depth = 1;
+    let mut parent_id = comment.parent_id;
+    while let Some(id) = parent_id.filter(|_| depth < MAX_COMMENT_DEPTH) {
+        depth += 1;
+        parent_id = comments::Entity::find_by_id(id)
+            .one(&state.db)
+            .await?
+            .and_then(|parent| parent.parent_id);
+    }
+    Ok(depth)
+}
+
+async fn live_blog_by_slug(state: &AppState, slug: &str) -> ApiResult<blogs::Model> {
+    blogs::Entity::find()
+        .filter(blogs::Column::Slug.eq(slug))
+        .filter(blogs::live())
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::not_found("Blog not found"))
+}
+
+/// Approved comments on a published post, as a thread.
+#[utoipa::path(
+    get,
+    path = "/api/blogs/{slug}/comments",
+    tag = "comments",
+    params(("slug" = String, Path, description = "Post slug")),
+    responses(
+        (status = 200, description = "Comment threads", body = Vec<CommentResponse>),
+        (status = 404, description = "No published post with this slug", body = ErrorResponse),
+    )
+)]
+pub async fn list_comments(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> ApiResult<Json<Vec<CommentResponse>>> {
+    let blog = live_blog_by_slug(&state, &slug).await?;
+    let approved = comments::Entity::find()
+        .filter(comments::Column::BlogId.eq(blog.id))
+        .filter(comments::Column::Status.eq(CommentStatus::Approved.as_str()))
+        .order_by_asc(comments::Column::CreatedAt)
+        .order_by_asc(comments::Column::Id)
+        .all(&state.db)
+        .await?;
+    Ok(Json(thread(approved)))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateCommentRequest {
+    /// Comment being replied to; it must be approved and on the same post.
+    /// Replies at `MAX_COMMENT_DEPTH` are posted alongside it instead.
+    pub parent_id: Option<i32>,
+    /// Required when not signed in; ignored otherwise
+    #[validate(length(min = 1, max = 100))]
+    pub author_name: Option<String>,
+    /// Required when not signed in; ignored otherwise. Never shown publicly.
+    #[validate(email, length(max = 255))]
+    pub author_email: Option<String>,
+    #[validate(length(min = 1, max = 5000), custom(function = "validate_not_blank"))]
+    pub content: String,
+}
+
+fn validate_not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        Err(ValidationError::new("blank"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Anonymous comments and those from readers are held as `pending` until an
+/// admin approves them; authors and above are published straight away.
+#[utoipa::path(
+    post,
+    path = "/api/blogs/{slug}/comments",
+    tag = "comments",
+    params(("slug" = String, Path, description = "Post slug")),
+    request_body = CreateCommentRequest,
+    security((), ("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Comment created, possibly pending moderation", body = CommentResponse),
+        (status = 404, description = "No published post with this slug", body = ErrorResponse),
+        (status = 422, description = "Validation failed or the parent cannot be replied to", body = ErrorResponse),
+    )
+)]
+pub async fn create_comment(
+    State(state): State<AppState>,
+    auth_user: Option<AuthUser>,
+    Path(slug): Path<String>,
+    Json(payload): Json<CreateCommentRequest>,
+) -> ApiResult<(StatusCode, Json<CommentResponse>)> {
+    payload.validate()?;
+    let blog = live_blog_by_slug(&state, &slug).await?;
+
+    let parent_id = match payload.parent_id {
+        Some(parent_id) => {
+            let parent = comments::Entity::find_by_id(parent_id)
+                .filter(comments::Column::BlogId.eq(blog.id))
+                .filter(comments::Column::Status.eq(CommentStatus::Approved.as_str()))
+                .one(&state.db)
+                .await?
+                .ok_or_else(|| ApiError::unprocessable("Parent comment not found on this post"))?;
+            if comment_depth(&state, &parent).await? >= MAX_COMMENT_DEPTH {
+                parent.parent_id
+            } else {
+                Some(parent.id)
+            }
+        }
+        None => None,
+    };
+
+    let (user_id, author_name, author_email, status) = match auth_user {
+        Some(auth_user) => {
+            let user = users::Entity::find_by_id(auth_user.id)
+                .one(&state.db)
+                .await?
+                .ok_or_else(forbidden)?;
+            let status = if auth_user.role >= Role::Author {
+                CommentStatus::Approved
+            } else {
+                CommentStatus::Pending
+            };
+            (Some(user.id), user.name, Some(user.email), status)
+        }
+        None => match (payload.author_name, payload.author_email) {
+            (Some(name), Some(email)) if !name.trim().is_empty() => {
+                (None, name.trim().to_string(), Some(email), CommentStatus::Pending)
+            }
+            _ => {
+                return Err(ApiError::unprocessable(
+                    "Name and email are required when not signed in",
+                ))
+            }
+        },
+    };
+
+    let now = chrono::Utc::now();
+    let model = comments::ActiveModel {
+        blog_id: Set(blog.id),
+        parent_id: Set(parent_id),
+        user_id: Set(user_id),
+        author_name: Set(author_name),
+        author_email: Set(author_email),
+        content: Set(payload.content.trim().to_string()),
+        status: Set(status.as_str().to_string()),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(CommentResponse::from_model(model))))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ModerationParams {
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+    /// Defaults to `pending`; empty for every status
+    pub status: Option<String>,
+}
+
+/// A comment as moderators see it, with the post it belongs to.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModerationItem {
+    #[serde(flatten)]
+    pub comment: comments::Model,
+    pub blog_slug: Option<String>,
+    pub blog_title: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModerationListResponse {
+    pub items: Vec<ModerationItem>,
+    pub page: u64,
+    pub per_page: u64,
+    pub total: u64,
+    pub total_pages: u64,
+}
+
+fn parse_comment_status(status: &str) -> ApiResult<CommentStatus> {
+    status.parse().map_err(|_| ApiError::bad_request("Unknown status"))
+}
+
+/// The moderation queue: pending comments oldest first, so nothing waits forever.
+#[utoipa::path(
+    get,
+    path = "/api/comments",
+    tag = "comments",
+    params(ModerationParams),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "One page of comments", body = ModerationListResponse),
+        (status = 400, description = "Unknown status", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Needs `admin`", body = ErrorResponse),
+    )
+)]
+pub async fn list_moderation_queue(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(params): Query<ModerationParams>,
+) -> ApiResult<Json<ModerationListResponse>> {
+    auth_user.require(Role::Admin)?;
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+
+    let mut query = comments::Entity::find()
+        .find_also_related(blogs::Entity)
+        .order_by_asc(comments::Column::CreatedAt)
+        .order_by_asc(comments::Column::Id);
+    match params.status.as_deref() {
+        Some("") => {}
+        status => {
+            let status = parse_comment_status(status.unwrap_or("pending"))?;
+            query = query.filter(comments::Column::Status.eq(status.as_str()));
+        }
+    }
+
+    let paginator = query.paginate(&state.db, per_page);
+    let total = paginator.num_items().await?;
+    let total_pages = paginator.num_pages().await?;
+    let items = paginator
+        .fetch_page(page - 1)
+        .await?
+        .into_iter()
+        .map(|(comment, blog)| ModerationItem {
+            comment,
+            blog_slug: blog.as_ref().map(|b| b.slug.clone()),
+            blog_title: blog.map(|b| b.title),
+        })
+        .collect();
+
+    Ok(Json(ModerationListResponse {
+        items,
+        page,
+        per_page,
+        total,
+        total_pages,
+    }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ModerateCommentRequest {
+    pub status: String,
+}
+
+async fn find_comment(state: &AppState, id: i32) -> ApiResult<comments::Model> {
+    comments::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::not_found("Comment not found"))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/comments/{id}",
+    tag = "comments",
+    params(("id" = i32, Path, description = "Comment id")),
+    request_body = ModerateCommentRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Comment updated", body = comments::Model),
+        (status = 400, description = "Unknown status", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Needs `admin`", body = ErrorResponse),
+        (status = 404, description = "Comment not found", body = ErrorResponse),
+    )
+)]
+pub async fn moderate_comment(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<ModerateCommentRequest>,
+) -> ApiResult<Json<comments::Model>> {
+    auth_user.require(Role::Admin)?;
+    let status = parse_comment_status(&payload.status)?;
+    let comment = find_comment(&state, id).await?;
+
+    let mut model: comments::ActiveModel = comment.into();
+    model.status = Set(status.as_str().to_string());
+    model.updated_at = Set(chrono::Utc::now());
+    let model = model.update(&state.db).await?;
+    Ok(Json(model))
+}
+
+/// Deletes the comment and every reply beneath it.
+#[utoipa::path(
+    delete,
+    path = "/api/comments/{id}",
+    tag = "comments",
+    params(("id" = i32, Path, description = "Comment id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Comment and replies deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Needs `admin`", body = ErrorResponse),
+        (status = 404, description = "Comment not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_comment(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i32>,
+) -> ApiResult<StatusCode> {
+    auth_user.require(Role::Admin)?;
+    let comment = find_comment(&state, id).await?;
+    comments::Entity::delete_by_id(comment.id)
+        .exec(&state.db)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
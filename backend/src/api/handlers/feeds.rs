@@ -0,0 +1,520 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, FixedOffset, Utc};
+use sea_orm::{ColumnTrait, EntityTrait, LoaderTrait, QueryFilter, QuerySelect};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::IntoParams;
+use super::{blogs::filtered_blogs, request_origin, ApiError, ApiResult, ErrorResponse};
+use crate::{
+    entities::{
+        blog_tags,
+        blogs::{self, BlogStatus},
+        tags, users,
+    },
+    markdown,
+    AppState,
+};
+
+/// Number of most recent posts included in every feed.
+const FEED_SIZE: u64 = 20;
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct FeedParams {
+    /// `full` (default) or `excerpt`
+    pub content: Option<String>,
+}
+
+impl FeedParams {
+    fn full_content(&self) -> bool {
+        self.content.as_deref() != Some("excerpt")
+    }
+}
+
+struct FeedEntry {
+    post: blogs::Model,
+    author: Option<String>,
+    tags: Vec<tags::Model>,
+}
+
+impl FeedEntry {
+    fn link(&self, site: &str) -> String {
+        format!("{}/blogs/{}", site, self.post.slug)
+    }
+
+    /// Tag URI built from the post id and creation date, so it survives
+    /// title and slug changes.
+    fn guid(&self, site: &str) -> String {
+        let host = site
+            .split_once("://")
+            .map_or(site, |(_, rest)| rest)
+            .split(['/', ':'])
+            .next()
+            .unwrap_or_default();
+        let date = self.post.created_at.unwrap_or_default().format("%Y-%m-%d");
+        format!("tag:{},{}:blogs/{}", host, date, self.post.id)
+    }
+
+    fn content_html(&self) -> String {
+        self.post
+            .content_html
+            .clone()
+            .unwrap_or_else(|| markdown::render(&self.post.content))
+    }
+
+    fn published(&self) -> DateTime<Utc> {
+        self.post
+            .published_at
+            .or(self.post.created_at)
+            .unwrap_or_default()
+    }
+
+    fn updated(&self) -> DateTime<Utc> {
+        self.post.updated_at.unwrap_or_else(|| self.published())
+    }
+}
+
+struct Feed {
+    title: String,
+    /// Public URL of the frontend, from `Config::site_url`
+    site: String,
+    /// Frontend page the feed mirrors
+    home: String,
+    /// Absolute URL the feed files are served under, e.g. `http://host/tags/rust`
+    base: String,
+    entries: Vec<FeedEntry>,
+    full_content: bool,
+}
+
+impl Feed {
+    fn updated(&self) -> Option<DateTime<Utc>> {
+        self.entries.iter().map(FeedEntry::updated).max()
+    }
+
+    /// Hash of which posts the feed shows, as of which edit, and how. Removing
+    /// a post changes it as surely as editing one, even when the newest post
+    /// and the number of entries stay the same.
+    fn etag(&self) -> String {
+        let mut hash = Sha256::new();
+        hash.update(&self.base);
+        hash.update(if self.full_content { "full" } else { "excerpt" });
+        for entry in &self.entries {
+            hash.update(format!("\n{}:{}", entry.post.id, entry.updated().timestamp_millis()));
+        }
+        format!("\"{:.32x}\"", hash.finalize())
+    }
+}
+
+/// Loads the newest published posts, optionally limited to one tag.
+async fn load_feed(
+    state: &AppState,
+    headers: &HeaderMap,
+    tag: Option<&str>,
+    params: &FeedParams,
+) -> ApiResult<Feed> {
+    let site = state.config.site_url.clone();
+    let origin = request_origin(headers, &state.config);
+    let (title, home, base) = match tag {
+        Some(slug) => {
+            let tag = tags::Entity::find()
+                .filter(tags::Column::Slug.eq(slug))
+                .one(&state.db)
+                .await?
+                .ok_or(ApiError::not_found("Tag not found"))?;
+            (
+                format!("Blog: {}", tag.name),
+                format!("{}/tags/{}", site, tag.slug),
+                format!("{}/tags/{}", origin, tag.slug),
+            )
+        }
+        None => ("Blog".to_string(), format!("{}/blogs", site), origin),
+    };
+
+    let rows = filtered_blogs(Some(BlogStatus::Published), tag, None)
+        .find_also_related(users::Entity)
+        .limit(FEED_SIZE)
+        .all(&state.db)
+        .await?;
+    let (posts, authors): (Vec<_>, Vec<_>) = rows.into_iter().unzip();
+    let tags = posts
+        .load_many_to_many(tags::Entity, blog_tags::Entity, &state.db)
+        .await?;
+
+    let entries = posts
+        .into_iter()
+        .zip(authors)
+        .zip(tags)
+        .map(|((post, author), tags)| FeedEntry {
+            post,
+            author: author.map(|a| a.name),
+            tags,
+        })
+        .collect();
+
+    Ok(Feed {
+        title,
+        site,
+        home,
+        base,
+        entries,
+        full_content: params.full_content(),
+    })
+}
+
+/// HTTP-date as required by `Last-Modified`.
+fn http_date(at: DateTime<Utc>) -> String {
+    at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Whether the client's cached copy is still current. `If-None-Match` wins
+/// over `If-Modified-Since` when both are sent.
+fn not_modified(headers: &HeaderMap, etag: &str, updated: Option<DateTime<Utc>>) -> bool {
+    if let Some(value) = headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+        return value
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == etag || tag == "*");
+    }
+    let since = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| DateTime::parse_from_rfc2822(v).ok());
+    match (since, updated) {
+        (Some(since), Some(updated)) => updated.timestamp() <= since.timestamp(),
+        _ => false,
+    }
+}
+
+/// Answers with `304 Not Modified` when possible, otherwise renders the feed.
+fn respond(
+    headers: &HeaderMap,
+    feed: &Feed,
+    content_type: &'static str,
+    render: impl FnOnce(&Feed) -> String,
+) -> Response {
+    let etag = feed.etag();
+    let updated = feed.updated();
+
+    let mut response = if not_modified(headers, &etag, updated) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        ([(header::CONTENT_TYPE, content_type)], render(feed)).into_response()
+    };
+
+    let cache = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        cache.insert(header::ETAG, value);
+    }
+    if let Some(value) = updated.and_then(|at| HeaderValue::from_str(&http_date(at)).ok()) {
+        cache.insert(header::LAST_MODIFIED, value);
+    }
+    response
+}
+
+fn render_rss(feed: &Feed) -> String {
+    use rss::extension::{atom, dublincore::DublinCoreExtension};
+
+    let site = &feed.site;
+    let items = feed
+        .entries
+        .iter()
+        .map(|entry| rss::Item {
+            title: Some(entry.post.title.clone()),
+            link: Some(entry.link(site)),
+            description: entry.post.excerpt.clone(),
+            content: feed.full_content.then(|| entry.content_html()),
+            guid: Some(rss::Guid {
+                value: entry.guid(site),
+                permalink: false,
+            }),
+            pub_date: Some(entry.published().to_rfc2822()),
+            categories: entry
+                .tags
+                .iter()
+                .map(|tag| rss::Category {
+                    name: tag.name.clone(),
+                    domain: None,
+                })
+                .collect(),
+            // RSS `author` must be an email address, so names go in dc:creator
+            dublin_core_ext: entry.author.clone().map(|name| DublinCoreExtension {
+                creators: vec![name],
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .collect();
+
+    rss::Channel {
+        title: feed.title.clone(),
+        link: feed.home.clone(),
+        description: format!("Latest posts from {}", feed.title),
+        last_build_date: feed.updated().map(|at| at.to_rfc2822()),
+        atom_ext: Some(atom::AtomExtension {
+            links: vec![atom::Link {
+                href: format!("{}/feed.xml", feed.base),
+                rel: "self".to_string(),
+                mime_type: Some("application/rss+xml".to_string()),
+                ..Default::default()
+            }],
+        }),
+        items,
+        ..Default::default()
+    }
+    .to_string()
+}
+
+fn render_atom(feed: &Feed) -> String {
+    use atom_syndication::{Category, Content, Entry, Link, Person, Text};
+
+    let site = &feed.site;
+    let fixed = |at: DateTime<Utc>| -> DateTime<FixedOffset> { at.into() };
+    let entries = feed
+        .entries
+        .iter()
+        .map(|entry| Entry {
+            title: Text::plain(entry.post.title.clone()),
+            id: entry.guid(site),
+            updated: fixed(entry.updated()),
+            published: Some(fixed(entry.published())),
+            authors: entry
+                .author
+                .iter()
+                .map(|name| Person {
+                    name: name.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            categories: entry
+                .tags
+                .iter()
+                .map(|tag| Category {
+                    term: tag.slug.clone(),
+                    label: Some(tag.name.clone()),
+                    ..Default::default()
+                })
+                .collect(),
+            links: vec![Link {
+                href: entry.link(site),
+                ..Default::default()
+            }],
+            summary: entry.post.excerpt.clone().map(Text::plain),
+            content: feed.full_content.then(|| Content {
+                value: Some(entry.content_html()),
+                content_type: Some("html".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .collect();
+
+    atom_syndication::Feed {
+        title: Text::plain(feed.title.clone()),
+        id: feed.home.clone(),
+        updated: fixed(feed.updated().unwrap_or_default()),
+        links: vec![
+            Link {
+                href: feed.home.clone(),
+                ..Default::default()
+            },
+            Link {
+                href: format!("{}/atom.xml", feed.base),
+                rel: "self".to_string(),
+                ..Default::default()
+            },
+        ],
+        entries,
+        ..Default::default()
+    }
+    .to_string()
+}
+
+/// JSON Feed 1.1, see https://www.jsonfeed.org/version/1.1/
+#[derive(Debug, Serialize)]
+struct JsonFeed {
+    version: &'static str,
+    title: String,
+    home_page_url: String,
+    feed_url: String,
+    items: Vec<JsonFeedItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonFeedItem {
+    id: String,
+    url: String,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_html: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    date_published: DateTime<Utc>,
+    date_modified: DateTime<Utc>,
+    authors: Vec<JsonFeedAuthor>,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonFeedAuthor {
+    name: String,
+}
+
+fn render_json(feed: &Feed) -> String {
+    let site = &feed.site;
+    let items = feed
+        .entries
+        .iter()
+        .map(|entry| JsonFeedItem {
+            id: entry.guid(site),
+            url: entry.link(site),
+            title: entry.post.title.clone(),
+            // JSON Feed requires content; fall back to the excerpt
+            content_html: if feed.full_content || entry.post.excerpt.is_none() {
+                Some(entry.content_html())
+            } else {
+                None
+            },
+            summary: entry.post.excerpt.clone(),
+            date_published: entry.published(),
+            date_modified: entry.updated(),
+            authors: entry
+                .author
+                .iter()
+                .map(|name| JsonFeedAuthor { name: name.clone() })
+                .collect(),
+            tags: entry.tags.iter().map(|tag| tag.name.clone()).collect(),
+        })
+        .collect();
+
+    let json = JsonFeed {
+        version: "https://jsonfeed.org/version/1.1",
+        title: feed.title.clone(),
+        home_page_url: feed.home.clone(),
+        feed_url: format!("{}/feed.json", feed.base),
+        items,
+    };
+    serde_json::to_string(&json).unwrap_or_default()
+}
+
+#[utoipa::path(
+    get,
+    path = "/feed.xml",
+    tag = "feeds",
+    params(FeedParams),
+    responses(
+        (status = 200, description = "RSS 2.0 feed of the latest published posts", body = String, content_type = "application/rss+xml"),
+        (status = 304, description = "Unchanged since `If-None-Match` / `If-Modified-Since`"),
+    )
+)]
+pub async fn rss_feed(
+    State(state): State<AppState>,
+    Query(params): Query<FeedParams>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let feed = load_feed(&state, &headers, None, &params).await?;
+    Ok(respond(&headers, &feed, "application/rss+xml; charset=utf-8", render_rss))
+}
+
+#[utoipa::path(
+    get,
+    path = "/atom.xml",
+    tag = "feeds",
+    params(FeedParams),
+    responses(
+        (status = 200, description = "Atom feed of the latest published posts", body = String, content_type = "application/atom+xml"),
+        (status = 304, description = "Unchanged since `If-None-Match` / `If-Modified-Since`"),
+    )
+)]
+pub async fn atom_feed(
+    State(state): State<AppState>,
+    Query(params): Query<FeedParams>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let feed = load_feed(&state, &headers, None, &params).await?;
+    Ok(respond(&headers, &feed, "application/atom+xml; charset=utf-8", render_atom))
+}
+
+#[utoipa::path(
+    get,
+    path = "/feed.json",
+    tag = "feeds",
+    params(FeedParams),
+    responses(
+        (status = 200, description = "JSON Feed 1.1 feed of the latest published posts", body = String, content_type = "application/feed+json"),
+        (status = 304, description = "Unchanged since `If-None-Match` / `If-Modified-Since`"),
+    )
+)]
+pub async fn json_feed(
+    State(state): State<AppState>,
+    Query(params): Query<FeedParams>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let feed = load_feed(&state, &headers, None, &params).await?;
+    Ok(respond(&headers, &feed, "application/feed+json; charset=utf-8", render_json))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tags/{tag}/feed.xml",
+    tag = "feeds",
+    params(("tag" = String, Path, description = "Tag slug"), FeedParams),
+    responses(
+        (status = 200, description = "RSS 2.0 feed of the latest published posts with the tag", body = String, content_type = "application/rss+xml"),
+        (status = 304, description = "Unchanged since `If-None-Match` / `If-Modified-Since`"),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+    )
+)]
+pub async fn tag_rss_feed(
+    State(state): State<AppState>,
+    Path(tag): Path<String>,
+    Query(params): Query<FeedParams>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let feed = load_feed(&state, &headers, Some(&tag), &params).await?;
+    Ok(respond(&headers, &feed, "application/rss+xml; charset=utf-8", render_rss))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tags/{tag}/atom.xml",
+    tag = "feeds",
+    params(("tag" = String, Path, description = "Tag slug"), FeedParams),
+    responses(
+        (status = 200, description = "Atom feed of the latest published posts with the tag", body = String, content_type = "application/atom+xml"),
+        (status = 304, description = "Unchanged since `If-None-Match` / `If-Modified-Since`"),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+    )
+)]
+pub async fn tag_atom_feed(
+    State(state): State<AppState>,
+    Path(tag): Path<String>,
+    Query(params): Query<FeedParams>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let feed = load_feed(&state, &headers, Some(&tag), &params).await?;
+    Ok(respond(&headers, &feed, "application/atom+xml; charset=utf-8", render_atom))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tags/{tag}/feed.json",
+    tag = "feeds",
+    params(("tag" = String, Path, description = "Tag slug"), FeedParams),
+    responses(
+        (status = 200, description = "JSON Feed 1.1 feed of the latest published posts with the tag", body = String, content_type = "application/feed+json"),
+        (status = 304, description = "Unchanged since `If-None-Match` / `If-Modified-Since`"),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+    )
+)]
+pub async fn tag_json_feed(
+    State(state): State<AppState>,
+    Path(tag): Path<String>,
+    Query(params): Query<FeedParams>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let feed = load_feed(&state, &headers, Some(&tag), &params).await?;
+    Ok(respond(&headers, &feed, "application/feed+json; charset=utf-8", render_json))
+}
@@ -0,0 +1,474 @@
+use axum::{
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use image::{imageops::FilterType, DynamicImage, ImageFormat, ImageReader, Limits};
+use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use std::{io::Cursor, str::FromStr};
+use tracing::{error, warn};
+use utoipa::{IntoParams, ToSchema};
+use super::{request_origin, ApiError, ApiResult, ErrorResponse};
+use crate::{
+    auth::{roles::forbidden, AuthUser, Role},
+    config::Config,
+    entities::media,
+    AppState,
+};
+
+/// Box sizes variants are rendered for. Requested widths and heights snap up
+/// to the next one, so anyone may ask for a variant but only a few exist.
+pub const VARIANT_SIZES: [u32; 7] = [160, 320, 640, 960, 1280, 1920, 2560];
+/// Cached variants per media item; past this, uncached sizes get the original.
+pub const MAX_VARIANTS: usize = 24;
+/// Largest width or height of an image that will be decoded.
+pub const MAX_SOURCE_DIMENSION: u32 = 8192;
+/// Media rows never change once written, so every response can be cached for good.
+const CACHE_FOREVER: &str = "public, max-age=31536000, immutable";
+
+/// Request body limit for uploads: the file plus room for multipart framing.
+pub fn upload_body_limit(config: &Config) -> DefaultBodyLimit {
+    DefaultBodyLimit::max(config.media_max_bytes + 64 * 1024)
+}
+
+/// Formats images are accepted in and may be converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+}
+
+impl MediaFormat {
+    /// Identifies an upload by its leading bytes rather than what the client claims.
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        match image::guess_format(bytes).ok()? {
+            ImageFormat::Png => Some(MediaFormat::Png),
+            ImageFormat::Jpeg => Some(MediaFormat::Jpeg),
+            ImageFormat::Gif => Some(MediaFormat::Gif),
+            ImageFormat::WebP => Some(MediaFormat::Webp),
+            _ => None,
+        }
+    }
+
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        [MediaFormat::Png, MediaFormat::Jpeg, MediaFormat::Gif, MediaFormat::Webp]
+            .into_iter()
+            .find(|f| f.mime() == mime)
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            MediaFormat::Png => "image/png",
+            MediaFormat::Jpeg => "image/jpeg",
+            MediaFormat::Gif => "image/gif",
+            MediaFormat::Webp => "image/webp",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            MediaFormat::Png => "png",
+            MediaFormat::Jpeg => "jpg",
+            MediaFormat::Gif => "gif",
+            MediaFormat::Webp => "webp",
+        }
+    }
+
+    fn image_format(&self) -> ImageFormat {
+        match self {
+            MediaFormat::Png => ImageFormat::Png,
+            MediaFormat::Jpeg => ImageFormat::Jpeg,
+            MediaFormat::Gif => ImageFormat::Gif,
+            MediaFormat::Webp => ImageFormat::WebP,
+        }
+    }
+}
+
+impl FromStr for MediaFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "png" => Ok(MediaFormat::Png),
+            "jpeg" | "jpg" => Ok(MediaFormat::Jpeg),
+            "gif" => Ok(MediaFormat::Gif),
+            "webp" => Ok(MediaFormat::Webp),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The allowed variant size a requested width or height is served at.
+pub fn snap_size(size: u32) -> u32 {
+    VARIANT_SIZES
+        .into_iter()
+        .find(|&allowed| allowed >= size)
+        .unwrap_or(VARIANT_SIZES[VARIANT_SIZES.len() - 1])
+}
+
+/// Bounds decoding so a small file cannot claim huge dimensions and exhaust memory.
+fn decode_limits() -> Limits {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    limits
+}
+
+/// Size of a variant that fits within the requested box, keeping the aspect
+/// ratio and never enlarging the original.
+pub fn fit_within(width: u32, height: u32, max_w: Option<u32>, max_h: Option<u32>) -> (u32, u32) {
+    let scale_w = max_w.map_or(1.0, |w| w as f64 / width as f64);
+    let scale_h = max_h.map_or(1.0, |h| h as f64 / height as f64);
+    let scale = scale_w.min(scale_h).min(1.0);
+    let scaled = |n: u32| ((n as f64 * scale).round() as u32).max(1);
+    (scaled(width), scaled(height))
+}
+
+/// Decodes `bytes`, resizes to exactly `width` x `height` and encodes as `format`.
+/// WebP output is lossless, the only mode the encoder supports.
+pub fn render_variant(
+    bytes: &[u8],
+    width: u32,
+    height: u32,
+    format: MediaFormat,
+) -> Result<Vec<u8>, image::ImageError> {
+    let mut reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    reader.limits(decode_limits());
+    let img = reader.decode()?;
+    let img = if (img.width(), img.height()) == (width, height) {
+        img
+    } else {
+        img.resize_exact(width, height, FilterType::Lanczos3)
+    };
+    // JPEG has no alpha channel; everything else keeps it
+    let img = match format {
+        MediaFormat::Jpeg => DynamicImage::ImageRgb8(img.to_rgb8()),
+        _ => DynamicImage::ImageRgba8(img.to_rgba8()),
+    };
+    let mut out = Cursor::new(Vec::new());
+    img.write_to(&mut out, format.image_format())?;
+    Ok(out.into_inner())
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MediaResponse {
+    pub id: i32,
+    /// Absolute URL of the original; add `w`, `h` or `format` for a variant
+    pub url: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub width: i32,
+    pub height: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MediaResponse {
+    fn from_model(model: media::Model, origin: &str) -> Self {
+        Self {
+            url: format!("{}/media/{}", origin, model.id),
+            id: model.id,
+            filename: model.filename,
+            mime_type: model.mime_type,
+            size_bytes: model.size_bytes,
+            width: model.width,
+            height: model.height,
+            created_at: model.created_at,
+        }
+    }
+}
+
+fn unsupported_media_type(message: &'static str) -> ApiError {
+    ApiError::new(StatusCode::UNSUPPORTED_MEDIA_TYPE, "unsupported_media_type", message)
+}
+
+fn storage_error(e: std::io::Error) -> ApiError {
+    error!("Media storage failed: {}", e);
+    ApiError::internal("Storage error")
+}
+
+fn variants_prefix(id: i32) -> String {
+    format!("variants/{}", id)
+}
+
+/// Body of `POST /api/media`, for the OpenAPI document.
+#[derive(ToSchema)]
+pub struct MediaUpload {
+    /// PNG, JPEG, GIF or WebP image
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+/// Multipart upload of a single image in the `file` field. The type is taken
+/// from the file's contents and must agree with the part's `Content-Type`.
+#[utoipa::path(
+    post,
+    path = "/api/media",
+    tag = "media",
+    request_body(content = MediaUpload, content_type = "multipart/form-data"),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Image stored", body = MediaResponse),
+        (status = 400, description = "Missing `file` field or malformed body", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Needs `author` or above", body = ErrorResponse),
+        (status = 413, description = "File too large", body = ErrorResponse),
+        (status = 415, description = "Not a supported image, or not the declared type", body = ErrorResponse),
+        (status = 422, description = "Image could not be decoded or is too large", body = ErrorResponse),
+    )
+)]
+pub async fn upload_media(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> ApiResult<(StatusCode, Json<MediaResponse>)> {
+    auth_user.require(Role::Author)?;
+    let max_bytes = state.config.media_max_bytes;
+    let multipart_error = |e: axum::extract::multipart::MultipartError| {
+        ApiError::new(e.status(), "invalid_multipart", e.body_text())
+    };
+
+    let mut upload = None;
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let filename = field.file_name().unwrap_or("upload").to_string();
+        let declared = field.content_type().map(str::to_string);
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+            if bytes.len() + chunk.len() > max_bytes {
+                return Err(ApiError::new(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "payload_too_large",
+                    format!("File exceeds the {} byte limit", max_bytes),
+                ));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        upload = Some((filename, declared, bytes));
+        break;
+    }
+    let Some((filename, declared, bytes)) = upload else {
+        return Err(ApiError::bad_request("Missing `file` field"));
+    };
+
+    let format = MediaFormat::sniff(&bytes)
+        .ok_or_else(|| unsupported_media_type("Upload a PNG, JPEG, GIF or WebP image"))?;
+    if let Some(declared) = declared.filter(|d| d != "application/octet-stream") {
+        if MediaFormat::from_mime(&declared) != Some(format) {
+            return Err(unsupported_media_type("Content type does not match the file contents"));
+        }
+    }
+    let mut reader = ImageReader::with_format(Cursor::new(&bytes), format.image_format());
+    reader.limits(decode_limits());
+    let (width, height) = reader
+        .into_dimensions()
+        .map_err(|_| ApiError::unprocessable("Image could not be decoded"))?;
+    if decode_limits().check_dimensions(width, height).is_err() {
+        return Err(ApiError::unprocessable(format!(
+            "Images may be at most {} pixels wide or high",
+            MAX_SOURCE_DIMENSION
+        )));
+    }
+
+    let key = format!("{}.{}", uuid::Uuid::new_v4(), format.extension());
+    state.storage.put(&key, &bytes).await.map_err(storage_error)?;
+
+    let inserted = media::ActiveModel {
+        uploader_id: Set(Some(auth_user.id)),
+        filename: Set(filename.chars().take(255).collect()),
+        mime_type: Set(format.mime().to_string()),
+        size_bytes: Set(bytes.len() as i64),
+        width: Set(width as i32),
+        height: Set(height as i32),
+        storage_key: Set(key.clone()),
+        created_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await;
+    let model = match inserted {
+        Ok(model) => model,
+        Err(e) => {
+            if let Err(e) = state.storage.delete(&key).await {
+                warn!("Could not remove orphaned upload {}: {}", key, e);
+            }
+            return Err(e.into());
+        }
+    };
+
+    Ok((
+        StatusCode::CREATED,
+        Json(MediaResponse::from_model(model, &request_origin(&headers, &state.config))),
+    ))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct VariantParams {
+    /// Maximum width in pixels, rounded up to one of `VARIANT_SIZES`
+    pub w: Option<u32>,
+    /// Maximum height in pixels, rounded up to one of `VARIANT_SIZES`
+    pub h: Option<u32>,
+    /// `webp`, `png`, `jpeg` or `gif`; defaults to the original's format
+    pub format: Option<String>,
+}
+
+async fn find_media(state: &AppState, id: i32) -> ApiResult<media::Model> {
+    media::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Media not found"))
+}
+
+fn image_response(mime: &str, bytes: Vec<u8>) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, mime.to_string()),
+            (header::CACHE_CONTROL, CACHE_FOREVER.to_string()),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
+/// The original file, or a variant scaled to fit within `w` x `h` and/or
+/// converted to `format`. Sizes snap to `VARIANT_SIZES`; variants are rendered
+/// once and kept in storage, up to `MAX_VARIANTS` per item, after which
+/// uncached variants are answered with the original.
+#[utoipa::path(
+    get,
+    path = "/media/{id}",
+    tag = "media",
+    params(("id" = i32, Path, description = "Media id"), VariantParams),
+    responses(
+        (status = 200, description = "The image", content(
+            (Vec<u8> = "image/png"),
+            (Vec<u8> = "image/jpeg"),
+            (Vec<u8> = "image/gif"),
+            (Vec<u8> = "image/webp"),
+        )),
+        (status = 400, description = "Unknown format or zero size", body = ErrorResponse),
+        (status = 404, description = "Media not found", body = ErrorResponse),
+    )
+)]
+pub async fn serve_media(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(params): Query<VariantParams>,
+) -> ApiResult<Response> {
+    let requested = params
+        .format
+        .as_deref()
+        .map(|f| {
+            f.parse::<MediaFormat>()
+                .map_err(|_| ApiError::bad_request("Unknown format"))
+        })
+        .transpose()?;
+    if params.w == Some(0) || params.h == Some(0) {
+        return Err(ApiError::bad_request("Width and height must be at least 1"));
+    }
+
+    let model = find_media(&state, id).await?;
+    let original_format = MediaFormat::from_mime(&model.mime_type).unwrap_or(MediaFormat::Png);
+    let format = requested.unwrap_or(original_format);
+    let (width, height) = fit_within(
+        model.width as u32,
+        model.height as u32,
+        params.w.map(snap_size),
+        params.h.map(snap_size),
+    );
+
+    let missing = || ApiError::not_found("Media file is missing");
+    let original = || async {
+        let bytes = state
+            .storage
+            .get(&model.storage_key)
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(missing)?;
+        Ok::<_, ApiError>(image_response(&model.mime_type, bytes))
+    };
+    if format == original_format && (width, height) == (model.width as u32, model.height as u32) {
+        return original().await;
+    }
+
+    let variant_key = format!(
+        "{}/{}x{}.{}",
+        variants_prefix(model.id),
+        width,
+        height,
+        format.extension()
+    );
+    if let Some(bytes) = state.storage.get(&variant_key).await.map_err(storage_error)? {
+        return Ok(image_response(format.mime(), bytes));
+    }
+    let cached = state
+        .storage
+        .list(&variants_prefix(model.id))
+        .await
+        .map_err(storage_error)?;
+    if cached.len() >= MAX_VARIANTS {
+        return original().await;
+    }
+
+    let source = state
+        .storage
+        .get(&model.storage_key)
+        .await
+        .map_err(storage_error)?
+        .ok_or_else(missing)?;
+    let bytes = tokio::task::spawn_blocking(move || render_variant(&source, width, height, format))
+        .await
+        .map_err(|_| ApiError::internal("Image processing failed"))?
+        .map_err(|e| {
+            error!("Rendering media {} variant failed: {}", model.id, e);
+            ApiError::internal("Image processing failed")
+        })?;
+    // Serving matters more than caching; a failed write is retried next request
+    if let Err(e) = state.storage.put(&variant_key, &bytes).await {
+        warn!("Could not cache media variant {}: {}", variant_key, e);
+    }
+
+    Ok(image_response(format.mime(), bytes))
+}
+
+/// Removes the row, the original and every cached variant.
+#[utoipa::path(
+    delete,
+    path = "/api/media/{id}",
+    tag = "media",
+    params(("id" = i32, Path, description = "Media id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Media and variants deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed to delete this media", body = ErrorResponse),
+        (status = 404, description = "Media not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_media(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i32>,
+) -> ApiResult<StatusCode> {
+    let model = find_media(&state, id).await?;
+    if !auth_user.can_edit_owned_by(model.uploader_id.unwrap_or_default()) {
+        return Err(forbidden());
+    }
+    media::Entity::delete_by_id(model.id)
+        .exec(&state.db)
+        .await?;
+    for key in [model.storage_key, variants_prefix(model.id)] {
+        if let Err(e) = state.storage.delete(&key).await {
+            warn!("Could not remove media file {}: {}", key, e);
+        }
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,5 +1,57 @@
+pub mod analytics;
 pub mod auth;
 pub mod blogs;
+pub mod comments;
+pub mod feeds;
+pub mod media;
+pub mod revisions;
+pub mod search;
+pub mod sitemap;
+pub mod tags;
 
+pub use analytics::*;
 pub use auth::*;
-pub use blogs::*;
\ No newline at end of file
+pub use blogs::*;
+pub use comments::*;
+pub use feeds::*;
+pub use media::*;
+pub use revisions::*;
+pub use search::*;
+pub use sitemap::*;
+pub use tags::*;
+
+use axum::http::{header, HeaderMap};
+use std::net::{IpAddr, SocketAddr};
+use crate::config::Config;
+
+pub(crate) use crate::error::{ApiError, ApiResult, ErrorResponse};
+
+/// Origin for absolute links back to this server: `Config::public_url`, or in
+/// development the scheme and host the request came in on. The proxy's
+/// `X-Forwarded-Proto` and `X-Forwarded-Host` are only used with
+/// `Config::trust_proxy`.
+pub(crate) fn request_origin(headers: &HeaderMap, config: &Config) -> String {
+    if let Some(url) = &config.public_url {
+        return url.clone();
+    }
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let forwarded = |name: &str| header(name).filter(|_| config.trust_proxy);
+    let scheme = forwarded("x-forwarded-proto").unwrap_or("http");
+    let host = forwarded("x-forwarded-host")
+        .or_else(|| header(header::HOST.as_str()))
+        .unwrap_or("127.0.0.1:3000");
+    format!("{}://{}", scheme, host)
+}
+
+/// Address of the client, for view counting. With `Config::trust_proxy` it is
+/// the last `X-Forwarded-For` entry, the one our proxy appended; earlier
+/// entries come from the client and can be forged.
+pub(crate) fn client_ip(headers: &HeaderMap, peer: SocketAddr, config: &Config) -> IpAddr {
+    headers
+        .get("x-forwarded-for")
+        .filter(|_| config.trust_proxy)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.rsplit(',').next())
+        .and_then(|ip| ip.trim().parse().ok())
+        .unwrap_or_else(|| peer.ip())
+}
@@ -0,0 +1,328 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use super::{
+    blogs::{detail_response, find_blog, BlogDetailResponse},
+    ApiError, ApiResult, ErrorResponse,
+};
+use crate::{
+    auth::{roles::forbidden, AuthUser},
+    entities::{blog_revisions, blogs, users},
+    AppState,
+};
+
+/// Highest revision row id written so far. Revisions the triggers add after
+/// this point can then be credited to an editor with `attribute_revisions`.
+pub(crate) async fn revision_watermark<C>(db: &C) -> Result<i32, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let max: Option<Option<i32>> = blog_revisions::Entity::find()
+        .select_only()
+        .column_as(blog_revisions::Column::Id.max(), "id")
+        .into_tuple()
+        .one(db)
+        .await?;
+    Ok(max.flatten().unwrap_or(0))
+}
+
+pub(crate) async fn attribute_revisions<C>(db: &C, watermark: i32, editor_id: i32) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    blog_revisions::Entity::update_many()
+        .col_expr(blog_revisions::Column::EditorId, Expr::value(editor_id))
+        .filter(blog_revisions::Column::Id.gt(watermark))
+        .filter(blog_revisions::Column::EditorId.is_null())
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevisionEditor {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevisionSummary {
+    pub revision: i32,
+    pub title: String,
+    pub status: Option<String>,
+    /// `None` for changes made by the scheduler or before history was kept
+    pub editor: Option<RevisionEditor>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevisionDetail {
+    #[serde(flatten)]
+    pub summary: RevisionSummary,
+    pub excerpt: Option<String>,
+    pub content: String,
+}
+
+fn summary(revision: &blog_revisions::Model, editor: Option<users::Model>) -> RevisionSummary {
+    RevisionSummary {
+        revision: revision.revision,
+        title: revision.title.clone(),
+        status: revision.status.clone(),
+        editor: editor.map(|u| RevisionEditor { id: u.id, name: u.name }),
+        created_at: revision.created_at,
+    }
+}
+
+/// The post, provided the caller may edit it; its history is as sensitive as its drafts.
+async fn editable_blog(state: &AppState, auth_user: &AuthUser, id: i32) -> ApiResult<blogs::Model> {
+    let blog = find_blog(state, id).await?;
+    if !auth_user.can_edit_owned_by(blog.author_id) {
+        return Err(forbidden());
+    }
+    Ok(blog)
+}
+
+async fn find_revision(
+    state: &AppState,
+    blog_id: i32,
+    revision: i32,
+) -> ApiResult<(blog_revisions::Model, Option<users::Model>)> {
+    blog_revisions::Entity::find()
+        .filter(blog_revisions::Column::BlogId.eq(blog_id))
+        .filter(blog_revisions::Column::Revision.eq(revision))
+        .find_also_related(users::Entity)
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::not_found("Revision not found"))
+}
+
+/// Newest first.
+#[utoipa::path(
+    get,
+    path = "/api/blogs/{slug}/revisions",
+    tag = "revisions",
+    params(("slug" = i32, Path, description = "Post id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Revisions of the post", body = Vec<RevisionSummary>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed to edit the post", body = ErrorResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_revisions(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i32>,
+) -> ApiResult<Json<Vec<RevisionSummary>>> {
+    let blog = editable_blog(&state, &auth_user, id).await?;
+    let revisions = blog_revisions::Entity::find()
+        .filter(blog_revisions::Column::BlogId.eq(blog.id))
+        .order_by_desc(blog_revisions::Column::Revision)
+        .find_also_related(users::Entity)
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(
+        revisions
+            .iter()
+            .map(|(revision, editor)| summary(revision, editor.clone()))
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/blogs/{slug}/revisions/{revision}",
+    tag = "revisions",
+    params(("slug" = i32, Path, description = "Post id"), ("revision" = i32, Path, description = "Revision number")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The revision", body = RevisionDetail),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed to edit the post", body = ErrorResponse),
+        (status = 404, description = "Post or revision not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_revision(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((id, revision)): Path<(i32, i32)>,
+) -> ApiResult<Json<RevisionDetail>> {
+    let blog = editable_blog(&state, &auth_user, id).await?;
+    let (revision, editor) = find_revision(&state, blog.id, revision).await?;
+
+    Ok(Json(RevisionDetail {
+        summary: summary(&revision, editor),
+        excerpt: revision.excerpt,
+        content: revision.content,
+    }))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DiffParams {
+    pub from: i32,
+    pub to: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FieldChange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl FieldChange {
+    fn between(from: Option<&str>, to: Option<&str>) -> Option<Self> {
+        (from != to).then(|| FieldChange {
+            from: from.map(str::to_string),
+            to: to.map(str::to_string),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LineOp {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DiffLine {
+    pub op: LineOp,
+    /// 1-based line number in `from`, absent for added lines
+    pub old_line: Option<usize>,
+    /// 1-based line number in `to`, absent for removed lines
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+/// Single-line fields are reported as before/after pairs when they differ;
+/// `content` as a full line-level diff.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevisionDiff {
+    pub from: RevisionSummary,
+    pub to: RevisionSummary,
+    pub title: Option<FieldChange>,
+    pub excerpt: Option<FieldChange>,
+    pub status: Option<FieldChange>,
+    pub content: Vec<DiffLine>,
+}
+
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let (mut old_line, mut new_line) = (0, 0);
+    diff::lines(old, new)
+        .into_iter()
+        .map(|line| match line {
+            diff::Result::Both(text, _) => {
+                old_line += 1;
+                new_line += 1;
+                DiffLine {
+                    op: LineOp::Context,
+                    old_line: Some(old_line),
+                    new_line: Some(new_line),
+                    text: text.to_string(),
+                }
+            }
+            diff::Result::Left(text) => {
+                old_line += 1;
+                DiffLine {
+                    op: LineOp::Removed,
+                    old_line: Some(old_line),
+                    new_line: None,
+                    text: text.to_string(),
+                }
+            }
+            diff::Result::Right(text) => {
+                new_line += 1;
+                DiffLine {
+                    op: LineOp::Added,
+                    old_line: None,
+                    new_line: Some(new_line),
+                    text: text.to_string(),
+                }
+            }
+        })
+        .collect()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/blogs/{slug}/revisions/diff",
+    tag = "revisions",
+    params(("slug" = i32, Path, description = "Post id"), DiffParams),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Changes between the two revisions", body = RevisionDiff),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed to edit the post", body = ErrorResponse),
+        (status = 404, description = "Post or revision not found", body = ErrorResponse),
+    )
+)]
+pub async fn diff_revisions(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i32>,
+    Query(params): Query<DiffParams>,
+) -> ApiResult<Json<RevisionDiff>> {
+    let blog = editable_blog(&state, &auth_user, id).await?;
+    let (from, from_editor) = find_revision(&state, blog.id, params.from).await?;
+    let (to, to_editor) = find_revision(&state, blog.id, params.to).await?;
+
+    Ok(Json(RevisionDiff {
+        title: FieldChange::between(Some(&from.title), Some(&to.title)),
+        excerpt: FieldChange::between(from.excerpt.as_deref(), to.excerpt.as_deref()),
+        status: FieldChange::between(from.status.as_deref(), to.status.as_deref()),
+        content: line_diff(&from.content, &to.content),
+        from: summary(&from, from_editor),
+        to: summary(&to, to_editor),
+    }))
+}
+
+/// Copies an old revision's title, excerpt and content back onto the post,
+/// which records it as the newest revision. The current status is kept so a
+/// restore never publishes or unpublishes anything.
+#[utoipa::path(
+    post,
+    path = "/api/blogs/{slug}/revisions/{revision}/restore",
+    tag = "revisions",
+    params(("slug" = i32, Path, description = "Post id"), ("revision" = i32, Path, description = "Revision number")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The post with the revision restored", body = BlogDetailResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed to edit the post", body = ErrorResponse),
+        (status = 404, description = "Post or revision not found", body = ErrorResponse),
+    )
+)]
+pub async fn restore_revision(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((id, revision)): Path<(i32, i32)>,
+) -> ApiResult<Json<BlogDetailResponse>> {
+    let blog = editable_blog(&state, &auth_user, id).await?;
+    let (revision, _) = find_revision(&state, blog.id, revision).await?;
+
+    let mut model: blogs::ActiveModel = blog.into();
+    model.title = Set(revision.title);
+    model.excerpt = Set(revision.excerpt);
+    model.content = Set(revision.content);
+
+    let txn = state.db.begin().await?;
+    let watermark = revision_watermark(&txn).await?;
+    let model = model.update(&txn).await?;
+    attribute_revisions(&txn, watermark, auth_user.id)
+        .await?;
+    txn.commit().await?;
+
+    detail_response(&state, model).await.map(Json)
+}
@@ -0,0 +1,182 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use sea_orm::{
+    sea_query::{Alias, Expr, Order, Query as SelectQuery, SelectStatement},
+    ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use super::{blogs::{list_items, BlogListItem}, ApiResult};
+use crate::{entities::blogs, AppState};
+
+// Control characters FTS5 wraps around matches; the index reads post text
+// with them removed, see the `create_blogs_fts` migration
+const MATCH_START: char = '\u{2}';
+const MATCH_END: char = '\u{3}';
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SearchParams {
+    /// Words to look for in titles, excerpts and content
+    pub q: Option<String>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BlogSearchItem {
+    #[serde(flatten)]
+    pub item: BlogListItem,
+    /// HTML-escaped excerpt with matches wrapped in `<mark>`
+    pub snippet: String,
+}
+
+/// Same envelope as `BlogsListResponse`, with a snippet on every item.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BlogSearchResponse {
+    pub items: Vec<BlogSearchItem>,
+    pub page: u64,
+    pub per_page: u64,
+    pub total: u64,
+    pub total_pages: u64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct SearchHit {
+    id: i32,
+    snippet: String,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct SearchCount {
+    total: i64,
+}
+
+/// Turns free text into an FTS5 query: every word must match, and the last one
+/// may be a prefix so results update while the reader is still typing.
+fn match_expression(q: &str) -> Option<String> {
+    let terms: Vec<&str> = q
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .take(10)
+        .collect();
+    let (last, rest) = terms.split_last()?;
+    let mut expr: Vec<String> = rest.iter().map(|t| format!("\"{}\"", t)).collect();
+    expr.push(format!("\"{}\"*", last));
+    Some(expr.join(" "))
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            MATCH_START => out.push_str("<mark>"),
+            MATCH_END => out.push_str("</mark>"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Posts matching the FTS5 query `expr`, joined to their index rows. Which
+/// posts are visible comes from `blogs::live()`, as for every other listing.
+fn matching(expr: &str) -> SelectStatement {
+    let fts = Alias::new("blogs_fts");
+    SelectQuery::select()
+        .from(blogs::Entity)
+        .inner_join(
+            fts.clone(),
+            Expr::col((fts, Alias::new("rowid"))).equals((blogs::Entity, blogs::Column::Id)),
+        )
+        .and_where(Expr::cust_with_values("blogs_fts MATCH ?", [expr]))
+        .cond_where(blogs::live())
+        .to_owned()
+}
+
+/// Full-text search over published posts, best matches first.
+#[utoipa::path(
+    get,
+    path = "/api/blogs/search",
+    tag = "blogs",
+    params(SearchParams),
+    responses((status = 200, description = "One page of matching posts", body = BlogSearchResponse))
+)]
+pub async fn search_blogs(
+    State(state): State<AppState>,
+    Query(params): Query<SearchParams>,
+) -> ApiResult<Json<BlogSearchResponse>> {
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(10).clamp(1, 100);
+    let empty = BlogSearchResponse {
+        items: Vec::new(),
+        page,
+        per_page,
+        total: 0,
+        total_pages: 0,
+    };
+
+    let Some(expr) = params.q.as_deref().and_then(match_expression) else {
+        return Ok(Json(empty));
+    };
+
+    let backend = state.db.get_database_backend();
+    let count = SearchCount::find_by_statement(backend.build(
+        matching(&expr).expr_as(Expr::cust("COUNT(*)"), Alias::new("total")),
+    ))
+    .one(&state.db)
+    .await?;
+    let total = count.map_or(0, |c| c.total as u64);
+    if total == 0 {
+        return Ok(Json(empty));
+    }
+
+    // Title matches outrank excerpt matches, which outrank body matches
+    let hits = SearchHit::find_by_statement(backend.build(
+        matching(&expr)
+            .expr_as(Expr::col((blogs::Entity, blogs::Column::Id)), Alias::new("id"))
+            .expr_as(
+                Expr::cust("snippet(blogs_fts, -1, char(2), char(3), '…', 16)"),
+                Alias::new("snippet"),
+            )
+            .order_by_expr(Expr::cust("bm25(blogs_fts, 10.0, 5.0, 1.0)"), Order::Asc)
+            .limit(per_page)
+            .offset((page - 1) * per_page),
+    ))
+    .all(&state.db)
+    .await?;
+
+    let ids: Vec<i32> = hits.iter().map(|h| h.id).collect();
+    let mut models = blogs::Entity::find()
+        .filter(blogs::Column::Id.is_in(ids.clone()))
+        .all(&state.db)
+        .await?;
+    // Restore rank order, which `IN` does not preserve
+    models.sort_by_key(|m| ids.iter().position(|id| *id == m.id));
+
+    let items = list_items(&state, models)
+        .await?
+        .into_iter()
+        .map(|item| {
+            let snippet = hits
+                .iter()
+                .find(|hit| hit.id == item.id)
+                .map(|hit| escape_html(&hit.snippet))
+                .unwrap_or_default();
+            BlogSearchItem { item, snippet }
+        })
+        .collect();
+
+    Ok(Json(BlogSearchResponse {
+        items,
+        page,
+        per_page,
+        total,
+        total_pages: total.div_ceil(per_page),
+    }))
+}
@@ -0,0 +1,195 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap},
+    response::IntoResponse,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use sea_orm::{EntityTrait, FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect};
+use shared::routes::STATIC_PAGES;
+use super::{request_origin, ApiError, ApiResult, ErrorResponse};
+use crate::{entities::blogs, AppState};
+
+/// Protocol limit on URLs per sitemap file; beyond it `/sitemap.xml` becomes an index.
+const MAX_URLS: u64 = 50_000;
+
+const XML_CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+#[derive(Debug, FromQueryResult)]
+struct SitemapPost {
+    slug: String,
+    updated_at: Option<DateTime<Utc>>,
+    published_at: Option<DateTime<Utc>>,
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn url_entry(loc: &str, lastmod: Option<DateTime<Utc>>) -> String {
+    match lastmod {
+        Some(at) => format!(
+            "<url><loc>{}</loc><lastmod>{}</lastmod></url>",
+            xml_escape(loc),
+            at.to_rfc3339_opts(SecondsFormat::Secs, true)
+        ),
+        None => format!("<url><loc>{}</loc></url>", xml_escape(loc)),
+    }
+}
+
+fn urlset(entries: Vec<String>) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">{}</urlset>\n",
+        entries.concat()
+    )
+}
+
+/// Published posts in a stable order, so a page of the index always lists
+/// the same posts.
+async fn post_urls(state: &AppState, offset: u64, limit: u64) -> ApiResult<Vec<String>> {
+    let site = &state.config.site_url;
+    let rows = blogs::Entity::find()
+        .select_only()
+        .column(blogs::Column::Slug)
+        .column(blogs::Column::UpdatedAt)
+        .column(blogs::Column::PublishedAt)
+        .filter(blogs::live())
+        .order_by_asc(blogs::Column::Id)
+        .offset(offset)
+        .limit(limit)
+        .into_model::<SitemapPost>()
+        .all(&state.db)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|post| {
+            url_entry(
+                &format!("{}/blogs/{}", site, post.slug),
+                post.updated_at.or(post.published_at),
+            )
+        })
+        .collect())
+}
+
+async fn published_count(state: &AppState) -> ApiResult<u64> {
+    blogs::Entity::find()
+        .filter(blogs::live())
+        .count(&state.db)
+        .await
+        .map_err(ApiError::from)
+}
+
+/// A single sitemap while everything fits in one file, otherwise a sitemap
+/// index pointing at `/sitemaps/posts-{n}.xml`.
+#[utoipa::path(
+    get,
+    path = "/sitemap.xml",
+    tag = "crawlers",
+    responses((status = 200, description = "Sitemap or sitemap index", body = String, content_type = "application/xml"))
+)]
+pub async fn sitemap(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> ApiResult<impl IntoResponse> {
+    let posts = published_count(&state).await?;
+    let site = &state.config.site_url;
+    let static_pages = STATIC_PAGES.len() as u64;
+
+    let body = if posts + static_pages <= MAX_URLS {
+        let mut entries: Vec<String> = STATIC_PAGES
+            .iter()
+            .map(|path| url_entry(&format!("{}{}", site, path), None))
+            .collect();
+        entries.extend(post_urls(&state, 0, MAX_URLS).await?);
+        urlset(entries)
+    } else {
+        let origin = request_origin(&headers, &state.config);
+        let files = (posts + static_pages).div_ceil(MAX_URLS);
+        let entries: String = (1..=files)
+            .map(|n| {
+                format!(
+                    "<sitemap><loc>{}</loc></sitemap>",
+                    xml_escape(&format!("{}/sitemaps/posts-{}.xml", origin, n))
+                )
+            })
+            .collect();
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">{}</sitemapindex>\n",
+            entries
+        )
+    };
+
+    Ok(([(header::CONTENT_TYPE, XML_CONTENT_TYPE)], body))
+}
+
+/// One file of the sitemap index. The first file also carries the static pages.
+#[utoipa::path(
+    get,
+    path = "/sitemaps/{file}",
+    tag = "crawlers",
+    params(("file" = String, Path, description = "`posts-{n}.xml`")),
+    responses(
+        (status = 200, description = "One sitemap file", body = String, content_type = "application/xml"),
+        (status = 404, description = "No such file", body = ErrorResponse),
+    )
+)]
+pub async fn sitemap_page(
+    State(state): State<AppState>,
+    Path(file): Path<String>,
+) -> ApiResult<impl IntoResponse> {
+    let not_found = || ApiError::not_found("Sitemap not found");
+    let page: u64 = file
+        .strip_prefix("posts-")
+        .and_then(|f| f.strip_suffix(".xml"))
+        .and_then(|n| n.parse().ok())
+        .filter(|n| *n >= 1)
+        .ok_or_else(not_found)?;
+
+    let site = &state.config.site_url;
+    let static_pages = STATIC_PAGES.len() as u64;
+    let mut entries = Vec::new();
+    let (offset, limit) = if page == 1 {
+        entries.extend(
+            STATIC_PAGES
+                .iter()
+                .map(|path| url_entry(&format!("{}{}", site, path), None)),
+        );
+        (0, MAX_URLS - static_pages)
+    } else {
+        ((page - 1) * MAX_URLS - static_pages, MAX_URLS)
+    };
+
+    let posts = post_urls(&state, offset, limit).await?;
+    if posts.is_empty() && page > 1 {
+        return Err(not_found());
+    }
+    entries.extend(posts);
+
+    Ok(([(header::CONTENT_TYPE, XML_CONTENT_TYPE)], urlset(entries)))
+}
+
+/// Disallowed paths come from `Config::robots_disallow` (empty allows
+/// everything, `/` blocks everything).
+#[utoipa::path(
+    get,
+    path = "/robots.txt",
+    tag = "crawlers",
+    responses((status = 200, description = "Crawler rules", body = String, content_type = "text/plain"))
+)]
+pub async fn robots_txt(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
+    let rules = &state.config.robots_disallow;
+    let mut body = String::from("User-agent: *\n");
+    if rules.is_empty() {
+        body.push_str("Disallow:\n");
+    }
+    for path in rules {
+        body.push_str(&format!("Disallow: {}\n", path));
+    }
+    body.push_str(&format!("\nSitemap: {}/sitemap.xml\n", request_origin(&headers, &state.config)));
+
+    ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], body)
+}
@@ -0,0 +1,121 @@
+use axum::{extract::State, Json};
+use sea_orm::{
+    sea_query::{Expr, Order},
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, JoinType, ModelTrait,
+    QueryFilter, QueryOrder, QuerySelect, RelationTrait, Set,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use super::ApiResult;
+pub use shared::blogs::TagResponse;
+use crate::{
+    entities::{blog_tags, blogs, tags},
+    slug::slugify,
+    AppState,
+};
+
+impl From<tags::Model> for TagResponse {
+    fn from(tag: tags::Model) -> Self {
+        Self {
+            name: tag.name,
+            slug: tag.slug,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, FromQueryResult, ToSchema)]
+pub struct TagWithCount {
+    pub name: String,
+    pub slug: String,
+    pub post_count: i64,
+}
+
+/// Tags that have at least one published post, most used first.
+#[utoipa::path(
+    get,
+    path = "/api/tags",
+    tag = "tags",
+    responses((status = 200, description = "Tags in use", body = Vec<TagWithCount>))
+)]
+pub async fn list_tags(State(state): State<AppState>) -> ApiResult<Json<Vec<TagWithCount>>> {
+    let tags = tags::Entity::find()
+        .select_only()
+        .column(tags::Column::Name)
+        .column(tags::Column::Slug)
+        .column_as(blogs::Column::Id.count(), "post_count")
+        .join(JoinType::InnerJoin, tags::Relation::BlogTags.def())
+        .join(JoinType::InnerJoin, blog_tags::Relation::Blogs.def())
+        .filter(blogs::live())
+        .group_by(tags::Column::Id)
+        .order_by(Expr::cust("post_count"), Order::Desc)
+        .order_by_asc(tags::Column::Name)
+        .into_model::<TagWithCount>()
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(tags))
+}
+
+/// Replaces the tags on a post, creating any tag names not seen before.
+pub(crate) async fn set_blog_tags<C>(
+    db: &C,
+    blog_id: i32,
+    names: &[String],
+) -> Result<Vec<tags::Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    blog_tags::Entity::delete_many()
+        .filter(blog_tags::Column::BlogId.eq(blog_id))
+        .exec(db)
+        .await?;
+
+    let mut attached: Vec<tags::Model> = Vec::with_capacity(names.len());
+    for name in names {
+        let name = name.trim();
+        let slug = slugify(name);
+        if slug.is_empty() || attached.iter().any(|t| t.slug == slug) {
+            continue;
+        }
+
+        let tag = match tags::Entity::find()
+            .filter(tags::Column::Slug.eq(&slug))
+            .one(db)
+            .await?
+        {
+            Some(tag) => tag,
+            None => {
+                tags::Entity::insert(tags::ActiveModel {
+                    name: Set(name.to_string()),
+                    slug: Set(slug),
+                    created_at: Set(Some(chrono::Utc::now())),
+                    ..Default::default()
+                })
+                .exec_with_returning(db)
+                .await?
+            }
+        };
+
+        blog_tags::Entity::insert(blog_tags::ActiveModel {
+            blog_id: Set(blog_id),
+            tag_id: Set(tag.id),
+        })
+        .exec_without_returning(db)
+        .await?;
+        attached.push(tag);
+    }
+
+    Ok(attached)
+}
+
+pub(crate) async fn tags_for_blog<C>(db: &C, blog: &blogs::Model) -> Result<Vec<TagResponse>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let tags = blog
+        .find_related(tags::Entity)
+        .order_by_asc(tags::Column::Name)
+        .all(db)
+        .await?;
+    Ok(tags.into_iter().map(TagResponse::from).collect())
+}
@@ -1,2 +1,4 @@
+pub mod openapi;
+pub mod request_id;
 pub mod routes;
-pub mod handlers;
\ No newline at end of file
+pub mod handlers;
@@ -0,0 +1,99 @@
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+use crate::{admin, api::handlers, error::ErrorResponse};
+
+/// Where the document is served; Swagger UI is mounted at `DOCS_PATH`.
+pub const SPEC_PATH: &str = "/api/openapi.json";
+pub const DOCS_PATH: &str = "/api/docs";
+
+/// OpenAPI 3 description of every route in `routes::create_routes`. Add new
+/// handlers to `paths` as well; `tests/openapi_tests.rs` fails otherwise.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Blog API", description = "REST API of the blog backend. Errors share the `ErrorResponse` envelope."),
+    paths(
+        super::routes::health_check,
+        super::routes::api_status,
+        handlers::auth::register,
+        handlers::auth::user_login,
+        handlers::auth::refresh_session,
+        handlers::auth::logout,
+        handlers::auth::logout_all,
+        handlers::auth::current_user,
+        handlers::auth::change_password,
+        admin::admin_panel_config,
+        admin::login::admin_login_page,
+        admin::graphql_playground,
+        admin::graphql_handler,
+        handlers::blogs::list_blogs,
+        handlers::blogs::create_blog,
+        handlers::search::search_blogs,
+        handlers::blogs::get_blog_by_slug,
+        handlers::blogs::replace_blog,
+        handlers::blogs::update_blog,
+        handlers::blogs::delete_blog,
+        handlers::blogs::create_preview_link,
+        handlers::revisions::list_revisions,
+        handlers::revisions::diff_revisions,
+        handlers::revisions::get_revision,
+        handlers::revisions::restore_revision,
+        handlers::comments::list_comments,
+        handlers::comments::create_comment,
+        handlers::tags::list_tags,
+        handlers::analytics::top_posts,
+        handlers::analytics::view_breakdown,
+        handlers::analytics::post_analytics,
+        handlers::comments::list_moderation_queue,
+        handlers::comments::moderate_comment,
+        handlers::comments::delete_comment,
+        handlers::media::upload_media,
+        handlers::media::delete_media,
+        handlers::media::serve_media,
+        handlers::feeds::rss_feed,
+        handlers::feeds::atom_feed,
+        handlers::feeds::json_feed,
+        handlers::feeds::tag_rss_feed,
+        handlers::feeds::tag_atom_feed,
+        handlers::feeds::tag_json_feed,
+        handlers::sitemap::sitemap,
+        handlers::sitemap::sitemap_page,
+        handlers::sitemap::robots_txt,
+    ),
+    components(schemas(ErrorResponse)),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "health"),
+        (name = "auth", description = "Accounts and session tokens"),
+        (name = "blogs", description = "Posts; reads are public, writes need a token"),
+        (name = "revisions", description = "Post history, for whoever may edit the post"),
+        (name = "comments"),
+        (name = "tags"),
+        (name = "analytics", description = "View statistics from the daily rollup"),
+        (name = "media", description = "Image uploads and resized variants"),
+        (name = "feeds", description = "RSS, Atom and JSON feeds of published posts"),
+        (name = "crawlers"),
+        (name = "admin"),
+        (name = "graphql", description = "Admin GraphQL API"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Declares the `Authorization: Bearer <token>` scheme operations refer to.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
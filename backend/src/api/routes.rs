@@ -1,38 +1,153 @@
 use axum::{
-    routing::{get, post, get_service},
+    extract::DefaultBodyLimit,
+    handler::Handler,
+    http::Method,
+    routing::{get_service, on, MethodFilter, MethodRouter},
     Router,
     Json,
 };
 use serde_json::{json, Value};
 use tower::ServiceBuilder;
 use tower_http::{
-    cors::{CorsLayer, Any},
+    cors::{AllowOrigin, CorsLayer, Any},
     services::{ServeDir, ServeFile},
 };
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 use chrono;
-use crate::{admin, api::handlers, AppState};
+use crate::{
+    admin,
+    api::{handlers, openapi::{ApiDoc, DOCS_PATH, SPEC_PATH}, request_id},
+    config::Config,
+    AppState,
+};
 
-pub fn create_routes(state: AppState) -> Router {
-    Router::new()
+/// Any origin unless `CORS_ORIGINS` lists some.
+fn cors_layer(origins: &[String]) -> CorsLayer {
+    let allow_origin = if origins.is_empty() {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(origins.iter().filter_map(|origin| origin.parse().ok()))
+    };
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(Any)
+        .allow_headers(Any)
+}
+
+/// One operation of the API: a method on a path and the handler serving it.
+pub struct ApiRoute {
+    pub method: Method,
+    pub path: &'static str,
+    handler: MethodRouter<AppState>,
+}
+
+impl ApiRoute {
+    fn layer(mut self, limit: DefaultBodyLimit) -> Self {
+        self.handler = self.handler.layer(limit);
+        self
+    }
+}
+
+fn op<H, T>(method: Method, path: &'static str, handler: H) -> ApiRoute
+where
+    H: Handler<T, AppState>,
+    T: 'static,
+{
+    let filter = MethodFilter::try_from(method.clone()).expect("route with a standard method");
+    ApiRoute {
+        method,
+        path,
+        handler: on(filter, handler),
+    }
+}
+
+/// Every API operation `create_routes` mounts. Keeping them in one table lets
+/// the OpenAPI tests check each is documented.
+pub fn api_routes(config: &Config) -> Vec<ApiRoute> {
+    vec![
         // Health endpoints
-        .route("/health", get(health_check))
-        .route("/api/v1/status", get(api_status))
-        
+        op(Method::GET, "/health", health_check),
+        op(Method::GET, "/api/v1/status", api_status),
+
         // Authentication endpoints
-        .route("/api/auth/login", post(handlers::user_login))
-        .route("/api/user/current", get(handlers::current_user))
-        
+        op(Method::POST, "/api/auth/register", handlers::register),
+        op(Method::POST, "/api/auth/login", handlers::user_login),
+        op(Method::POST, "/api/auth/refresh", handlers::refresh_session),
+        op(Method::POST, "/api/auth/logout", handlers::logout),
+        op(Method::POST, "/api/auth/logout-all", handlers::logout_all),
+        op(Method::GET, "/api/user/current", handlers::current_user),
+        op(Method::PUT, "/api/user/password", handlers::change_password),
+
         // Admin panel
-        .route("/api/admin/config", get(admin::admin_panel_config))
-        .route("/login", get(admin::admin_login_page))
-        
+        op(Method::GET, "/api/admin/config", admin::admin_panel_config),
+        op(Method::GET, "/login", admin::admin_login_page),
+
         // GraphQL endpoints
-        .route("/api/graphql", get(admin::graphql_playground))
-        .route("/api/graphql", post(admin::graphql_handler))
+        op(Method::GET, "/api/graphql", admin::graphql_playground),
+        op(Method::POST, "/api/graphql", admin::graphql_handler),
+
+        // Blog REST endpoints (reads are public, writes require a Bearer token).
+        // Writes address a post by its numeric id through the same path segment.
+        op(Method::GET, "/api/blogs", handlers::list_blogs),
+        op(Method::POST, "/api/blogs", handlers::create_blog),
+        op(Method::GET, "/api/blogs/search", handlers::search_blogs),
+        op(Method::GET, "/api/blogs/{slug}", handlers::get_blog_by_slug),
+        op(Method::PUT, "/api/blogs/{slug}", handlers::replace_blog),
+        op(Method::PATCH, "/api/blogs/{slug}", handlers::update_blog),
+        op(Method::DELETE, "/api/blogs/{slug}", handlers::delete_blog),
+        op(Method::POST, "/api/blogs/{slug}/preview", handlers::create_preview_link),
+        op(Method::GET, "/api/blogs/{slug}/revisions", handlers::list_revisions),
+        op(Method::GET, "/api/blogs/{slug}/revisions/diff", handlers::diff_revisions),
+        op(Method::GET, "/api/blogs/{slug}/revisions/{revision}", handlers::get_revision),
+        op(
+            Method::POST,
+            "/api/blogs/{slug}/revisions/{revision}/restore",
+            handlers::restore_revision,
+        ),
+        op(Method::GET, "/api/blogs/{slug}/comments", handlers::list_comments),
+        op(Method::POST, "/api/blogs/{slug}/comments", handlers::create_comment),
+        op(Method::GET, "/api/tags", handlers::list_tags),
+
+        // View analytics (authenticated)
+        op(Method::GET, "/api/analytics/posts/top", handlers::top_posts),
+        op(Method::GET, "/api/analytics/breakdown", handlers::view_breakdown),
+        op(Method::GET, "/api/analytics/posts/{id}", handlers::post_analytics),
+
+        // Comment moderation (admin)
+        op(Method::GET, "/api/comments", handlers::list_moderation_queue),
+        op(Method::PATCH, "/api/comments/{id}", handlers::moderate_comment),
+        op(Method::DELETE, "/api/comments/{id}", handlers::delete_comment),
+
+        // Media library: uploads need a Bearer token, files and variants are public
+        op(Method::POST, "/api/media", handlers::upload_media)
+            .layer(handlers::upload_body_limit(config)),
+        op(Method::DELETE, "/api/media/{id}", handlers::delete_media),
+        op(Method::GET, "/media/{id}", handlers::serve_media),
+
+        // Syndication feeds of published posts, site-wide and per tag
+        op(Method::GET, "/feed.xml", handlers::rss_feed),
+        op(Method::GET, "/atom.xml", handlers::atom_feed),
+        op(Method::GET, "/feed.json", handlers::json_feed),
+        op(Method::GET, "/tags/{tag}/feed.xml", handlers::tag_rss_feed),
+        op(Method::GET, "/tags/{tag}/atom.xml", handlers::tag_atom_feed),
+        op(Method::GET, "/tags/{tag}/feed.json", handlers::tag_json_feed),
+
+        // Crawler support
+        op(Method::GET, "/sitemap.xml", handlers::sitemap),
+        op(Method::GET, "/sitemaps/{file}", handlers::sitemap_page),
+        op(Method::GET, "/robots.txt", handlers::robots_txt),
+    ]
+}
+
+pub fn create_routes(state: AppState) -> Router {
+    let cors = cors_layer(&state.config.cors_origins);
+    api_routes(&state.config)
+        .into_iter()
+        .fold(Router::new(), |router, route| router.route(route.path, route.handler))
         
-        // Blog read-only REST endpoints
-        .route("/api/blogs", get(handlers::list_blogs))
-        .route("/api/blogs/{slug}", get(handlers::get_blog_by_slug))
+        // OpenAPI document of the routes above, and Swagger UI to try them
+        .merge(SwaggerUi::new(DOCS_PATH).url(SPEC_PATH, ApiDoc::openapi()))
         
         // Serve admin panel static files
         .nest_service(
@@ -46,24 +161,32 @@ pub fn create_routes(state: AppState) -> Router {
         // Apply middleware
         .layer(
             ServiceBuilder::new()
-                .layer(
-                    CorsLayer::new()
-                        .allow_origin(Any)
-                        .allow_methods(Any)
-                        .allow_headers(Any)
-                )
+                .layer(axum::middleware::from_fn(request_id::assign))
+                .layer(cors)
         )
         .with_state(state)
 }
 
-async fn health_check() -> Json<Value> {
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "The server is up", body = serde_json::Value))
+)]
+pub(crate) async fn health_check() -> Json<Value> {
     Json(json!({
         "status": "healthy",
         "timestamp": chrono::Utc::now()
     }))
 }
 
-async fn api_status() -> Json<Value> {
+#[utoipa::path(
+    get,
+    path = "/api/v1/status",
+    tag = "health",
+    responses((status = 200, description = "Service name, version and status", body = serde_json::Value))
+)]
+pub(crate) async fn api_status() -> Json<Value> {
     Json(json!({
         "service": "backend",
         "version": "0.1.0",
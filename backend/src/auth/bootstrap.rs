@@ -0,0 +1,14 @@
+use sea_orm::{sea_query::Expr, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use super::Role;
+use crate::entities::{users, Users};
+
+/// Promotes the account with `email`, from `ADMIN_EMAIL`; returns whether
+/// such an account exists.
+pub async fn promote(db: &DatabaseConnection, email: &str) -> Result<bool, DbErr> {
+    let result = Users::update_many()
+        .col_expr(users::Column::Role, Expr::value(Role::Admin.to_string()))
+        .filter(users::Column::Email.eq(email))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
@@ -1,54 +1,77 @@
-use axum::{
-    http::{HeaderMap, StatusCode},
+pub mod bootstrap;
+pub mod preview;
+pub mod roles;
+pub mod sessions;
+
+pub use roles::{AuthUser, Role};
+pub use shared::auth::{
+    ChangePasswordRequest, LoginRequest, LoginResponse, RefreshRequest, RegisterRequest,
+    UserResponse,
 };
+
+use axum::http::HeaderMap;
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
+use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
-use std::env;
+use crate::{config::Config, error::ApiError};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
     pub sub: String, // Subject (user ID)
+    #[serde(default)]
+    pub role: Role,  // Role at the time the token was issued
     pub exp: usize,  // Expiration time
+    pub jti: String, // Session the token belongs to, see `sessions`
 }
 
-#[derive(Deserialize)]
-pub struct LoginRequest {
-    pub email: String,
-    pub password: String,
-}
+/// A bcrypt hash, at the default cost, of no one's password. It is checked
+/// when an account has no hash so that rejecting an unknown email takes as
+/// long as rejecting a wrong password.
+const DUMMY_HASH: &str = "$2b$12$z5es0xuCy1y/hWPjEQDHa.PzfQMMLOqcs9wJnccQCOOFJEOYDSGHe";
 
-#[derive(Serialize)]
-pub struct LoginResponse {
-    pub token: String,
-    pub user: UserResponse,
+/// bcrypt is deliberately slow, so it runs on the blocking pool rather than
+/// stalling the async workers.
+pub async fn hash_password(password: &str) -> Result<String, bcrypt::BcryptError> {
+    let password = password.to_owned();
+    tokio::task::spawn_blocking(move || bcrypt::hash(password, bcrypt::DEFAULT_COST))
+        .await
+        .map_err(|e| bcrypt::BcryptError::Io(std::io::Error::other(e)))?
 }
 
-#[derive(Serialize)]
-pub struct UserResponse {
-    pub id: i32,
-    pub email: String,
-    pub name: String,
+/// Whether `password` matches `hash`. A missing or malformed hash never
+/// matches, but costs as much to check as a real one.
+pub async fn verify_password(password: &str, hash: Option<&str>) -> bool {
+    let (password, hash) = (password.to_owned(), hash.map(str::to_owned));
+    tokio::task::spawn_blocking(move || match hash {
+        Some(hash) => bcrypt::verify(password, &hash).unwrap_or(false),
+        None => {
+            let _ = bcrypt::verify(password, DUMMY_HASH);
+            false
+        }
+    })
+    .await
+    .unwrap_or(false)
 }
 
-pub fn create_jwt(user_id: &str) -> Result<String, jsonwebtoken::errors::Error> {
-    let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "default_secret".to_string());
+pub fn create_jwt(user_id: &str, role: Role, jti: &str, config: &Config) -> Result<String, jsonwebtoken::errors::Error> {
     let claims = Claims {
         sub: user_id.to_owned(),
-        exp: (chrono::Utc::now() + chrono::Duration::hours(24)).timestamp() as usize,
+        role,
+        exp: (chrono::Utc::now() + chrono::Duration::minutes(config.access_token_ttl_minutes)).timestamp() as usize,
+        jti: jti.to_owned(),
     };
 
     encode(
         &Header::default(),
         &claims,
-        &EncodingKey::from_secret(secret.as_ref()),
+        &EncodingKey::from_secret(config.jwt_secret.as_ref()),
     )
 }
 
-pub fn verify_jwt(token: &str) -> Result<TokenData<Claims>, jsonwebtoken::errors::Error> {
-    let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "default_secret".to_string());
+pub fn verify_jwt(token: &str, config: &Config) -> Result<TokenData<Claims>, jsonwebtoken::errors::Error> {
     decode::<Claims>(
         token,
-        &DecodingKey::from_secret(secret.as_ref()),
+        &DecodingKey::from_secret(config.jwt_secret.as_ref()),
         &Validation::default(),
     )
 }
@@ -57,20 +80,22 @@ pub fn extract_token_from_header(headers: &HeaderMap) -> Option<String> {
     headers
         .get("Authorization")
         .and_then(|header| header.to_str().ok())
-        .and_then(|header| {
-            if header.starts_with("Bearer ") {
-                Some(header[7..].to_string())
-            } else {
-                None
-            }
-        })
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .map(|token| token.to_string())
 }
 
-pub fn check_user_auth(headers: &HeaderMap) -> Result<Claims, (StatusCode, &'static str)> {
+/// Also rejects tokens whose session has since been revoked or refreshed.
+pub async fn check_user_auth(
+    headers: &HeaderMap,
+    config: &Config,
+    db: &DatabaseConnection,
+) -> Result<Claims, ApiError> {
     let token = extract_token_from_header(headers)
-        .ok_or((StatusCode::UNAUTHORIZED, "Missing authorization header"))?;
+        .ok_or(ApiError::unauthorized("Missing authorization header"))?;
 
-    verify_jwt(&token)
-        .map(|token_data| token_data.claims)
-        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token"))
+    let claims = verify_jwt(&token, config)?.claims;
+    if !sessions::is_active(db, &claims.jti).await? {
+        return Err(ApiError::unauthorized("Session has ended"));
+    }
+    Ok(claims)
 }
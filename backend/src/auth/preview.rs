@@ -0,0 +1,44 @@
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use crate::config::Config;
+
+/// Audience that keeps preview tokens and session tokens from being used for
+/// one another.
+const AUDIENCE: &str = "blog-preview";
+
+/// Grants read access to one unpublished post until `exp`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewClaims {
+    pub blog_id: i32,
+    pub aud: String,
+    pub exp: usize,
+}
+
+pub fn create_preview_token(
+    blog_id: i32,
+    expires_at: chrono::DateTime<chrono::Utc>,
+    config: &Config,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let claims = PreviewClaims {
+        blog_id,
+        aud: AUDIENCE.to_string(),
+        exp: expires_at.timestamp() as usize,
+    };
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config.jwt_secret.as_ref()),
+    )
+}
+
+/// Returns the post id the token was issued for.
+pub fn verify_preview_token(token: &str, config: &Config) -> Result<i32, jsonwebtoken::errors::Error> {
+    let mut validation = Validation::default();
+    validation.set_audience(&[AUDIENCE]);
+    decode::<PreviewClaims>(
+        token,
+        &DecodingKey::from_secret(config.jwt_secret.as_ref()),
+        &validation,
+    )
+    .map(|data| data.claims.blog_id)
+}
@@ -0,0 +1,87 @@
+use axum::{
+    extract::{FromRef, FromRequestParts, OptionalFromRequestParts},
+    http::{header, request::Parts},
+};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use super::Claims;
+use crate::{config::Config, error::ApiError};
+
+pub use shared::auth::Role;
+
+/// The caller identified by the Bearer token. Use as a handler argument to
+/// require authentication, then `require` a minimum role where needed.
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub id: i32,
+    pub role: Role,
+}
+
+impl AuthUser {
+    pub fn require(&self, min: Role) -> Result<(), ApiError> {
+        if self.role >= min {
+            Ok(())
+        } else {
+            Err(forbidden())
+        }
+    }
+
+    /// Authors may only touch their own rows; editors and admins may touch any.
+    pub fn can_edit_owned_by(&self, owner_id: i32) -> bool {
+        self.role >= Role::Editor || (self.role >= Role::Author && self.id == owner_id)
+    }
+}
+
+pub fn forbidden() -> ApiError {
+    ApiError::forbidden("Insufficient permissions")
+}
+
+impl<S> FromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+    Arc<Config>: FromRef<S>,
+    DatabaseConnection: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let config = Arc::<Config>::from_ref(state);
+        let db = DatabaseConnection::from_ref(state);
+        AuthUser::try_from(super::check_user_auth(&parts.headers, &config, &db).await?)
+    }
+}
+
+/// `Option<AuthUser>` is `None` for anonymous requests; a token that is sent
+/// but invalid is still rejected.
+impl<S> OptionalFromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+    Arc<Config>: FromRef<S>,
+    DatabaseConnection: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if !parts.headers.contains_key(header::AUTHORIZATION) {
+            return Ok(None);
+        }
+        <AuthUser as FromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
+
+impl TryFrom<Claims> for AuthUser {
+    type Error = ApiError;
+
+    fn try_from(claims: Claims) -> Result<Self, Self::Error> {
+        let id = claims
+            .sub
+            .parse()
+            .map_err(|_| ApiError::bad_request("Invalid user ID"))?;
+        Ok(AuthUser {
+            id,
+            role: claims.role,
+        })
+    }
+}
@@ -0,0 +1,158 @@
+use chrono::{Duration, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, Set,
+};
+use sha2::{Digest, Sha256};
+use super::{create_jwt, Role};
+use crate::{
+    config::Config,
+    entities::{sessions, users, Sessions, Users},
+    error::ApiError,
+};
+
+/// A freshly issued access and refresh token pair.
+#[derive(Debug)]
+pub struct Tokens {
+    pub access_token: String,
+    /// Seconds until `access_token` expires
+    pub expires_in: u64,
+    /// `<family>.<secret>`; only a hash of the secret is stored
+    pub refresh_token: String,
+}
+
+fn random_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+fn random_secret() -> String {
+    format!("{}{}", random_id(), random_id())
+}
+
+fn hash_secret(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+fn issue(user: &users::Model, family: &str, secret: &str, jti: &str, config: &Config) -> Result<Tokens, ApiError> {
+    let access_token = create_jwt(&user.id.to_string(), Role::from_db(&user.role), jti, config)
+        .map_err(|_| ApiError::internal("Failed to create token"))?;
+    Ok(Tokens {
+        access_token,
+        expires_in: (config.access_token_ttl_minutes * 60) as u64,
+        refresh_token: format!("{}.{}", family, secret),
+    })
+}
+
+/// Starts a session for a user who has just proven who they are. Sessions of
+/// any user that have expired are cleared out on the way.
+pub async fn start(db: &DatabaseConnection, user: &users::Model, config: &Config) -> Result<Tokens, ApiError> {
+    let now = Utc::now();
+    Sessions::delete_many()
+        .filter(sessions::Column::ExpiresAt.lt(now))
+        .exec(db)
+        .await?;
+
+    let (family, secret, jti) = (random_id(), random_secret(), random_id());
+    sessions::ActiveModel {
+        user_id: Set(user.id),
+        family: Set(family.clone()),
+        refresh_token_hash: Set(hash_secret(&secret)),
+        jti: Set(jti.clone()),
+        created_at: Set(now),
+        last_used_at: Set(now),
+        expires_at: Set(now + Duration::days(config.refresh_token_ttl_days)),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    issue(user, &family, &secret, &jti, config)
+}
+
+/// Exchanges a refresh token for a new pair, which invalidates it and the
+/// access token issued with it. A refresh token that was already exchanged
+/// has leaked or been replayed, so presenting it revokes the whole session.
+pub async fn refresh(
+    db: &DatabaseConnection,
+    refresh_token: &str,
+    config: &Config,
+) -> Result<(Tokens, users::Model), ApiError> {
+    let invalid = || ApiError::unauthorized("Invalid refresh token");
+    let (family, secret) = refresh_token.split_once('.').ok_or_else(invalid)?;
+    let session = Sessions::find()
+        .filter(sessions::Column::Family.eq(family))
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+
+    let now = Utc::now();
+    if session.expires_at < now {
+        Sessions::delete_by_id(session.id).exec(db).await?;
+        return Err(ApiError::unauthorized("Refresh token has expired"));
+    }
+
+    let presented = hash_secret(secret);
+    let (new_secret, jti) = (random_secret(), random_id());
+    // Matching on the old hash as well means only one of two concurrent
+    // refreshes with the same token wins; the other counts as reuse
+    let rotated = presented == session.refresh_token_hash
+        && Sessions::update_many()
+            .col_expr(sessions::Column::RefreshTokenHash, Expr::value(hash_secret(&new_secret)))
+            .col_expr(sessions::Column::Jti, Expr::value(jti.clone()))
+            .col_expr(sessions::Column::LastUsedAt, Expr::value(now))
+            .col_expr(
+                sessions::Column::ExpiresAt,
+                Expr::value(now + Duration::days(config.refresh_token_ttl_days)),
+            )
+            .filter(sessions::Column::Id.eq(session.id))
+            .filter(sessions::Column::RefreshTokenHash.eq(presented))
+            .exec(db)
+            .await?
+            .rows_affected
+            == 1;
+    if !rotated {
+        Sessions::delete_by_id(session.id).exec(db).await?;
+        tracing::warn!(user_id = session.user_id, "Refresh token reused; session revoked");
+        return Err(ApiError::unauthorized(
+            "Refresh token was already used; the session has been revoked",
+        ));
+    }
+
+    let user = Users::find_by_id(session.user_id)
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+    let tokens = issue(&user, family, &new_secret, &jti, config)?;
+    Ok((tokens, user))
+}
+
+/// Ends the session a refresh token belongs to. Only the session's current
+/// token does, so knowing the family id alone is not enough to sign someone out.
+pub async fn end(db: &DatabaseConnection, refresh_token: &str) -> Result<(), DbErr> {
+    if let Some((family, secret)) = refresh_token.split_once('.') {
+        Sessions::delete_many()
+            .filter(sessions::Column::Family.eq(family))
+            .filter(sessions::Column::RefreshTokenHash.eq(hash_secret(secret)))
+            .exec(db)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Signs a user out everywhere; returns how many sessions were ended.
+pub async fn end_all(db: &DatabaseConnection, user_id: i32) -> Result<u64, DbErr> {
+    let result = Sessions::delete_many()
+        .filter(sessions::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+/// Whether the access token with this `jti` is the current one of a session.
+pub async fn is_active(db: &DatabaseConnection, jti: &str) -> Result<bool, DbErr> {
+    let count = Sessions::find()
+        .filter(sessions::Column::Jti.eq(jti))
+        .count(db)
+        .await?;
+    Ok(count > 0)
+}
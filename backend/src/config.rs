@@ -0,0 +1,299 @@
+use serde::Deserialize;
+use std::{fs, net::IpAddr, path::Path, str::FromStr};
+use crate::error::{Error, Result};
+
+/// Optional settings file; every key can also be set (and overridden) by the
+/// environment variable of the same name in upper case.
+const DEFAULT_CONFIG_FILE: &str = "app.toml";
+
+/// Secrets from examples and old defaults, refused in production.
+const PLACEHOLDER_SECRETS: [&str; 3] = ["default_secret", "your-secret-key", "change-me"];
+const MIN_SECRET_LEN: usize = 32;
+/// Used in development when no secret is configured.
+const DEV_SECRET: &str = "insecure-development-secret-do-not-use-in-production";
+/// Paths kept out of crawlers when `ROBOTS_DISALLOW` is unset.
+const DEFAULT_ROBOTS_DISALLOW: [&str; 3] = ["/admin", "/api/", "/login"];
+const DEFAULT_MEDIA_MAX_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    #[default]
+    Development,
+    Production,
+}
+
+impl FromStr for Environment {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "development" | "dev" => Ok(Environment::Development),
+            "production" | "prod" => Ok(Environment::Production),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Settings read once at startup and shared through `AppState`.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub environment: Environment,
+    pub host: IpAddr,
+    pub port: u16,
+    pub database_url: String,
+    /// HMAC key for session tokens and preview links
+    pub jwt_secret: String,
+    /// How long an access token stays valid
+    pub access_token_ttl_minutes: i64,
+    /// How long a session may sit unused before its refresh token expires
+    pub refresh_token_ttl_days: i64,
+    /// Origins allowed to call the API from a browser; empty allows any
+    pub cors_origins: Vec<String>,
+    /// Directory holding the admin panel's `config.toml`
+    pub admin_config_path: String,
+    /// Account made an admin at startup and on registration, to recover an
+    /// install that has no admin who can sign in
+    pub admin_email: Option<String>,
+    /// Public URL of the frontend, without a trailing slash; links to posts
+    /// and pages are built from it
+    pub site_url: String,
+    /// Public URL of this server, without a trailing slash; absolute links to
+    /// feeds, sitemaps and media are built from it. Required in production;
+    /// development falls back to the request's `Host` header
+    pub public_url: Option<String>,
+    /// Believe the `X-Forwarded-*` headers of a reverse proxy in front of the
+    /// server. Off by default, as any client can send them when there is none
+    pub trust_proxy: bool,
+    /// Paths robots.txt disallows; empty allows everything
+    pub robots_disallow: Vec<String>,
+    /// Directory for uploaded media and cached variants
+    pub media_dir: String,
+    /// Largest accepted upload
+    pub media_max_bytes: usize,
+    /// How often scheduled posts are checked
+    pub scheduler_interval_secs: u64,
+    /// How often raw views are rolled up into daily counts
+    pub analytics_rollup_interval_secs: u64,
+    /// Repeat views of a post by one visitor within this window count once
+    pub view_dedup_window_minutes: i64,
+    /// Days raw view rows are kept once rolled up; `None` keeps them
+    pub view_retention_days: Option<i64>,
+}
+
+/// The settings file as written; anything missing falls back to defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    environment: Option<Environment>,
+    host: Option<IpAddr>,
+    port: Option<u16>,
+    database_url: Option<String>,
+    jwt_secret: Option<String>,
+    access_token_ttl_minutes: Option<i64>,
+    refresh_token_ttl_days: Option<i64>,
+    cors_origins: Option<Vec<String>>,
+    admin_config_path: Option<String>,
+    admin_email: Option<String>,
+    site_url: Option<String>,
+    public_url: Option<String>,
+    trust_proxy: Option<bool>,
+    robots_disallow: Option<Vec<String>>,
+    media_dir: Option<String>,
+    media_max_bytes: Option<usize>,
+    scheduler_interval_secs: Option<u64>,
+    analytics_rollup_interval_secs: Option<u64>,
+    view_dedup_window_minutes: Option<i64>,
+    view_retention_days: Option<i64>,
+}
+
+fn config_error(message: impl Into<String>) -> Error {
+    Error::Config(message.into())
+}
+
+/// `name` from `env`, parsed, if set and not blank.
+fn env_value<T: FromStr>(env: &impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<T>> {
+    match env(name).filter(|value| !value.trim().is_empty()) {
+        Some(value) => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| config_error(format!("{} has an invalid value", name))),
+        None => Ok(None),
+    }
+}
+
+impl Config {
+    /// Reads `APP_CONFIG_FILE` (default `app.toml`, skipped if absent) and the
+    /// process environment, which takes precedence.
+    pub fn load() -> Result<Self> {
+        let explicit = std::env::var("APP_CONFIG_FILE").ok();
+        let path = explicit.as_deref().unwrap_or(DEFAULT_CONFIG_FILE);
+        let toml = if explicit.is_some() || Path::new(path).exists() {
+            let text = fs::read_to_string(path)
+                .map_err(|e| config_error(format!("Cannot read {}: {}", path, e)))?;
+            Some(text)
+        } else {
+            None
+        };
+        Self::from_sources(toml.as_deref(), |name| std::env::var(name).ok())
+    }
+
+    /// Builds and validates the configuration from TOML text and an
+    /// environment lookup, so both can be supplied directly in tests.
+    pub fn from_sources(toml: Option<&str>, env: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let file: FileConfig = match toml {
+            Some(text) => toml::from_str(text).map_err(|e| config_error(format!("Invalid settings file: {}", e)))?,
+            None => FileConfig::default(),
+        };
+
+        let environment = env_value::<String>(&env, "APP_ENV")?
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| config_error("APP_ENV must be `development` or `production`"))
+            })
+            .transpose()?
+            .or(file.environment)
+            .unwrap_or_default();
+        // Lists are comma separated; an empty variable means an empty list
+        let list = |text: String| -> Vec<String> {
+            text.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        let cors_origins = match env("CORS_ORIGINS") {
+            Some(text) => list(text),
+            None => file.cors_origins.unwrap_or_default(),
+        };
+        let robots_disallow = match env("ROBOTS_DISALLOW") {
+            Some(text) => list(text),
+            None => file
+                .robots_disallow
+                .unwrap_or_else(|| DEFAULT_ROBOTS_DISALLOW.map(String::from).to_vec()),
+        };
+
+        let config = Config {
+            environment,
+            host: env_value(&env, "HOST")?
+                .or(file.host)
+                .unwrap_or(IpAddr::from([0, 0, 0, 0])),
+            port: env_value(&env, "PORT")?.or(file.port).unwrap_or(3000),
+            database_url: env_value(&env, "DATABASE_URL")?
+                .or(file.database_url)
+                .unwrap_or_else(|| "sqlite://./data.db?mode=rwc".to_string()),
+            jwt_secret: env_value(&env, "JWT_SECRET")?
+                .or(file.jwt_secret)
+                .unwrap_or_default(),
+            access_token_ttl_minutes: env_value(&env, "ACCESS_TOKEN_TTL_MINUTES")?
+                .or(file.access_token_ttl_minutes)
+                .unwrap_or(15),
+            refresh_token_ttl_days: env_value(&env, "REFRESH_TOKEN_TTL_DAYS")?
+                .or(file.refresh_token_ttl_days)
+                .unwrap_or(30),
+            cors_origins,
+            admin_config_path: env_value(&env, "ADMIN_CONFIG_PATH")?
+                .or(file.admin_config_path)
+                .unwrap_or_else(|| "pro_admin".to_string()),
+            admin_email: env_value(&env, "ADMIN_EMAIL")?.or(file.admin_email),
+            site_url: env_value::<String>(&env, "SITE_URL")?
+                .or(file.site_url)
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| "http://127.0.0.1:8080".to_string()),
+            public_url: env_value::<String>(&env, "PUBLIC_URL")?
+                .or(file.public_url)
+                .map(|url| url.trim_end_matches('/').to_string()),
+            trust_proxy: env_value(&env, "TRUST_PROXY")?
+                .or(file.trust_proxy)
+                .unwrap_or(false),
+            robots_disallow,
+            media_dir: env_value(&env, "MEDIA_DIR")?
+                .or(file.media_dir)
+                .unwrap_or_else(|| "./media".to_string()),
+            media_max_bytes: env_value(&env, "MEDIA_MAX_BYTES")?
+                .or(file.media_max_bytes)
+                .unwrap_or(DEFAULT_MEDIA_MAX_BYTES),
+            scheduler_interval_secs: env_value(&env, "SCHEDULER_INTERVAL_SECS")?
+                .or(file.scheduler_interval_secs)
+                .unwrap_or(30),
+            analytics_rollup_interval_secs: env_value(&env, "ANALYTICS_ROLLUP_INTERVAL_SECS")?
+                .or(file.analytics_rollup_interval_secs)
+                .unwrap_or(60),
+            view_dedup_window_minutes: env_value(&env, "VIEW_DEDUP_WINDOW_MINUTES")?
+                .or(file.view_dedup_window_minutes)
+                .unwrap_or(30),
+            view_retention_days: env_value(&env, "VIEW_RETENTION_DAYS")?
+                .or(file.view_retention_days)
+                // 0 keeps rows forever, like leaving it unset
+                .filter(|days| *days > 0),
+        };
+        config.validate()
+    }
+
+    /// Refuses a weak or missing secret in production; development falls back
+    /// to a fixed, clearly insecure one.
+    fn validate(mut self) -> Result<Self> {
+        if !(1..=24 * 60).contains(&self.access_token_ttl_minutes) {
+            return Err(config_error("ACCESS_TOKEN_TTL_MINUTES must be between 1 and 1440"));
+        }
+        if !(1..=365).contains(&self.refresh_token_ttl_days) {
+            return Err(config_error("REFRESH_TOKEN_TTL_DAYS must be between 1 and 365"));
+        }
+        if !self.site_url.starts_with("http://") && !self.site_url.starts_with("https://") {
+            return Err(config_error("SITE_URL must start with http:// or https://"));
+        }
+        match &self.public_url {
+            Some(url) if !url.starts_with("http://") && !url.starts_with("https://") => {
+                return Err(config_error("PUBLIC_URL must start with http:// or https://"));
+            }
+            None if self.environment == Environment::Production => {
+                return Err(config_error("PUBLIC_URL must be set in production"));
+            }
+            _ => {}
+        }
+        if self.media_max_bytes == 0 {
+            return Err(config_error("MEDIA_MAX_BYTES must be positive"));
+        }
+        if self.scheduler_interval_secs == 0 || self.analytics_rollup_interval_secs == 0 {
+            return Err(config_error(
+                "SCHEDULER_INTERVAL_SECS and ANALYTICS_ROLLUP_INTERVAL_SECS must be positive",
+            ));
+        }
+        if self.view_dedup_window_minutes < 0 {
+            return Err(config_error("VIEW_DEDUP_WINDOW_MINUTES must not be negative"));
+        }
+        if let Some(origin) = self
+            .cors_origins
+            .iter()
+            .find(|origin| *origin != "*" && !origin.starts_with("http://") && !origin.starts_with("https://"))
+        {
+            return Err(config_error(format!("CORS origin `{}` must start with http:// or https://", origin)));
+        }
+        if self.cors_origins.iter().any(|origin| origin == "*") {
+            self.cors_origins.clear();
+        }
+
+        let weak = self.jwt_secret.len() < MIN_SECRET_LEN
+            || PLACEHOLDER_SECRETS.contains(&self.jwt_secret.as_str());
+        match self.environment {
+            Environment::Production if weak => {
+                return Err(config_error(format!(
+                    "JWT_SECRET must be set to a random value of at least {} characters in production",
+                    MIN_SECRET_LEN
+                )));
+            }
+            Environment::Development if self.jwt_secret.is_empty() => {
+                tracing::warn!("JWT_SECRET is not set; using an insecure development secret");
+                self.jwt_secret = DEV_SECRET.to_string();
+            }
+            _ => {}
+        }
+        Ok(self)
+    }
+
+    pub fn is_production(&self) -> bool {
+        self.environment == Environment::Production
+    }
+}
@@ -0,0 +1,47 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of a post after each write. Rows are created by database triggers
+/// so admin GraphQL edits are captured too; `editor_id` is filled in by the
+/// handler that made the change.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "blog_revisions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub blog_id: i32,
+    /// 1-based and sequential per post.
+    pub revision: i32,
+    pub title: String,
+    pub excerpt: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub content: String,
+    pub status: Option<String>,
+    pub editor_id: Option<i32>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(belongs_to = "super::blogs::Entity", from = "Column::BlogId", to = "super::blogs::Column::Id")]
+    Blogs,
+    #[sea_orm(belongs_to = "super::users::Entity", from = "Column::EditorId", to = "super::users::Column::Id")]
+    Users,
+}
+
+impl Related<super::blogs::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Blogs.def()
+    }
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelatedEntity)]
+pub enum RelatedEntity {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,32 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A slug a post used to have. Rows are written by a database trigger whenever
+/// `blogs.slug` changes, so old links can be redirected to the current slug.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "blog_slug_history")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub blog_id: i32,
+    #[sea_orm(unique)]
+    pub slug: String,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(belongs_to = "super::blogs::Entity", from = "Column::BlogId", to = "super::blogs::Column::Id")]
+    Blogs,
+}
+
+impl Related<super::blogs::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Blogs.def()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelatedEntity)]
+pub enum RelatedEntity {}
+
+impl ActiveModelBehavior for ActiveModel {}
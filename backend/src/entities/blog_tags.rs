@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "blog_tags")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub blog_id: i32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub tag_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(belongs_to = "super::blogs::Entity", from = "Column::BlogId", to = "super::blogs::Column::Id")]
+    Blogs,
+    #[sea_orm(belongs_to = "super::tags::Entity", from = "Column::TagId", to = "super::tags::Column::Id")]
+    Tags,
+}
+
+impl Related<super::blogs::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Blogs.def()
+    }
+}
+
+impl Related<super::tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tags.def()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelatedEntity)]
+pub enum RelatedEntity {}
+
+impl ActiveModelBehavior for ActiveModel {}
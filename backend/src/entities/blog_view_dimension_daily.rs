@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Views of a post on one UTC day that share a value of one dimension, e.g.
+/// `referrer` = `news.ycombinator.com`. Rolled up alongside `blog_view_daily`.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "blog_view_dimension_daily")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub post_id: i32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub day: Date,
+    /// See `analytics::ViewDimension`.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub dimension: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub value: String,
+    pub views: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(belongs_to = "super::blogs::Entity", from = "Column::PostId", to = "super::blogs::Column::Id")]
+    Blogs,
+}
+
+impl Related<super::blogs::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Blogs.def()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelatedEntity)]
+pub enum RelatedEntity {}
+
+impl ActiveModelBehavior for ActiveModel {}
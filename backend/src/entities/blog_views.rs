@@ -7,7 +7,17 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub post_id: i32,
-    pub ip_address: String,
+    /// Hash of the visitor's IP and User-Agent under that day's salt; see `analytics`.
+    pub visitor_hash: Option<String>,
+    /// Host of the external page that linked here, without `www.`
+    pub referrer_host: Option<String>,
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+    /// `desktop`, `mobile` or `tablet`, parsed from the User-Agent
+    pub device: Option<String>,
+    pub browser: Option<String>,
+    pub os: Option<String>,
     pub timestamp: DateTimeUtc,
 }
 
@@ -1,5 +1,6 @@
-use sea_orm::entity::prelude::*;
+use sea_orm::{entity::prelude::*, ActiveValue, Condition, Set};
 use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "blogs")]
@@ -11,18 +12,117 @@ pub struct Model {
     #[sea_orm(unique)]
     pub slug: String,
     pub excerpt: Option<String>,
+    /// Markdown source.
     #[sea_orm(column_type = "Text")]
     pub content: String,
+    /// Sanitised HTML rendered from `content` on save.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub content_html: Option<String>,
+    /// One of `draft`, `scheduled`, `published`, `archived`; see `BlogStatus`.
     pub status: Option<String>,
+    /// Single-valued category slug, e.g. `engineering`.
+    pub category: Option<String>,
     pub published_at: Option<DateTimeUtc>,
     pub created_at: Option<DateTimeUtc>,
     pub updated_at: Option<DateTimeUtc>,
+    /// Counted views, kept up to date by `analytics::roll_up_views`.
+    pub views_count: i64,
+}
+
+/// Publication lifecycle of a post. A scheduled post is published by
+/// `scheduler` once its `published_at` has passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlogStatus {
+    Draft,
+    Scheduled,
+    Published,
+    Archived,
+}
+
+impl BlogStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlogStatus::Draft => "draft",
+            BlogStatus::Scheduled => "scheduled",
+            BlogStatus::Published => "published",
+            BlogStatus::Archived => "archived",
+        }
+    }
+
+    /// Settles the status and `published_at` actually stored for a requested
+    /// status: publishing with a future date schedules the post instead, and
+    /// publishing without a date publishes it `now`.
+    pub fn resolve(
+        self,
+        published_at: Option<DateTimeUtc>,
+        now: DateTimeUtc,
+    ) -> Result<(BlogStatus, Option<DateTimeUtc>), &'static str> {
+        match (self, published_at) {
+            (BlogStatus::Published, None) => Ok((BlogStatus::Published, Some(now))),
+            (BlogStatus::Published | BlogStatus::Scheduled, Some(at)) if at > now => {
+                Ok((BlogStatus::Scheduled, Some(at)))
+            }
+            (BlogStatus::Published, Some(at)) => Ok((BlogStatus::Published, Some(at))),
+            (BlogStatus::Scheduled, _) => Err("Scheduled posts need a future published_at"),
+            (status, at) => Ok((status, at)),
+        }
+    }
+}
+
+impl fmt::Display for BlogStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BlogStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "draft" => Ok(BlogStatus::Draft),
+            "scheduled" => Ok(BlogStatus::Scheduled),
+            "published" => Ok(BlogStatus::Published),
+            "archived" => Ok(BlogStatus::Archived),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Model {
+    pub fn blog_status(&self) -> BlogStatus {
+        self.status
+            .as_deref()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(BlogStatus::Draft)
+    }
+
+    /// Whether readers may see the post; the row-level twin of `live()`.
+    pub fn is_live(&self) -> bool {
+        self.blog_status() == BlogStatus::Published
+            && self.published_at.is_none_or(|at| at <= chrono::Utc::now())
+    }
+}
+
+/// Rows readers may see: published, and not dated in the future. The date
+/// check covers the gap between a post falling due and the next scheduler run.
+pub fn live() -> Condition {
+    Condition::all()
+        .add(Column::Status.eq(BlogStatus::Published.as_str()))
+        .add(
+            Condition::any()
+                .add(Column::PublishedAt.is_null())
+                .add(Column::PublishedAt.lte(chrono::Utc::now())),
+        )
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(belongs_to = "super::users::Entity", from = "Column::AuthorId", to = "super::users::Column::Id")]
     Users,
+    #[sea_orm(has_many = "super::blog_tags::Entity")]
+    BlogTags,
 }
 
 impl Related<super::users::Entity> for Entity {
@@ -31,7 +131,44 @@ impl Related<super::users::Entity> for Entity {
     }
 }
 
+impl Related<super::blog_tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BlogTags.def()
+    }
+}
+
+impl Related<super::tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::blog_tags::Relation::Tags.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::blog_tags::Relation::Blogs.def().rev())
+    }
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelatedEntity)]
 pub enum RelatedEntity {}
 
-impl ActiveModelBehavior for ActiveModel {}
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    /// Keep `created_at`/`updated_at` and the rendered HTML current for every
+    /// write, whether it comes from the REST handlers or the admin GraphQL mutations.
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let now = chrono::Utc::now();
+        if insert && self.created_at.is_not_set() {
+            self.created_at = Set(Some(now));
+        }
+        self.updated_at = Set(Some(now));
+        if let ActiveValue::Set(content) = &self.content {
+            self.content_html = Set(Some(crate::markdown::render(content)));
+        } else {
+            // Only ever derived from `content`; never trust a direct write
+            self.content_html = ActiveValue::NotSet;
+        }
+        Ok(self)
+    }
+}
@@ -1,9 +1,36 @@
 pub mod users;
 pub mod blogs;
 pub mod blog_views;
+pub mod tags;
+pub mod blog_tags;
+pub mod blog_revisions;
+pub mod blog_slug_history;
+pub mod media;
+pub mod comments;
+pub mod view_salts;
+pub mod blog_view_daily;
+pub mod blog_view_dimension_daily;
+pub mod sessions;
 
 pub use users::Entity as Users;
 pub use blogs::Entity as Blogs;
 pub use blog_views::Entity as BlogViews;
+pub use tags::Entity as Tags;
+pub use blog_tags::Entity as BlogTags;
+pub use blog_revisions::Entity as BlogRevisions;
+pub use blog_slug_history::Entity as BlogSlugHistory;
+pub use media::Entity as Media;
+pub use comments::Entity as Comments;
+pub use view_salts::Entity as ViewSalts;
+pub use blog_view_daily::Entity as BlogViewDaily;
+pub use blog_view_dimension_daily::Entity as BlogViewDimensionDaily;
+pub use sessions::Entity as Sessions;
 
-seaography::register_entity_modules!([users, blogs, blog_views]);
+// Revisions and slug history are maintained by triggers; they are served
+// read-only by the REST API rather than exposed to GraphQL mutations. Media
+// rows own files in storage, so they only change through `/api/media`.
+// View salts must never leave the server, and daily view rollups are derived
+// data served by `/api/analytics`. Sessions hold refresh token hashes and only
+// change through `/api/auth`. `blogs` is registered by `graphql::schema`, which
+// supplies its own list query and revision-recording mutations
+seaography::register_entity_modules!([users, blog_views, tags, blog_tags, comments]);
@@ -0,0 +1,42 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// One signed-in device. Every refresh rotates `refresh_token_hash` and `jti`
+/// in place, so a row covers the whole chain (family) of tokens issued since
+/// login; deleting it revokes them all. See `auth::sessions`.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "sessions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i32,
+    /// Random id sent as the first half of every refresh token of the session
+    #[sea_orm(unique)]
+    pub family: String,
+    /// SHA-256 of the secret half of the only refresh token still valid
+    #[serde(skip_serializing)]
+    pub refresh_token_hash: String,
+    /// `jti` claim of the access token issued with that refresh token
+    #[sea_orm(unique)]
+    pub jti: String,
+    pub created_at: DateTimeUtc,
+    pub last_used_at: DateTimeUtc,
+    pub expires_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(belongs_to = "super::users::Entity", from = "Column::UserId", to = "super::users::Column::Id")]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelatedEntity)]
+pub enum RelatedEntity {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -9,6 +9,10 @@ pub struct Model {
     #[sea_orm(unique)]
     pub email: String,
     pub name: String,
+    #[serde(skip_serializing)]
+    pub password_hash: Option<String>,
+    /// One of `reader`, `author`, `editor`, `admin`; see `auth::Role`.
+    pub role: String,
     pub created_at: Option<DateTimeUtc>,
     pub updated_at: Option<DateTimeUtc>,
 }
@@ -1,19 +1,173 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
 use thiserror::Error;
 use sea_orm::{DbErr, RuntimeErr};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::borrow::Cow;
+use utoipa::ToSchema;
 
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Database error: {0}")]
     Database(#[from] DbErr),
-    
+
     #[error("Runtime error: {0}")]
     Runtime(#[from] RuntimeErr),
-    
+
     #[error("Configuration error: {0}")]
     Config(String),
-    
+
     #[error("Not found")]
     NotFound,
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
+pub type Result<T> = std::result::Result<T, Error>;
+
+tokio::task_local! {
+    /// Id of the request being handled, set by `api::request_id`.
+    pub static REQUEST_ID: String;
+}
+
+/// Error returned by every API handler. Renders as
+/// `{"error": {"code", "message", "details", "request_id"}}` with the matching
+/// status; `code` is a stable, machine-readable name for the kind of failure.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub code: &'static str,
+    pub message: Cow<'static, str>,
+    pub details: Option<Value>,
+}
+
+pub type ApiResult<T> = std::result::Result<T, ApiError>;
+
+/// The JSON body of every error response.
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse<'a> {
+    pub error: ErrorBody<'a>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorBody<'a> {
+    #[schema(example = "not_found")]
+    pub code: &'a str,
+    pub message: &'a str,
+    /// Per-field failed rules for `validation_failed`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<&'a Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn bad_request(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
+    }
+
+    pub fn unauthorized(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", message)
+    }
+
+    pub fn forbidden(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "forbidden", message)
+    }
+
+    pub fn not_found(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not_found", message)
+    }
+
+    pub fn conflict(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::CONFLICT, "conflict", message)
+    }
+
+    pub fn unprocessable(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::UNPROCESSABLE_ENTITY, "unprocessable", message)
+    }
+
+    /// The message is shown to the client, so keep internals out of it.
+    pub fn internal(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal", message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponse {
+            error: ErrorBody {
+                code: self.code,
+                message: &self.message,
+                details: self.details.as_ref(),
+                request_id: REQUEST_ID.try_with(Clone::clone).ok(),
+            },
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+impl From<DbErr> for ApiError {
+    fn from(err: DbErr) -> Self {
+        tracing::error!("Database error: {}", err);
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "database_error", "Database error")
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for ApiError {
+    fn from(_: jsonwebtoken::errors::Error) -> Self {
+        Self::unauthorized("Invalid token")
+    }
+}
+
+/// Details list the failed rules per field, e.g. `{"password": [{"code":
+/// "length"}]}`. Submitted values are left out so passwords are never echoed.
+impl From<validator::ValidationErrors> for ApiError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        let details: Map<String, Value> = errors
+            .field_errors()
+            .into_iter()
+            .map(|(field, errors)| {
+                let rules = errors
+                    .iter()
+                    .map(|e| match &e.message {
+                        Some(message) => json!({"code": e.code, "message": message}),
+                        None => json!({"code": e.code}),
+                    })
+                    .collect();
+                (field.to_string(), Value::Array(rules))
+            })
+            .collect();
+        Self::new(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", "Validation failed")
+            .with_details(Value::Object(details))
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Database(err) => err.into(),
+            Error::NotFound => Self::not_found("Not found"),
+            other => {
+                tracing::error!("{}", other);
+                Self::internal("Internal error")
+            }
+        }
+    }
+}
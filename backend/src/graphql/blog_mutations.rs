@@ -0,0 +1,164 @@
+use async_graphql::dynamic::*;
+use sea_orm::{
+    ActiveModelTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
+use seaography::{
+    async_graphql, get_filter_conditions, prepare_active_model, BuilderContext,
+    EntityCreateBatchMutationBuilder, EntityCreateOneMutationBuilder, EntityInputBuilder,
+    EntityObjectBuilder, EntityUpdateMutationBuilder, FilterInputBuilder, GuardAction,
+};
+use crate::{
+    api::handlers::revisions::{attribute_revisions, revision_watermark},
+    auth::AuthUser,
+    entities::blogs,
+};
+
+fn blocked(reason: Option<String>, fallback: &str) -> async_graphql::Error {
+    async_graphql::Error::new(reason.unwrap_or_else(|| fallback.into()))
+}
+
+/// The `Blogs` entity guard, then the field guard of every column in `input`.
+fn check_guards(
+    context: &'static BuilderContext,
+    ctx: &ResolverContext,
+    input: &ObjectAccessor,
+) -> async_graphql::Result<()> {
+    let object_name = EntityObjectBuilder { context }.type_name::<blogs::Entity>();
+    if let Some(GuardAction::Block(reason)) =
+        context.guards.entity_guards.get(&object_name).map(|guard| guard(ctx))
+    {
+        return Err(blocked(reason, "Entity guard triggered."));
+    }
+    for (column, _) in input.iter() {
+        let guard = context.guards.field_guards.get(&format!("{}.{}", object_name, column));
+        if let Some(GuardAction::Block(reason)) = guard.map(|guard| guard(ctx)) {
+            return Err(blocked(reason, "Field guard triggered."));
+        }
+    }
+    Ok(())
+}
+
+fn active_model(
+    context: &'static BuilderContext,
+    input: &ObjectAccessor,
+) -> async_graphql::Result<blogs::ActiveModel> {
+    prepare_active_model::<blogs::Entity, blogs::ActiveModel>(
+        &EntityInputBuilder { context },
+        &EntityObjectBuilder { context },
+        input,
+    )
+}
+
+/// Opens the transaction a mutation writes in, with the revision watermark
+/// taken inside it.
+async fn begin(ctx: &ResolverContext<'_>) -> async_graphql::Result<(DatabaseTransaction, i32)> {
+    let txn = ctx.data::<DatabaseConnection>()?.begin().await?;
+    let watermark = revision_watermark(&txn).await?;
+    Ok((txn, watermark))
+}
+
+/// Credits the revisions the triggers wrote in `txn` to the signed-in editor
+/// and commits. Doing both in the write's own transaction means concurrent
+/// edits, including the scheduler's, are never credited to the wrong person.
+async fn commit(ctx: &ResolverContext<'_>, txn: DatabaseTransaction, watermark: i32) -> Result<(), DbErr> {
+    if let Some(user) = ctx.data_opt::<AuthUser>() {
+        attribute_revisions(&txn, watermark, user.id).await?;
+    }
+    txn.commit().await
+}
+
+/// `blogs_create_one`, `blogs_create_batch` and `blogs_update` as seaography
+/// builds them, except that each runs in one transaction which also records
+/// who made the change.
+pub(crate) fn blog_mutations(context: &'static BuilderContext) -> Vec<Field> {
+    let input_builder = EntityInputBuilder { context };
+    let object_builder = EntityObjectBuilder { context };
+    let object_name = object_builder.type_name::<blogs::Entity>();
+    let basic_type = object_builder.basic_type_name::<blogs::Entity>();
+
+    let create_one = Field::new(
+        EntityCreateOneMutationBuilder { context }.type_name::<blogs::Entity>(),
+        TypeRef::named_nn(&basic_type),
+        move |ctx| {
+            FieldFuture::new(async move {
+                let input = ctx.args.get(&context.entity_create_one_mutation.data_field).unwrap();
+                let input = input.object()?;
+                check_guards(context, &ctx, &input)?;
+                let model = active_model(context, &input)?;
+
+                let (txn, watermark) = begin(&ctx).await?;
+                let created = model.insert(&txn).await?;
+                commit(&ctx, txn, watermark).await?;
+                Ok(Some(FieldValue::owned_any(created)))
+            })
+        },
+    )
+    .argument(InputValue::new(
+        &context.entity_create_one_mutation.data_field,
+        TypeRef::named_nn(input_builder.insert_type_name::<blogs::Entity>()),
+    ));
+
+    let create_batch = Field::new(
+        EntityCreateBatchMutationBuilder { context }.type_name::<blogs::Entity>(),
+        TypeRef::named_nn_list_nn(&basic_type),
+        move |ctx| {
+            FieldFuture::new(async move {
+                let inputs = ctx.args.get(&context.entity_create_batch_mutation.data_field).unwrap();
+                let mut models = Vec::new();
+                for input in inputs.list()?.iter() {
+                    let input = input.object()?;
+                    check_guards(context, &ctx, &input)?;
+                    models.push(active_model(context, &input)?);
+                }
+
+                let (txn, watermark) = begin(&ctx).await?;
+                let mut created = Vec::with_capacity(models.len());
+                for model in models {
+                    created.push(model.insert(&txn).await?);
+                }
+                commit(&ctx, txn, watermark).await?;
+                Ok(Some(FieldValue::list(created.into_iter().map(FieldValue::owned_any))))
+            })
+        },
+    )
+    .argument(InputValue::new(
+        &context.entity_create_batch_mutation.data_field,
+        TypeRef::named_nn_list_nn(input_builder.insert_type_name::<blogs::Entity>()),
+    ));
+
+    let update = Field::new(
+        EntityUpdateMutationBuilder { context }.type_name::<blogs::Entity>(),
+        TypeRef::named_nn_list_nn(&basic_type),
+        move |ctx| {
+            FieldFuture::new(async move {
+                let input = ctx.args.get(&context.entity_update_mutation.data_field).unwrap();
+                let input = input.object()?;
+                check_guards(context, &ctx, &input)?;
+                let model = active_model(context, &input)?;
+                let filters = ctx.args.get(&context.entity_update_mutation.filter_field);
+                let condition = get_filter_conditions::<blogs::Entity>(context, filters);
+
+                let (txn, watermark) = begin(&ctx).await?;
+                blogs::Entity::update_many()
+                    .set(model)
+                    .filter(condition.clone())
+                    .exec(&txn)
+                    .await?;
+                let updated = blogs::Entity::find().filter(condition).all(&txn).await?;
+                commit(&ctx, txn, watermark).await?;
+                Ok(Some(FieldValue::list(updated.into_iter().map(FieldValue::owned_any))))
+            })
+        },
+    )
+    .argument(InputValue::new(
+        &context.entity_update_mutation.data_field,
+        TypeRef::named_nn(input_builder.update_type_name::<blogs::Entity>()),
+    ))
+    .argument(InputValue::new(
+        &context.entity_update_mutation.filter_field,
+        TypeRef::named(FilterInputBuilder { context }.type_name(&object_name)),
+    ));
+
+    vec![create_one, create_batch, update]
+}
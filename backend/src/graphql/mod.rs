@@ -1,3 +1,6 @@
+mod blog_mutations;
+pub mod operation;
 pub mod schema;
 
+pub use operation::*;
 pub use schema::*;
@@ -0,0 +1,120 @@
+use async_graphql::parser::{
+    parse_query,
+    types::{DocumentOperations, ExecutableDocument, OperationType, Selection, SelectionSet},
+};
+use std::collections::HashSet;
+use crate::auth::Role;
+
+/// What a request's selected operation does, decided from the parsed document
+/// rather than from words that happen to appear in the query text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    /// A query selecting nothing but `__schema`, `__type` and `__typename`
+    Introspection,
+    Query,
+    Mutation,
+    Subscription,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    pub kind: OperationKind,
+    /// Names of the top-level fields, with fragments expanded
+    pub root_fields: Vec<String>,
+}
+
+impl Operation {
+    /// Role needed to run the operation at all; entity guards in the schema
+    /// still apply. `None` for introspection and queries, which anyone may run
+    /// against published content.
+    pub fn required_role(&self) -> Option<Role> {
+        match self.kind {
+            OperationKind::Introspection | OperationKind::Query => None,
+            OperationKind::Mutation | OperationKind::Subscription => self
+                .root_fields
+                .iter()
+                .map(|field| mutation_role(field))
+                .max()
+                .or(Some(Role::Editor)),
+        }
+    }
+}
+
+const INTROSPECTION_FIELDS: [&str; 3] = ["__schema", "__type", "__typename"];
+const MUTATION_SUFFIXES: [&str; 4] = ["_create_one", "_create_batch", "_update", "_delete"];
+
+/// Role needed for a top-level mutation field such as `blogs_update`.
+pub fn mutation_role(field: &str) -> Role {
+    let entity = MUTATION_SUFFIXES
+        .iter()
+        .find_map(|suffix| field.strip_suffix(suffix))
+        .unwrap_or(field);
+    match entity {
+        "users" | "comments" => Role::Admin,
+        _ => Role::Editor,
+    }
+}
+
+/// Parses `query` and classifies the operation that would run, picked the
+/// same way the executor does. `None` if the document does not parse or has
+/// no such operation; executing it would only return an error.
+pub fn classify(query: &str, operation_name: Option<&str>) -> Option<Operation> {
+    let document = parse_query(query).ok()?;
+    let operation = match (&document.operations, operation_name) {
+        (DocumentOperations::Single(operation), None) => operation,
+        (DocumentOperations::Multiple(operations), None) if operations.len() == 1 => {
+            operations.values().next()?
+        }
+        (DocumentOperations::Multiple(operations), Some(name)) => operations.get(name)?,
+        _ => return None,
+    };
+
+    let mut root_fields = Vec::new();
+    collect_fields(
+        &document,
+        &operation.node.selection_set.node,
+        &mut HashSet::new(),
+        &mut root_fields,
+    );
+    let kind = match operation.node.ty {
+        OperationType::Query
+            if root_fields
+                .iter()
+                .all(|field| INTROSPECTION_FIELDS.contains(&field.as_str())) =>
+        {
+            OperationKind::Introspection
+        }
+        OperationType::Query => OperationKind::Query,
+        OperationType::Mutation => OperationKind::Mutation,
+        OperationType::Subscription => OperationKind::Subscription,
+    };
+    Some(Operation { kind, root_fields })
+}
+
+/// Field names selected directly by `selection_set`, looking through
+/// fragments. `visited` stops fragment cycles, which validation rejects later.
+fn collect_fields(
+    document: &ExecutableDocument,
+    selection_set: &SelectionSet,
+    visited: &mut HashSet<String>,
+    fields: &mut Vec<String>,
+) {
+    for selection in &selection_set.items {
+        match &selection.node {
+            Selection::Field(field) => fields.push(field.node.name.node.to_string()),
+            Selection::InlineFragment(fragment) => {
+                collect_fields(document, &fragment.node.selection_set.node, visited, fields)
+            }
+            Selection::FragmentSpread(spread) => {
+                let name = &spread.node.fragment_name.node;
+                if !visited.insert(name.to_string()) {
+                    continue;
+                }
+                // An unknown fragment selects nothing here; validation reports it
+                if let Some(fragment) = document.fragments.get(name) {
+                    collect_fields(document, &fragment.node.selection_set.node, visited, fields);
+                }
+            }
+        }
+    }
+}
@@ -1,10 +1,219 @@
 use async_graphql::dynamic::*;
-use sea_orm::DatabaseConnection;
-use seaography::{Builder, BuilderContext, lazy_static, async_graphql};
-use crate::entities;
+use sea_orm::{
+    Condition, ConnectionTrait, DatabaseConnection, EntityName, EntityTrait, IdenStatic, Iterable,
+    QueryFilter,
+};
+use seaography::{
+    apply_order, apply_pagination, async_graphql, get_filter_conditions, lazy_static, Builder,
+    BuilderContext, ConnectionObjectBuilder, EdgeObjectBuilder, EntityDeleteMutationBuilder,
+    EntityInputBuilder, EntityObjectBuilder, EntityQueryFieldBuilder, FilterInputBuilder, FnGuard,
+    GuardAction, GuardsConfig, OrderInputBuilder, PaginationInputBuilder, RelationBuilder,
+};
+use std::{cell::Cell, collections::BTreeMap};
+use super::blog_mutations::blog_mutations;
+use crate::{auth::{AuthUser, Role}, entities::{self, blogs}};
+
+/// Request data marking a read-only query from someone below `editor`,
+/// including anonymous visitors. They may read published posts and tags only.
+pub struct PublicReader;
+
+fn is_editor(ctx: &ResolverContext) -> bool {
+    ctx.data_opt::<AuthUser>().is_some_and(|user| user.role >= Role::Editor)
+}
+
+/// Blocks the entity unless the request carries an `AuthUser` with at least `min`.
+fn require_role(min: Role) -> FnGuard {
+    Box::new(move |ctx| match ctx.data_opt::<AuthUser>() {
+        Some(user) if user.role >= min => GuardAction::Allow,
+        _ => GuardAction::Block(Some("Insufficient permissions".into())),
+    })
+}
+
+/// Like `require_role`, but also lets public readers in.
+fn require_role_or_public(min: Role) -> FnGuard {
+    Box::new(move |ctx| match ctx.data_opt::<AuthUser>() {
+        Some(user) if user.role >= min => GuardAction::Allow,
+        _ if ctx.data_opt::<PublicReader>().is_some() => GuardAction::Allow,
+        _ => GuardAction::Block(Some("Insufficient permissions".into())),
+    })
+}
+
+/// Guards a `Blogs` field. Public readers only see fields of live posts, which
+/// also covers rows reached through relations; a `read_only` field can be read
+/// off a loaded row but not set by mutations.
+fn blog_field(read_only: bool) -> FnGuard {
+    Box::new(move |ctx| match ctx.parent_value.try_downcast_ref::<blogs::Model>() {
+        Ok(blog) if blog.is_live() || is_editor(ctx) => GuardAction::Allow,
+        Ok(_) => GuardAction::Block(Some("Post is not published".into())),
+        Err(_) if read_only => GuardAction::Block(Some("Field is read-only".into())),
+        Err(_) => GuardAction::Allow,
+    })
+}
 
 lazy_static::lazy_static! {
-    static ref CONTEXT: BuilderContext = BuilderContext::default();
+    static ref CONTEXT: BuilderContext = {
+        let mut entity_guards: BTreeMap<String, FnGuard> = BTreeMap::new();
+        entity_guards.insert("Users".into(), require_role(Role::Admin));
+        entity_guards.insert("Blogs".into(), require_role_or_public(Role::Editor));
+        entity_guards.insert("BlogViews".into(), require_role(Role::Editor));
+        entity_guards.insert("Tags".into(), require_role_or_public(Role::Editor));
+        entity_guards.insert("BlogTags".into(), require_role(Role::Editor));
+        // Comment moderation, including commenters' email addresses, is for admins
+        entity_guards.insert("Comments".into(), require_role(Role::Admin));
+
+        let mut field_guards: BTreeMap<String, FnGuard> = BTreeMap::new();
+        // Password hashes must never be readable or writable through GraphQL
+        field_guards.insert(
+            "Users.password_hash".into(),
+            Box::new(|_| GuardAction::Block(Some("Field is not accessible".into()))),
+        );
+        for column in blogs::Column::iter() {
+            // Rendered HTML is derived from `content` and view counts are
+            // maintained by the rollup job; readable, but never writable
+            let read_only = matches!(column, blogs::Column::ContentHtml | blogs::Column::ViewsCount);
+            field_guards.insert(
+                format!("Blogs.{}", column.as_str()),
+                blog_field(read_only),
+            );
+        }
+        BuilderContext {
+            guards: GuardsConfig {
+                entity_guards,
+                field_guards,
+            },
+            ..Default::default()
+        }
+    };
+}
+
+/// Limits applied to everything except introspection queries.
+pub const DEPTH_LIMIT: usize = 10;
+pub const COMPLEXITY_LIMIT: usize = 1000;
+
+/// Looser limits for introspection, which nests `ofType` deeply to unwrap list
+/// and non-null types; still finite, since it needs no token.
+pub const INTROSPECTION_DEPTH_LIMIT: usize = 20;
+pub const INTROSPECTION_COMPLEXITY_LIMIT: usize = 2000;
+
+/// The admin schemas, built once at startup since registering every entity
+/// is far more expensive than executing a typical query. Cloning is cheap.
+#[derive(Clone)]
+pub struct Schemas {
+    /// Depth and complexity limited, for regular queries and mutations
+    pub limited: Schema,
+    /// Generously limited, for the admin panel's (unauthenticated) introspection
+    pub introspection: Schema,
+}
+
+impl Schemas {
+    pub fn new(database: DatabaseConnection) -> Result<Self, SchemaError> {
+        Ok(Self {
+            limited: schema(database.clone(), Some(DEPTH_LIMIT), Some(COMPLEXITY_LIMIT))?,
+            introspection: schema(
+                database,
+                Some(INTROSPECTION_DEPTH_LIMIT),
+                Some(INTROSPECTION_COMPLEXITY_LIMIT),
+            )?,
+        })
+    }
+}
+
+/// The `blogs` query as seaography builds it, except that public readers only
+/// get live rows. Filtering the query itself keeps their filters and counts
+/// from revealing anything about unpublished posts.
+fn blogs_query(context: &'static BuilderContext) -> Field {
+    let object_name = EntityObjectBuilder { context }.type_name::<blogs::Entity>();
+    let guard = context.guards.entity_guards.get(&object_name);
+
+    Field::new(
+        EntityQueryFieldBuilder { context }.type_name::<blogs::Entity>(),
+        TypeRef::named_nn(ConnectionObjectBuilder { context }.type_name(&object_name)),
+        move |ctx| {
+            FieldFuture::new(async move {
+                if let Some(GuardAction::Block(reason)) = guard.map(|guard| guard(&ctx)) {
+                    return Err(async_graphql::Error::new(
+                        reason.unwrap_or_else(|| "Entity guard triggered.".into()),
+                    ));
+                }
+
+                let filters = ctx.args.get(&context.entity_query_field.filters);
+                let mut condition = Condition::all().add(get_filter_conditions::<blogs::Entity>(context, filters));
+                if ctx.data_opt::<PublicReader>().is_some() {
+                    condition = condition.add(blogs::live());
+                }
+                let order_by = ctx.args.get(&context.entity_query_field.order_by);
+                let order_by = OrderInputBuilder { context }.parse_object::<blogs::Entity>(order_by);
+                let pagination = ctx.args.get(&context.entity_query_field.pagination);
+                let pagination = PaginationInputBuilder { context }.parse_object(pagination);
+
+                let stmt = apply_order(blogs::Entity::find().filter(condition), order_by);
+                let db = ctx.data::<DatabaseConnection>()?;
+                let connection = apply_pagination::<blogs::Entity>(db, stmt, pagination).await?;
+                Ok(Some(FieldValue::owned_any(connection)))
+            })
+        },
+    )
+    .argument(InputValue::new(
+        &context.entity_query_field.filters,
+        TypeRef::named(FilterInputBuilder { context }.type_name(&object_name)),
+    ))
+    .argument(InputValue::new(
+        &context.entity_query_field.order_by,
+        TypeRef::named(OrderInputBuilder { context }.type_name(&object_name)),
+    ))
+    .argument(InputValue::new(
+        &context.entity_query_field.pagination,
+        TypeRef::named(PaginationInputBuilder { context }.type_name()),
+    ))
+}
+
+/// Registers `blogs` as `seaography::register_entity!` would, except that the
+/// list query and the mutations that write revisions are ours rather than
+/// generated and then swapped out.
+fn register_blogs(mut builder: Builder) -> Builder {
+    let context = builder.context;
+    let relations = blogs::RelatedEntity::iter()
+        .map(|relation| relation.get_relation(context))
+        .collect::<Vec<_>>();
+    let object = relations
+        .into_iter()
+        .fold(EntityObjectBuilder { context }.to_object::<blogs::Entity>(), Object::field);
+    builder.outputs.extend([
+        object,
+        EdgeObjectBuilder { context }.to_object::<blogs::Entity>(),
+        ConnectionObjectBuilder { context }.to_object::<blogs::Entity>(),
+        EntityObjectBuilder { context }.basic_to_object::<blogs::Entity>(),
+    ]);
+    builder.inputs.extend([
+        FilterInputBuilder { context }.to_object::<blogs::Entity>(),
+        OrderInputBuilder { context }.to_object::<blogs::Entity>(),
+        EntityInputBuilder { context }.insert_input_object::<blogs::Entity>(),
+        EntityInputBuilder { context }.update_input_object::<blogs::Entity>(),
+    ]);
+    let metadata = sea_orm::Schema::new(builder.connection.get_database_backend())
+        .json_schema_from_entity(blogs::Entity);
+    builder.metadata.insert(blogs::Entity.table_name().to_string(), metadata);
+
+    builder.queries.push(blogs_query(context));
+    builder.mutations.extend(blog_mutations(context));
+    builder
+        .mutations
+        .push(EntityDeleteMutationBuilder { context }.to_field::<blogs::Entity, blogs::ActiveModel>());
+
+    builder
+        .register_entity_dataloader_one_to_one(blogs::Entity, tokio::spawn)
+        .register_entity_dataloader_one_to_many(blogs::Entity, tokio::spawn)
+}
+
+thread_local! {
+    static BUILDS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// How many schemas `schema` has built on this thread. Building registers every
+/// entity, which is the per-request cost `Schemas` avoids, so tests count
+/// builds instead of timing requests.
+pub fn schemas_built() -> usize {
+    BUILDS.with(Cell::get)
 }
 
 pub fn schema(
@@ -12,9 +221,10 @@ pub fn schema(
     depth: Option<usize>,
     complexity: Option<usize>,
 ) -> Result<Schema, SchemaError> {
+    BUILDS.with(|builds| builds.set(builds.get() + 1));
     let builder = Builder::new(&CONTEXT, database.clone());
-    let builder = entities::register_entity_modules(builder);
-    
+    let builder = register_blogs(entities::register_entity_modules(builder));
+
     builder
         .set_depth_limit(depth)
         .set_complexity_limit(complexity)
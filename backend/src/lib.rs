@@ -1,36 +1,81 @@
 pub mod admin;
+pub mod analytics;
 pub mod api;
 pub mod auth;
+pub mod config;
 pub mod entities;
 pub mod error;
 pub mod graphql;
+pub mod markdown;
 pub mod migration;
+pub mod scheduler;
+pub mod slug;
+pub mod storage;
 
-use axum::Router;
+use axum::{extract::FromRef, Router};
+use config::Config;
 use sea_orm::{Database, DatabaseConnection};
 use sea_orm_migration::MigratorTrait;
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tracing_subscriber::fmt::init;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: DatabaseConnection,
+    /// Uploaded media files
+    pub storage: Arc<dyn storage::Storage>,
+    /// Admin GraphQL schemas, see `graphql::Schemas`
+    pub graphql: graphql::Schemas,
+    pub config: Arc<Config>,
+}
+
+/// Lets extractors such as `AuthUser` reach the configuration.
+impl FromRef<AppState> for Arc<Config> {
+    fn from_ref(state: &AppState) -> Self {
+        state.config.clone()
+    }
+}
+
+/// `AuthUser` also checks that the token's session is still active.
+impl FromRef<AppState> for DatabaseConnection {
+    fn from_ref(state: &AppState) -> Self {
+        state.db.clone()
+    }
 }
 
 pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
     dotenvy::dotenv().ok();
     init();
-    
-    let database_url = std::env::var("DATABASE_URL")
-        .unwrap_or_else(|_| "sqlite://./data.db?mode=rwc".to_string());
-    
-    let db = Database::connect(&database_url).await?;
+
+    let config = Config::load()?;
+    if config.is_production() && config.cors_origins.is_empty() {
+        tracing::warn!("CORS_ORIGINS is not set; any origin may call the API");
+    }
+
+    let db = Database::connect(&config.database_url).await?;
     migration::Migrator::up(&db, None).await?;
+    if let Some(email) = &config.admin_email {
+        if !auth::bootstrap::promote(&db, email).await? {
+            tracing::warn!("ADMIN_EMAIL {} has no account yet; it becomes an admin on registration", email);
+        }
+    }
+    scheduler::spawn(db.clone(), Duration::from_secs(config.scheduler_interval_secs));
+    analytics::spawn_rollup(db.clone(), Duration::from_secs(config.analytics_rollup_interval_secs));
+    analytics::spawn_retention(
+        db.clone(),
+        chrono::Duration::minutes(config.view_dedup_window_minutes),
+        config.view_retention_days,
+    );
     
-    let state = AppState { db };
+    let state = AppState {
+        graphql: graphql::Schemas::new(db.clone())?,
+        db,
+        storage: Arc::new(storage::LocalDisk::new(&config.media_dir)),
+        config: Arc::new(config),
+    };
+    let addr = SocketAddr::new(state.config.host, state.config.port);
     let app = create_app(state);
-    
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
+
     println!("🚀 Server running on http://{}", addr);
     
     let listener = tokio::net::TcpListener::bind(addr).await?;
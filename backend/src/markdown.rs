@@ -0,0 +1,36 @@
+use ammonia::Builder;
+use pulldown_cmark::{html, Options, Parser};
+use std::sync::LazyLock;
+
+/// CommonMark plus the GFM extensions authors expect.
+fn options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_GFM
+}
+
+static SANITIZER: LazyLock<Builder<'static>> = LazyLock::new(|| {
+    let mut builder = Builder::default();
+    builder
+        // Task list checkboxes
+        .add_tags(["input"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        .attribute_filter(|element, attribute, value| match (element, attribute) {
+            ("input", "type") if value != "checkbox" => None,
+            _ => Some(value.into()),
+        })
+        // Footnote anchors and their styling hooks
+        .add_tag_attributes("div", ["id"])
+        .add_allowed_classes("sup", ["footnote-reference", "footnote-definition-label"])
+        .add_allowed_classes("div", ["footnote-definition"]);
+    builder
+});
+
+/// Renders Markdown to HTML that is safe to inject into the page as-is.
+pub fn render(markdown: &str) -> String {
+    let mut unsafe_html = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, Parser::new_ext(markdown, options()));
+    SANITIZER.clean(&unsafe_html).to_string()
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Rendered lazily for rows written before this column existed
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Blogs::Table)
+                    .add_column(ColumnDef::new(Blogs::ContentHtml).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Admin GraphQL updates bypass `ActiveModelBehavior`; drop the cached HTML
+        // whenever `content` changes without it so readers never see stale output
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE TRIGGER IF NOT EXISTS blogs_content_html_invalidate
+                 AFTER UPDATE OF content ON blogs
+                 WHEN NEW.content_html IS OLD.content_html
+                 BEGIN
+                     UPDATE blogs SET content_html = NULL WHERE id = NEW.id;
+                 END",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DROP TRIGGER IF EXISTS blogs_content_html_invalidate")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Blogs::Table)
+                    .drop_column(Blogs::ContentHtml)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Blogs {
+    Table,
+    ContentHtml,
+}
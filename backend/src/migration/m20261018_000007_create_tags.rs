@@ -0,0 +1,151 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Tags::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Tags::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Tags::Name)
+                            .string_len(64)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Tags::Slug)
+                            .string_len(64)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Tags::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(BlogTags::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(BlogTags::BlogId).integer().not_null())
+                    .col(ColumnDef::new(BlogTags::TagId).integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(BlogTags::BlogId)
+                            .col(BlogTags::TagId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_blog_tags_blog")
+                            .from(BlogTags::Table, BlogTags::BlogId)
+                            .to(Blogs::Table, Blogs::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_blog_tags_tag")
+                            .from(BlogTags::Table, BlogTags::TagId)
+                            .to(Tags::Table, Tags::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_blog_tags_tag_id")
+                    .table(BlogTags::Table)
+                    .col(BlogTags::TagId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Blogs::Table)
+                    .add_column(ColumnDef::new(Blogs::Category).string_len(64))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_blogs_category")
+                    .table(Blogs::Table)
+                    .col(Blogs::Category)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("idx_blogs_category").table(Blogs::Table).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Blogs::Table)
+                    .drop_column(Blogs::Category)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(BlogTags::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Tags::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Tags {
+    Table,
+    Id,
+    Name,
+    Slug,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum BlogTags {
+    Table,
+    BlogId,
+    TagId,
+}
+
+#[derive(DeriveIden)]
+enum Blogs {
+    Table,
+    Id,
+    Category,
+}
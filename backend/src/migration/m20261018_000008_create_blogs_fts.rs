@@ -0,0 +1,92 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// `sql` with the control characters `snippet()` wraps matches in removed, so
+/// post text can never forge or break the search highlighting.
+fn strip(sql: &str) -> String {
+    format!("replace(replace({}, char(2), ''), char(3), '')", sql)
+}
+
+/// External-content FTS5 index over `blogs`, kept in sync by triggers so
+/// writes from the REST handlers and the admin GraphQL API are both covered.
+/// It reads post text through the `blogs_fts_source` view, which strips the
+/// match markers, so the triggers index exactly what the view returns.
+fn up() -> Vec<String> {
+    let values = |row: &str| {
+        format!(
+            "{}.id, {}, {}, {}",
+            row,
+            strip(&format!("{}.title", row)),
+            strip(&format!("{}.excerpt", row)),
+            strip(&format!("{}.content", row)),
+        )
+    };
+    vec![
+        format!(
+            "CREATE VIEW IF NOT EXISTS blogs_fts_source AS
+             SELECT id, {} AS title, {} AS excerpt, {} AS content FROM blogs",
+            strip("title"),
+            strip("excerpt"),
+            strip("content"),
+        ),
+        "CREATE VIRTUAL TABLE IF NOT EXISTS blogs_fts USING fts5(
+             title, excerpt, content,
+             content = 'blogs_fts_source', content_rowid = 'id',
+             tokenize = 'porter unicode61'
+         )"
+        .to_string(),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS blogs_fts_insert AFTER INSERT ON blogs BEGIN
+                 INSERT INTO blogs_fts(rowid, title, excerpt, content) VALUES ({});
+             END",
+            values("NEW"),
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS blogs_fts_delete AFTER DELETE ON blogs BEGIN
+                 INSERT INTO blogs_fts(blogs_fts, rowid, title, excerpt, content)
+                 VALUES ('delete', {});
+             END",
+            values("OLD"),
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS blogs_fts_update AFTER UPDATE OF title, excerpt, content ON blogs BEGIN
+                 INSERT INTO blogs_fts(blogs_fts, rowid, title, excerpt, content)
+                 VALUES ('delete', {});
+                 INSERT INTO blogs_fts(rowid, title, excerpt, content) VALUES ({});
+             END",
+            values("OLD"),
+            values("NEW"),
+        ),
+        // Index rows that existed before the table was created
+        "INSERT INTO blogs_fts(blogs_fts) VALUES ('rebuild')".to_string(),
+    ]
+}
+
+const DOWN: &[&str] = &[
+    "DROP TRIGGER IF EXISTS blogs_fts_update",
+    "DROP TRIGGER IF EXISTS blogs_fts_delete",
+    "DROP TRIGGER IF EXISTS blogs_fts_insert",
+    "DROP TABLE IF EXISTS blogs_fts",
+    "DROP VIEW IF EXISTS blogs_fts_source",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in up() {
+            db.execute_unprepared(&sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in DOWN {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// `blogs.status` used to be free-form. Existing rows are mapped onto the
+/// lifecycle and triggers reject anything else, including admin GraphQL writes.
+const UP: &[&str] = &[
+    "UPDATE blogs SET status = 'draft'
+     WHERE status IS NULL OR status NOT IN ('draft', 'scheduled', 'published', 'archived')",
+    "UPDATE blogs SET status = 'scheduled'
+     WHERE status = 'published' AND published_at > strftime('%Y-%m-%dT%H:%M:%S', 'now')",
+    "CREATE TRIGGER IF NOT EXISTS blogs_status_check_insert
+     BEFORE INSERT ON blogs
+     WHEN NEW.status NOT IN ('draft', 'scheduled', 'published', 'archived')
+     BEGIN
+         SELECT RAISE(ABORT, 'invalid blog status');
+     END",
+    "CREATE TRIGGER IF NOT EXISTS blogs_status_check_update
+     BEFORE UPDATE OF status ON blogs
+     WHEN NEW.status NOT IN ('draft', 'scheduled', 'published', 'archived')
+     BEGIN
+         SELECT RAISE(ABORT, 'invalid blog status');
+     END",
+];
+
+const DOWN: &[&str] = &[
+    "DROP TRIGGER IF EXISTS blogs_status_check_update",
+    "DROP TRIGGER IF EXISTS blogs_status_check_insert",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in UP {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in DOWN {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Snapshots are taken by triggers so every write path, including admin
+/// GraphQL mutations and the scheduler, leaves a trail.
+const TRIGGERS: &[&str] = &[
+    "CREATE TRIGGER IF NOT EXISTS blog_revisions_insert AFTER INSERT ON blogs BEGIN
+         INSERT INTO blog_revisions (blog_id, revision, title, excerpt, content, status, created_at)
+         VALUES (
+             NEW.id,
+             (SELECT COALESCE(MAX(revision), 0) + 1 FROM blog_revisions WHERE blog_id = NEW.id),
+             NEW.title, NEW.excerpt, NEW.content, NEW.status,
+             strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+         );
+     END",
+    "CREATE TRIGGER IF NOT EXISTS blog_revisions_update AFTER UPDATE OF title, excerpt, content, status ON blogs
+     WHEN NEW.title IS NOT OLD.title
+       OR NEW.excerpt IS NOT OLD.excerpt
+       OR NEW.content IS NOT OLD.content
+       OR NEW.status IS NOT OLD.status
+     BEGIN
+         INSERT INTO blog_revisions (blog_id, revision, title, excerpt, content, status, created_at)
+         VALUES (
+             NEW.id,
+             (SELECT COALESCE(MAX(revision), 0) + 1 FROM blog_revisions WHERE blog_id = NEW.id),
+             NEW.title, NEW.excerpt, NEW.content, NEW.status,
+             strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+         );
+     END",
+    // Existing posts start their history at their current state
+    "INSERT INTO blog_revisions (blog_id, revision, title, excerpt, content, status, editor_id, created_at)
+     SELECT id, 1, title, excerpt, content, status, author_id,
+            COALESCE(updated_at, created_at, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+     FROM blogs",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BlogRevisions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BlogRevisions::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BlogRevisions::BlogId).integer().not_null())
+                    .col(ColumnDef::new(BlogRevisions::Revision).integer().not_null())
+                    .col(ColumnDef::new(BlogRevisions::Title).string_len(255).not_null())
+                    .col(ColumnDef::new(BlogRevisions::Excerpt).text())
+                    .col(ColumnDef::new(BlogRevisions::Content).text().not_null())
+                    .col(ColumnDef::new(BlogRevisions::Status).string_len(16))
+                    .col(ColumnDef::new(BlogRevisions::EditorId).integer())
+                    .col(
+                        ColumnDef::new(BlogRevisions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_blog_revisions_blog")
+                            .from(BlogRevisions::Table, BlogRevisions::BlogId)
+                            .to(Blogs::Table, Blogs::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_blog_revisions_editor")
+                            .from(BlogRevisions::Table, BlogRevisions::EditorId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_blog_revisions_blog_revision")
+                    .table(BlogRevisions::Table)
+                    .col(BlogRevisions::BlogId)
+                    .col(BlogRevisions::Revision)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        for sql in TRIGGERS {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP TRIGGER IF EXISTS blog_revisions_update")
+            .await?;
+        db.execute_unprepared("DROP TRIGGER IF EXISTS blog_revisions_insert")
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(BlogRevisions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BlogRevisions {
+    Table,
+    Id,
+    BlogId,
+    Revision,
+    Title,
+    Excerpt,
+    Content,
+    Status,
+    EditorId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Blogs {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Keeps the history current for every write path, including admin GraphQL
+/// edits. Taking a slug back out of history (e.g. renaming a post back) frees it.
+const TRIGGERS: &[&str] = &[
+    "CREATE TRIGGER IF NOT EXISTS blog_slug_history_update AFTER UPDATE OF slug ON blogs
+     WHEN NEW.slug IS NOT OLD.slug
+     BEGIN
+         DELETE FROM blog_slug_history WHERE slug = NEW.slug;
+         INSERT OR REPLACE INTO blog_slug_history (blog_id, slug, created_at)
+         VALUES (NEW.id, OLD.slug, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+     END",
+    "CREATE TRIGGER IF NOT EXISTS blog_slug_history_insert AFTER INSERT ON blogs BEGIN
+         DELETE FROM blog_slug_history WHERE slug = NEW.slug;
+     END",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BlogSlugHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BlogSlugHistory::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BlogSlugHistory::BlogId).integer().not_null())
+                    .col(
+                        ColumnDef::new(BlogSlugHistory::Slug)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(BlogSlugHistory::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_blog_slug_history_blog")
+                            .from(BlogSlugHistory::Table, BlogSlugHistory::BlogId)
+                            .to(Blogs::Table, Blogs::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_blog_slug_history_blog_id")
+                    .table(BlogSlugHistory::Table)
+                    .col(BlogSlugHistory::BlogId)
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        for sql in TRIGGERS {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP TRIGGER IF EXISTS blog_slug_history_insert")
+            .await?;
+        db.execute_unprepared("DROP TRIGGER IF EXISTS blog_slug_history_update")
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(BlogSlugHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BlogSlugHistory {
+    Table,
+    Id,
+    BlogId,
+    Slug,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Blogs {
+    Table,
+    Id,
+}
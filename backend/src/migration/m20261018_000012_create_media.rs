@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Media::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Media::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Media::UploaderId).integer())
+                    .col(ColumnDef::new(Media::Filename).string_len(255).not_null())
+                    .col(ColumnDef::new(Media::MimeType).string_len(64).not_null())
+                    .col(ColumnDef::new(Media::SizeBytes).big_integer().not_null())
+                    .col(ColumnDef::new(Media::Width).integer().not_null())
+                    .col(ColumnDef::new(Media::Height).integer().not_null())
+                    .col(
+                        ColumnDef::new(Media::StorageKey)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Media::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_media_uploader")
+                            .from(Media::Table, Media::UploaderId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Media::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Media {
+    Table,
+    Id,
+    UploaderId,
+    Filename,
+    MimeType,
+    SizeBytes,
+    Width,
+    Height,
+    StorageKey,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
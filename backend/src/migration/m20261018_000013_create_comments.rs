@@ -0,0 +1,146 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Moderation happens in the admin panel too, so the status is checked by
+/// triggers like `blogs.status`. Replies must stay on their parent's post.
+const TRIGGERS: &[&str] = &[
+    "CREATE TRIGGER IF NOT EXISTS comments_status_check_insert
+     BEFORE INSERT ON comments
+     WHEN NEW.status NOT IN ('pending', 'approved', 'spam')
+     BEGIN
+         SELECT RAISE(ABORT, 'invalid comment status');
+     END",
+    "CREATE TRIGGER IF NOT EXISTS comments_status_check_update
+     BEFORE UPDATE OF status ON comments
+     WHEN NEW.status NOT IN ('pending', 'approved', 'spam')
+     BEGIN
+         SELECT RAISE(ABORT, 'invalid comment status');
+     END",
+    "CREATE TRIGGER IF NOT EXISTS comments_parent_check_insert
+     BEFORE INSERT ON comments
+     WHEN NEW.parent_id IS NOT NULL
+      AND NOT EXISTS (SELECT 1 FROM comments WHERE id = NEW.parent_id AND blog_id = NEW.blog_id)
+     BEGIN
+         SELECT RAISE(ABORT, 'reply must belong to the same post as its parent');
+     END",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Comments::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Comments::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Comments::BlogId).integer().not_null())
+                    .col(ColumnDef::new(Comments::ParentId).integer())
+                    .col(ColumnDef::new(Comments::UserId).integer())
+                    .col(ColumnDef::new(Comments::AuthorName).string_len(100).not_null())
+                    .col(ColumnDef::new(Comments::AuthorEmail).string_len(255))
+                    .col(ColumnDef::new(Comments::Content).text().not_null())
+                    .col(
+                        ColumnDef::new(Comments::Status)
+                            .string_len(16)
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(
+                        ColumnDef::new(Comments::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Comments::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_comments_blog")
+                            .from(Comments::Table, Comments::BlogId)
+                            .to(Blogs::Table, Blogs::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_comments_parent")
+                            .from(Comments::Table, Comments::ParentId)
+                            .to(Comments::Table, Comments::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_comments_user")
+                            .from(Comments::Table, Comments::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_comments_blog_status")
+                    .table(Comments::Table)
+                    .col(Comments::BlogId)
+                    .col(Comments::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        for sql in TRIGGERS {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Comments::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Comments {
+    Table,
+    Id,
+    BlogId,
+    ParentId,
+    UserId,
+    AuthorName,
+    AuthorEmail,
+    Content,
+    Status,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Blogs {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
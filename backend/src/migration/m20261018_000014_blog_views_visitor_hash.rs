@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Views stop storing client IPs. Existing addresses are discarded rather
+/// than hashed, since they would all land on one salt and stay linkable.
+const UP: &[&str] = &[
+    "ALTER TABLE blog_views ADD COLUMN visitor_hash VARCHAR(64)",
+    "ALTER TABLE blog_views DROP COLUMN ip_address",
+    "CREATE INDEX IF NOT EXISTS idx_blog_views_dedup ON blog_views (post_id, visitor_hash, timestamp)",
+];
+
+const DOWN: &[&str] = &[
+    "DROP INDEX IF EXISTS idx_blog_views_dedup",
+    "ALTER TABLE blog_views ADD COLUMN ip_address VARCHAR NOT NULL DEFAULT ''",
+    "ALTER TABLE blog_views DROP COLUMN visitor_hash",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in UP {
+            db.execute_unprepared(sql).await?;
+        }
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ViewSalts::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ViewSalts::Day).date().not_null().primary_key())
+                    .col(ColumnDef::new(ViewSalts::Salt).string_len(64).not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ViewSalts::Table).to_owned())
+            .await?;
+
+        let db = manager.get_connection();
+        for sql in DOWN {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ViewSalts {
+    Table,
+    Day,
+    Salt,
+}
@@ -0,0 +1,113 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Existing raw views are rolled up here; `analytics::roll_up_views` carries
+/// on from `last_view_id`.
+const BACKFILL: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS blog_view_rollup_state (
+         id INTEGER PRIMARY KEY CHECK (id = 1),
+         last_view_id INTEGER NOT NULL
+     )",
+    "INSERT INTO blog_view_daily (post_id, day, views)
+     SELECT post_id, substr(timestamp, 1, 10), COUNT(*)
+     FROM blog_views
+     GROUP BY post_id, substr(timestamp, 1, 10)",
+    "UPDATE blogs SET views_count = (SELECT COUNT(*) FROM blog_views WHERE post_id = blogs.id)",
+    "INSERT INTO blog_view_rollup_state (id, last_view_id)
+     SELECT 1, COALESCE(MAX(id), 0) FROM blog_views",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BlogViewDaily::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(BlogViewDaily::PostId).integer().not_null())
+                    .col(ColumnDef::new(BlogViewDaily::Day).date().not_null())
+                    .col(
+                        ColumnDef::new(BlogViewDaily::Views)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(BlogViewDaily::PostId)
+                            .col(BlogViewDaily::Day),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_blog_view_daily_post")
+                            .from(BlogViewDaily::Table, BlogViewDaily::PostId)
+                            .to(Blogs::Table, Blogs::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_blog_view_daily_day")
+                    .table(BlogViewDaily::Table)
+                    .col(BlogViewDaily::Day)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Blogs::Table)
+                    .add_column(
+                        ColumnDef::new(Blogs::ViewsCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        for sql in BACKFILL {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP TABLE IF EXISTS blog_view_rollup_state")
+            .await?;
+        db.execute_unprepared("ALTER TABLE blogs DROP COLUMN views_count")
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(BlogViewDaily::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BlogViewDaily {
+    Table,
+    PostId,
+    Day,
+    Views,
+}
+
+#[derive(DeriveIden)]
+enum Blogs {
+    Table,
+    Id,
+    ViewsCount,
+}
@@ -0,0 +1,134 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Columns describing where a view came from and on what kind of client.
+/// Views recorded before this migration have none of them and are not part
+/// of any breakdown.
+const SOURCE_COLUMNS: &[BlogViews] = &[
+    BlogViews::ReferrerHost,
+    BlogViews::UtmSource,
+    BlogViews::UtmMedium,
+    BlogViews::UtmCampaign,
+    BlogViews::Device,
+    BlogViews::Browser,
+    BlogViews::Os,
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only takes one column per ALTER TABLE
+        for column in SOURCE_COLUMNS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(BlogViews::Table)
+                        .add_column(ColumnDef::new(*column).string_len(100))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(BlogViewDimensionDaily::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(BlogViewDimensionDaily::PostId).integer().not_null())
+                    .col(ColumnDef::new(BlogViewDimensionDaily::Day).date().not_null())
+                    .col(
+                        ColumnDef::new(BlogViewDimensionDaily::Dimension)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BlogViewDimensionDaily::Value)
+                            .string_len(100)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BlogViewDimensionDaily::Views)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(BlogViewDimensionDaily::PostId)
+                            .col(BlogViewDimensionDaily::Day)
+                            .col(BlogViewDimensionDaily::Dimension)
+                            .col(BlogViewDimensionDaily::Value),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_blog_view_dimension_daily_post")
+                            .from(BlogViewDimensionDaily::Table, BlogViewDimensionDaily::PostId)
+                            .to(Blogs::Table, Blogs::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_blog_view_dimension_daily_day")
+                    .table(BlogViewDimensionDaily::Table)
+                    .col(BlogViewDimensionDaily::Dimension)
+                    .col(BlogViewDimensionDaily::Day)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BlogViewDimensionDaily::Table).to_owned())
+            .await?;
+
+        for column in SOURCE_COLUMNS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(BlogViews::Table)
+                        .drop_column(*column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden, Clone, Copy)]
+enum BlogViews {
+    Table,
+    ReferrerHost,
+    UtmSource,
+    UtmMedium,
+    UtmCampaign,
+    Device,
+    Browser,
+    Os,
+}
+
+#[derive(DeriveIden)]
+enum BlogViewDimensionDaily {
+    Table,
+    PostId,
+    Day,
+    Dimension,
+    Value,
+    Views,
+}
+
+#[derive(DeriveIden)]
+enum Blogs {
+    Table,
+    Id,
+}
@@ -3,6 +3,20 @@ pub use sea_orm_migration::prelude::*;
 mod m20240101_000001_initial;
 mod m20250830_000002_create_blogs;
 mod m20250830_000003_create_blog_views;
+mod m20261018_000004_add_users_password_hash;
+mod m20261018_000005_add_users_role;
+mod m20261018_000006_add_blogs_content_html;
+mod m20261018_000007_create_tags;
+mod m20261018_000008_create_blogs_fts;
+mod m20261018_000009_blogs_status_lifecycle;
+mod m20261018_000010_create_blog_revisions;
+mod m20261018_000011_create_blog_slug_history;
+mod m20261018_000012_create_media;
+mod m20261018_000013_create_comments;
+mod m20261018_000014_blog_views_visitor_hash;
+mod m20261018_000015_create_blog_view_daily;
+mod m20261018_000016_blog_views_sources;
+mod m20261018_000017_create_sessions;
 
 pub struct Migrator;
 
@@ -13,6 +27,20 @@ impl MigratorTrait for Migrator {
             Box::new(m20240101_000001_initial::Migration),
             Box::new(m20250830_000002_create_blogs::Migration),
             Box::new(m20250830_000003_create_blog_views::Migration),
+            Box::new(m20261018_000004_add_users_password_hash::Migration),
+            Box::new(m20261018_000005_add_users_role::Migration),
+            Box::new(m20261018_000006_add_blogs_content_html::Migration),
+            Box::new(m20261018_000007_create_tags::Migration),
+            Box::new(m20261018_000008_create_blogs_fts::Migration),
+            Box::new(m20261018_000009_blogs_status_lifecycle::Migration),
+            Box::new(m20261018_000010_create_blog_revisions::Migration),
+            Box::new(m20261018_000011_create_blog_slug_history::Migration),
+            Box::new(m20261018_000012_create_media::Migration),
+            Box::new(m20261018_000013_create_comments::Migration),
+            Box::new(m20261018_000014_blog_views_visitor_hash::Migration),
+            Box::new(m20261018_000015_create_blog_view_daily::Migration),
+            Box::new(m20261018_000016_blog_views_sources::Migration),
+            Box::new(m20261018_000017_create_sessions::Migration),
         ]
     }
 }
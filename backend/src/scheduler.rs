@@ -0,0 +1,39 @@
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+use crate::entities::blogs::{self, BlogStatus};
+
+/// Publishes every scheduled post whose `published_at` has passed and returns
+/// how many were flipped.
+pub async fn publish_due(db: &DatabaseConnection) -> Result<u64, DbErr> {
+    let now = chrono::Utc::now();
+    // `update_many` skips `before_save`, so bump `updated_at` here for feeds and the sitemap
+    let result = blogs::Entity::update_many()
+        .col_expr(blogs::Column::Status, Expr::value(BlogStatus::Published.as_str()))
+        .col_expr(blogs::Column::UpdatedAt, Expr::value(now))
+        .filter(blogs::Column::Status.eq(BlogStatus::Scheduled.as_str()))
+        .filter(blogs::Column::PublishedAt.lte(now))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+/// Runs `publish_due` every `period` (`Config::scheduler_interval_secs`),
+/// starting immediately so posts that fell due while the server was down go out.
+pub fn spawn(db: DatabaseConnection, period: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match publish_due(&db).await {
+                Ok(0) => {}
+                Ok(n) => info!("Published {} scheduled post(s)", n),
+                Err(e) => error!("Scheduled publishing failed: {}", e),
+            }
+        }
+    })
+}
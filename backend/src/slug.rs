@@ -0,0 +1,25 @@
+/// Slugs a post cannot have, because a fixed route under `/api/blogs/`
+/// answers at that address first.
+pub const RESERVED_SLUGS: &[&str] = &["search"];
+
+pub fn is_reserved(slug: &str) -> bool {
+    RESERVED_SLUGS.contains(&slug)
+}
+
+/// Lowercases `input` and collapses every run of characters other than ASCII
+/// letters and digits into a single `-`, e.g. `"Rust & WebAssembly!"` becomes
+/// `"rust-webassembly"`. Input with no ASCII letters or digits gives `""`.
+pub fn slugify(input: &str) -> String {
+    let mut slug = String::with_capacity(input.len());
+    for c in input.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
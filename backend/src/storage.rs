@@ -0,0 +1,89 @@
+use std::{io, path::PathBuf};
+
+/// Where uploaded media bytes live. Keys are relative, `/`-separated paths
+/// chosen by the server, never by the client.
+#[async_trait::async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, bytes: &[u8]) -> io::Result<()>;
+    /// `Ok(None)` when nothing is stored under `key`.
+    async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>>;
+    /// Removes `key` and anything stored beneath it as a prefix.
+    async fn delete(&self, key: &str) -> io::Result<()>;
+    /// Keys stored directly beneath `prefix`; empty when there are none.
+    async fn list(&self, prefix: &str) -> io::Result<Vec<String>>;
+}
+
+/// Files under a directory on the local disk.
+pub struct LocalDisk {
+    root: PathBuf,
+}
+
+impl LocalDisk {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> io::Result<PathBuf> {
+        let mut path = self.root.clone();
+        for part in key.split('/') {
+            if part.is_empty() || part == "." || part == ".." {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid storage key"));
+            }
+            path.push(part);
+        }
+        Ok(path)
+    }
+}
+
+#[async_trait::async_trait]
+impl Storage for LocalDisk {
+    async fn put(&self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        let path = self.path(key)?;
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        // Write beside the target and rename so readers never see a partial file
+        let tmp = path.with_extension("part");
+        tokio::fs::write(&tmp, bytes).await?;
+        tokio::fs::rename(&tmp, &path).await
+    }
+
+    async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        let path = self.path(key)?;
+        let result = match tokio::fs::metadata(&path).await {
+            Ok(meta) if meta.is_dir() => tokio::fs::remove_dir_all(&path).await,
+            Ok(_) => tokio::fs::remove_file(&path).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> io::Result<Vec<String>> {
+        let mut entries = match tokio::fs::read_dir(self.path(prefix)?).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut keys = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            // Skip writes still in progress
+            if entry.file_type().await?.is_file() && !name.ends_with(".part") {
+                keys.push(format!("{}/{}", prefix, name));
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+}
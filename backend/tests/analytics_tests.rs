@@ -0,0 +1,316 @@
+// Tests for view counting: bot filtering, visitor dedup, sources, rollups,
+// retention and who may see which posts' numbers
+// `record_view` runs against an in-memory database with all migrations applied
+
+mod common;
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{header, Request, StatusCode},
+};
+use backend::{
+    analytics::{
+        apply_retention, is_bot, parse_user_agent, record_view, referrer_host, roll_up_views,
+        visitor_hash, ViewDimension, ViewSource,
+    },
+    api::handlers::{bucket_series, Granularity},
+    entities::{blog_view_daily, blog_view_dimension_daily, blog_views, blogs, view_salts},
+};
+use chrono::{Duration, NaiveDate, Utc};
+use common::{app_with, body_json, call, seed_author, token_for};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, PaginatorTrait, Set};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+/// The default `VIEW_DEDUP_WINDOW_MINUTES`
+const WINDOW: Duration = Duration::minutes(30);
+const FIREFOX: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:131.0) Gecko/20100101 Firefox/131.0";
+
+async fn setup_db() -> (DatabaseConnection, i32) {
+    let db = common::setup_db().await;
+    seed_author(&db).await;
+    let post = blogs::ActiveModel {
+        author_id: Set(1),
+        title: Set("Post".to_string()),
+        slug: Set("post".to_string()),
+        content: Set("Body".to_string()),
+        ..Default::default()
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+    (db, post.id)
+}
+
+#[test]
+fn crawlers_and_scripts_are_bots() {
+    assert!(is_bot(Some("Mozilla/5.0 (compatible; Googlebot/2.1)")));
+    assert!(is_bot(Some("curl/8.5.0")));
+    assert!(is_bot(None));
+    assert!(!is_bot(Some(FIREFOX)));
+}
+
+#[test]
+fn visitor_hash_hides_the_ip_and_depends_on_the_salt() {
+    let ip = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+    let hash = visitor_hash("salt-a", ip, FIREFOX);
+    assert!(!hash.contains("203.0.113.7"));
+    assert_ne!(hash, visitor_hash("salt-b", ip, FIREFOX));
+}
+
+#[tokio::test]
+async fn repeat_views_within_the_window_count_once() {
+    let (db, post) = setup_db().await;
+    let ip = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+    let other = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 1));
+
+    assert!(record_view(&db, WINDOW, post, ip, Some(FIREFOX), &ViewSource::default()).await.unwrap());
+    assert!(!record_view(&db, WINDOW, post, ip, Some(FIREFOX), &ViewSource::default()).await.unwrap());
+    assert!(!record_view(&db, WINDOW, post, ip, Some("Googlebot/2.1"), &ViewSource::default()).await.unwrap());
+    assert!(record_view(&db, WINDOW, post, other, Some(FIREFOX), &ViewSource::default()).await.unwrap());
+
+    assert_eq!(blog_views::Entity::find().count(&db).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn visits_either_side_of_midnight_count_once() {
+    let (db, post) = setup_db().await;
+    let ip = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+    view_salts::ActiveModel {
+        day: Set((Utc::now() - Duration::days(1)).date_naive()),
+        salt: Set("yesterday".to_string()),
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+    blog_views::ActiveModel {
+        post_id: Set(post),
+        visitor_hash: Set(Some(visitor_hash("yesterday", ip, FIREFOX))),
+        timestamp: Set(Utc::now() - Duration::minutes(1)),
+        ..Default::default()
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+
+    // A day-long window always reaches back into yesterday
+    let window = Duration::days(1);
+    assert!(!record_view(&db, window, post, ip, Some(FIREFOX), &ViewSource::default()).await.unwrap());
+
+    // Yesterday's salt is kept for as long as the window can reach it
+    assert_eq!(apply_retention(&db, window, None).await.unwrap().salts_deleted, 0);
+    assert_eq!(apply_retention(&db, Duration::zero(), None).await.unwrap().salts_deleted, 1);
+}
+
+/// Views `post` twice through one proxy, for two different forwarded clients,
+/// and returns how many were counted.
+async fn views_through_a_proxy(trust_proxy: &'static str) -> u64 {
+    let (db, post) = setup_db().await;
+    let mut published: blogs::ActiveModel =
+        blogs::Entity::find_by_id(post).one(&db).await.unwrap().unwrap().into();
+    published.status = Set(Some("published".to_string()));
+    published.update(&db).await.unwrap();
+    let app = app_with(&db, &[("TRUST_PROXY", trust_proxy)]);
+
+    let proxy: SocketAddr = "10.0.0.2:443".parse().unwrap();
+    for client in ["203.0.113.7", "198.51.100.1"] {
+        let mut request = Request::get("/api/blogs/post")
+            .header(header::USER_AGENT, FIREFOX)
+            .header("x-forwarded-for", format!("192.0.2.99, {}", client))
+            .body(Body::empty())
+            .unwrap();
+        request.extensions_mut().insert(ConnectInfo(proxy));
+        assert_eq!(call(&app, request).await.status(), StatusCode::OK);
+    }
+    blog_views::Entity::find().count(&db).await.unwrap()
+}
+
+#[tokio::test]
+async fn forwarded_clients_are_told_apart_only_behind_a_trusted_proxy() {
+    assert_eq!(views_through_a_proxy("false").await, 1);
+    assert_eq!(views_through_a_proxy("true").await, 2);
+}
+
+#[tokio::test]
+async fn authors_only_rank_published_posts_and_their_own() {
+    // Post 1 is a draft by someone else
+    let (db, others_draft) = setup_db().await;
+    let app = app_with(&db, &[]);
+    let (_, editor) = token_for(&app, &db, "editor@example.com", "editor").await;
+    let (_, author) = token_for(&app, &db, "author2@example.com", "author").await;
+
+    let mut posts = vec![others_draft];
+    for (author_id, slug, status) in [(1, "live", "published"), (3, "mine", "draft")] {
+        let post = blogs::ActiveModel {
+            author_id: Set(author_id),
+            title: Set(slug.to_string()),
+            slug: Set(slug.to_string()),
+            content: Set("Body".to_string()),
+            status: Set(Some(status.to_string())),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        posts.push(post.id);
+    }
+    for (views, post) in posts.into_iter().enumerate() {
+        blog_view_daily::ActiveModel {
+            post_id: Set(post),
+            day: Set(Utc::now().date_naive()),
+            views: Set(10 - views as i64),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+
+    let ranked = |token: String| {
+        let app = app.clone();
+        async move {
+            let request = Request::get("/api/analytics/posts/top")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap();
+            let response = call(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = body_json(response).await;
+            body["items"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item["slug"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(ranked(author).await, vec!["live", "mine"]);
+    assert_eq!(ranked(editor).await, vec!["post", "live", "mine"]);
+}
+
+#[test]
+fn user_agents_are_reduced_to_device_browser_and_os() {
+    let client = parse_user_agent(FIREFOX);
+    assert_eq!((client.device, client.browser, client.os), ("desktop", "Firefox", "Linux"));
+
+    let iphone = "Mozilla/5.0 (iPhone; CPU iPhone OS 18_0 like Mac OS X) AppleWebKit/605.1.15 \
+                  (KHTML, like Gecko) Version/18.0 Mobile/15E148 Safari/604.1";
+    let client = parse_user_agent(iphone);
+    assert_eq!((client.device, client.browser, client.os), ("mobile", "Safari", "iOS"));
+
+    let edge = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                (KHTML, like Gecko) Chrome/130.0.0.0 Safari/537.36 Edg/130.0.0.0";
+    assert_eq!(parse_user_agent(edge).browser, "Edge");
+}
+
+#[test]
+fn referrers_keep_only_foreign_hosts() {
+    assert_eq!(
+        referrer_host("https://WWW.News.Example.com:443/item?id=1").as_deref(),
+        Some("news.example.com")
+    );
+    assert_eq!(referrer_host("not a url"), None);
+
+    let own = vec!["blog.example.org".to_string()];
+    let internal = ViewSource::new(Some("https://blog.example.org/"), None, None, None, &own);
+    assert_eq!(internal.referrer_host, None);
+    let tagged = ViewSource::new(None, Some(" Newsletter "), Some(""), None, &own);
+    assert_eq!(tagged.utm_source.as_deref(), Some("newsletter"));
+    assert_eq!(tagged.utm_medium, None);
+}
+
+#[tokio::test]
+async fn rollups_break_views_down_by_source_and_client() {
+    let (db, post) = setup_db().await;
+    let source = ViewSource::new(Some("https://news.example.com/"), Some("hn"), None, None, &[]);
+    for last in 1..=3 {
+        let ip = IpAddr::V4(Ipv4Addr::new(203, 0, 113, last));
+        let source = if last == 3 { ViewSource::default() } else { source.clone() };
+        record_view(&db, WINDOW, post, ip, Some(FIREFOX), &source).await.unwrap();
+    }
+    roll_up_views(&db).await.unwrap();
+
+    let rows = blog_view_dimension_daily::Entity::find().all(&db).await.unwrap();
+    let views = |dimension: ViewDimension, value: &str| {
+        rows.iter()
+            .find(|r| r.dimension == dimension.as_str() && r.value == value)
+            .map(|r| r.views)
+    };
+    assert_eq!(views(ViewDimension::Referrer, "news.example.com"), Some(2));
+    assert_eq!(views(ViewDimension::Referrer, "(direct)"), Some(1));
+    assert_eq!(views(ViewDimension::UtmSource, "hn"), Some(2));
+    assert_eq!(views(ViewDimension::UtmMedium, "(none)"), Some(3));
+    assert_eq!(views(ViewDimension::Browser, "Firefox"), Some(3));
+}
+
+#[tokio::test]
+async fn retention_drops_old_salts_and_views() {
+    let (db, post) = setup_db().await;
+    view_salts::ActiveModel {
+        day: Set((Utc::now() - Duration::days(2)).date_naive()),
+        salt: Set("two days ago".to_string()),
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+    for age in [Duration::days(100), Duration::days(1)] {
+        blog_views::ActiveModel {
+            post_id: Set(post),
+            visitor_hash: Set(None),
+            timestamp: Set(Utc::now() - age),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+
+    // Nothing is purged before it has been counted
+    let report = apply_retention(&db, WINDOW, Some(90)).await.unwrap();
+    assert_eq!((report.salts_deleted, report.views_deleted), (1, 0));
+    roll_up_views(&db).await.unwrap();
+
+    let report = apply_retention(&db, WINDOW, Some(90)).await.unwrap();
+    assert_eq!((report.salts_deleted, report.views_deleted), (0, 1));
+    assert_eq!(blog_views::Entity::find().count(&db).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn rollups_count_each_view_once() {
+    let (db, post) = setup_db().await;
+    let ip = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+    record_view(&db, WINDOW, post, ip, Some(FIREFOX), &ViewSource::default()).await.unwrap();
+    record_view(&db, WINDOW, post, ip, Some("Mozilla/5.0 Safari"), &ViewSource::default()).await.unwrap();
+
+    assert_eq!(roll_up_views(&db).await.unwrap(), 2);
+    assert_eq!(roll_up_views(&db).await.unwrap(), 0);
+    record_view(&db, WINDOW, post, ip, Some("Mozilla/5.0 Chrome"), &ViewSource::default()).await.unwrap();
+    assert_eq!(roll_up_views(&db).await.unwrap(), 1);
+
+    let daily = blog_view_daily::Entity::find().all(&db).await.unwrap();
+    assert_eq!(daily.len(), 1);
+    assert_eq!(daily[0].views, 3);
+    let post = blogs::Entity::find_by_id(post).one(&db).await.unwrap().unwrap();
+    assert_eq!(post.views_count, 3);
+}
+
+#[test]
+fn series_are_bucketed_and_gap_filled() {
+    let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+    // 2026-10-05 and 2026-10-12 are Mondays
+    let daily = [(day(6), 2), (day(7), 1), (day(14), 4)];
+
+    let weekly: Vec<(NaiveDate, i64)> = bucket_series(&daily, day(1), day(20), Granularity::Week)
+        .into_iter()
+        .map(|p| (p.period, p.views))
+        .collect();
+    assert_eq!(
+        weekly,
+        vec![
+            (NaiveDate::from_ymd_opt(2026, 9, 28).unwrap(), 0),
+            (day(5), 3),
+            (day(12), 4),
+            (day(19), 0),
+        ]
+    );
+    assert_eq!(bucket_series(&daily, day(6), day(8), Granularity::Day).len(), 3);
+}
@@ -0,0 +1,181 @@
+// Tests for writing posts over REST: create, replace, patch and delete by id
+
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{app, token_for};
+use serde_json::{json, Value};
+
+async fn call(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    token: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    common::call_json(app, method, uri, Some(token), body).await
+}
+
+async fn create(app: &Router, token: &str) -> Value {
+    let body = json!({
+        "title": "Sourdough",
+        "excerpt": "Bread notes",
+        "content": "Feed the starter",
+        "status": "published",
+        "category": "baking",
+        "tags": ["bread", "yeast"],
+    });
+    let (status, post) = call(app, Method::POST, "/api/blogs", token, Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    post
+}
+
+fn tag_names(post: &Value) -> Vec<&str> {
+    post["tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tag| tag["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn the_author_comes_from_the_token() {
+    let (app, db) = app(&[]).await;
+    let (admin, _) = token_for(&app, &db, "admin@example.com", "admin").await;
+    let (author, token) = token_for(&app, &db, "author@example.com", "author").await;
+
+    let body = json!({"title": "Mine", "content": "Body", "author_id": admin});
+    let (status, post) = call(&app, Method::POST, "/api/blogs", &token, Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(post["author_id"], author);
+    assert_eq!(post["status"], "draft");
+
+    let (_, reader) = token_for(&app, &db, "reader@example.com", "reader").await;
+    let body = json!({"title": "Nope", "content": "Body"});
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/api/blogs",
+        &reader,
+        Some(body.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = call(&app, Method::POST, "/api/blogs", "not-a-token", Some(body)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn invalid_posts_are_rejected() {
+    let (app, db) = app(&[]).await;
+    let (_, token) = token_for(&app, &db, "author@example.com", "author").await;
+
+    for body in [
+        json!({"title": "", "content": "Body"}),
+        json!({"title": "Title", "content": ""}),
+        json!({"title": "Title", "content": "Body", "slug": "Not A Slug"}),
+        json!({"title": "Title", "content": "Body", "status": "live"}),
+        json!({"title": "Title", "content": "Body", "tags": ["?!"]}),
+        json!({"title": "Title", "content": "Body", "status": "scheduled"}),
+        json!({"content": "Body"}),
+    ] {
+        let (status, _) = call(&app, Method::POST, "/api/blogs", &token, Some(body.clone())).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", body);
+    }
+
+    let post = create(&app, &token).await;
+    let uri = format!("/api/blogs/{}", post["id"]);
+    let (status, _) = call(
+        &app,
+        Method::PATCH,
+        &uri,
+        &token,
+        Some(json!({"title": ""})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn put_replaces_the_whole_post() {
+    let (app, db) = app(&[]).await;
+    let (_, token) = token_for(&app, &db, "author@example.com", "author").await;
+    let post = create(&app, &token).await;
+    let uri = format!("/api/blogs/{}", post["id"]);
+
+    // A partial body is not a replacement
+    let partial = json!({"title": "Rye"});
+    let (status, _) = call(&app, Method::PUT, &uri, &token, Some(partial)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = json!({"title": "Rye", "slug": "rye", "content": "Use a dark flour", "status": "published"});
+    let (status, replaced) = call(&app, Method::PUT, &uri, &token, Some(body)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        (replaced["title"].as_str(), replaced["slug"].as_str()),
+        (Some("Rye"), Some("rye"))
+    );
+    assert_eq!(replaced["excerpt"], Value::Null);
+    assert_eq!(replaced["category"], Value::Null);
+    assert!(tag_names(&replaced).is_empty());
+    assert_eq!(replaced["published_at"], post["published_at"]);
+}
+
+#[tokio::test]
+async fn patch_only_changes_the_fields_it_is_given() {
+    let (app, db) = app(&[]).await;
+    let (_, token) = token_for(&app, &db, "author@example.com", "author").await;
+    let post = create(&app, &token).await;
+    let uri = format!("/api/blogs/{}", post["id"]);
+
+    let (status, patched) = call(
+        &app,
+        Method::PATCH,
+        &uri,
+        &token,
+        Some(json!({"title": "Rye"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(patched["title"], "Rye");
+    assert_eq!(patched["slug"], post["slug"]);
+    assert_eq!(patched["excerpt"], "Bread notes");
+    assert_eq!(patched["category"], "baking");
+    assert_eq!(tag_names(&patched), tag_names(&post));
+}
+
+#[tokio::test]
+async fn only_the_owner_or_an_editor_can_write_a_post() {
+    let (app, db) = app(&[]).await;
+    let (_, owner) = token_for(&app, &db, "owner@example.com", "author").await;
+    let (_, other) = token_for(&app, &db, "other@example.com", "author").await;
+    let (_, editor) = token_for(&app, &db, "editor@example.com", "editor").await;
+    let post = create(&app, &owner).await;
+    let uri = format!("/api/blogs/{}", post["id"]);
+
+    let patch = json!({"title": "Taken over"});
+    let (status, _) = call(&app, Method::PATCH, &uri, &other, Some(patch.clone())).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = call(&app, Method::DELETE, &uri, &other, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = call(&app, Method::PATCH, &uri, &editor, Some(patch)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = call(&app, Method::DELETE, &uri, &owner, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = call(&app, Method::DELETE, &uri, &owner, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = call(
+        &app,
+        Method::PATCH,
+        "/api/blogs/404",
+        &owner,
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
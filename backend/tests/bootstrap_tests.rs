@@ -0,0 +1,72 @@
+// Tests for choosing admins: only ADMIN_EMAIL is promoted, and racing sign-ups for one email conflict
+
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use backend::{auth::bootstrap, entities::users};
+use common::{app, call_json, PASSWORD};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use serde_json::{json, Value};
+
+/// An admin from before accounts had passwords, as the role migration leaves it.
+async fn passwordless_admin(db: &DatabaseConnection) {
+    users::ActiveModel {
+        email: Set("demo@example.com".to_string()),
+        name: Set("Demo User".to_string()),
+        role: Set("admin".to_string()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .unwrap();
+}
+
+async fn try_register(app: &Router, email: &str) -> (StatusCode, Value) {
+    let body = json!({"email": email, "name": "Someone", "password": PASSWORD});
+    call_json(app, Method::POST, "/api/auth/register", None, Some(body)).await
+}
+
+/// Registers `email` and returns the role it was given.
+async fn register(app: &Router, email: &str) -> String {
+    common::register(app, email).await["user"]["role"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn registering_never_makes_an_admin_on_its_own() {
+    let (fresh, _) = app(&[]).await;
+    assert_eq!(register(&fresh, "ada@example.com").await, "reader");
+    assert_eq!(register(&fresh, "bob@example.com").await, "reader");
+
+    // Nor when the only admin cannot sign in
+    let (upgraded, db) = app(&[]).await;
+    passwordless_admin(&db).await;
+    assert_eq!(register(&upgraded, "ada@example.com").await, "reader");
+}
+
+#[tokio::test]
+async fn admin_email_is_promoted_even_when_an_admin_exists() {
+    let (app, db) = app(&[("ADMIN_EMAIL", "owner@example.com")]).await;
+    passwordless_admin(&db).await;
+    assert_eq!(register(&app, "ada@example.com").await, "reader");
+    assert_eq!(register(&app, "owner@example.com").await, "admin");
+
+    assert!(bootstrap::promote(&db, "ada@example.com").await.unwrap());
+    assert!(!bootstrap::promote(&db, "nobody@example.com").await.unwrap());
+}
+
+#[tokio::test]
+async fn concurrent_sign_ups_for_one_email_conflict() {
+    let (app, _db) = app(&[]).await;
+    let results = tokio::join!(
+        try_register(&app, "ada@example.com"),
+        try_register(&app, "ada@example.com"),
+        try_register(&app, "ada@example.com"),
+        try_register(&app, "ada@example.com"),
+    );
+    let statuses = [results.0 .0, results.1 .0, results.2 .0, results.3 .0];
+    assert_eq!(statuses.iter().filter(|s| **s == StatusCode::CREATED).count(), 1);
+    assert_eq!(statuses.iter().filter(|s| **s == StatusCode::CONFLICT).count(), 3);
+}
@@ -0,0 +1,108 @@
+// Tests for threading reader comments and capping how deep replies nest
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use backend::{
+    api::handlers::{thread, CommentResponse, MAX_COMMENT_DEPTH},
+    entities::comments,
+};
+use chrono::Utc;
+use common::{app, call_json, token_for};
+use serde_json::json;
+
+fn depth(comments: &[CommentResponse]) -> usize {
+    comments.iter().map(|c| 1 + depth(&c.replies)).max().unwrap_or(0)
+}
+
+/// The replies under the first comment `level` levels down.
+fn threaded_at(comments: &[CommentResponse], level: usize) -> &[CommentResponse] {
+    match level {
+        0 => comments,
+        _ => threaded_at(&comments[0].replies, level - 1),
+    }
+}
+
+fn comment(id: i32, parent_id: Option<i32>) -> comments::Model {
+    comments::Model {
+        id,
+        blog_id: 1,
+        parent_id,
+        user_id: None,
+        author_name: format!("Reader {}", id),
+        author_email: None,
+        content: "Hi".to_string(),
+        status: "approved".to_string(),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn replies_nest_under_their_parents_in_order() {
+    let threaded = thread(vec![
+        comment(1, None),
+        comment(2, Some(1)),
+        comment(3, None),
+        comment(4, Some(2)),
+        comment(5, Some(1)),
+    ]);
+
+    let shape: Vec<(i32, Vec<i32>)> = threaded
+        .iter()
+        .map(|c| (c.id, c.replies.iter().map(|r| r.id).collect()))
+        .collect();
+    assert_eq!(shape, vec![(1, vec![2, 5]), (3, vec![])]);
+    assert_eq!(threaded[0].replies[0].replies[0].id, 4);
+}
+
+#[test]
+fn replies_to_hidden_comments_are_dropped() {
+    // Comment 1 is pending, so it and the reply beneath it stay hidden
+    let threaded = thread(vec![comment(2, Some(1)), comment(3, None)]);
+    assert_eq!(threaded.iter().map(|c| c.id).collect::<Vec<_>>(), vec![3]);
+}
+
+#[test]
+fn replies_below_the_deepest_level_are_listed_flat() {
+    // A single chain twice as deep as threads may nest
+    let chain: Vec<_> = (1..=2 * MAX_COMMENT_DEPTH as i32)
+        .map(|id| comment(id, (id > 1).then(|| id - 1)))
+        .collect();
+    let threaded = thread(chain);
+    assert_eq!(depth(&threaded), MAX_COMMENT_DEPTH);
+    let flat: Vec<_> = threaded_at(&threaded, MAX_COMMENT_DEPTH - 1).iter().map(|c| c.id).collect();
+    assert_eq!(flat, (MAX_COMMENT_DEPTH as i32..=2 * MAX_COMMENT_DEPTH as i32).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn replies_at_the_deepest_level_go_alongside_their_parent() {
+    let (app, db) = app(&[]).await;
+    let (_, token) = token_for(&app, &db, "ada@example.com", "admin").await;
+    let token = Some(token.as_str());
+    let post = json!({"title": "Hello", "content": "Hi", "status": "published"});
+    assert_eq!(call_json(&app, Method::POST, "/api/blogs", token, Some(post)).await.0, StatusCode::CREATED);
+
+    let mut parent: Option<i64> = None;
+    let mut grandparent: Option<i64> = None;
+    for level in 1..=MAX_COMMENT_DEPTH + 1 {
+        let body = json!({"content": format!("Level {}", level), "parent_id": parent});
+        let (status, created) = call_json(&app, Method::POST, "/api/blogs/hello/comments", token, Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        if level > MAX_COMMENT_DEPTH {
+            assert_eq!(created["parent_id"].as_i64(), grandparent);
+        } else {
+            assert_eq!(created["parent_id"].as_i64(), parent);
+        }
+        grandparent = parent;
+        parent = created["id"].as_i64();
+    }
+
+    let (_, listed) = call_json(&app, Method::GET, "/api/blogs/hello/comments", token, None).await;
+    let mut level = &listed;
+    for _ in 1..MAX_COMMENT_DEPTH {
+        level = &level[0]["replies"];
+    }
+    assert_eq!(level.as_array().unwrap().len(), 2);
+    assert!(level[0]["replies"].as_array().unwrap().is_empty());
+}
@@ -1,25 +1,135 @@
-// This file contains helper functions and utilities that multiple tests can use
-// Think of it as a toolbox of useful functions for testing
-
-use sqlx::SqlitePool;
-
-// This function creates a test database in memory (not saved to disk)
-// It's perfect for testing because:
-// 1. It's fast (everything happens in RAM)
-// 2. It's clean (starts fresh for each test)
-// 3. It doesn't interfere with your real database
-pub async fn setup_test_db() -> SqlitePool {
-    // Create a database connection that only exists in memory
-    let pool = SqlitePool::connect(":memory:")
-        .await
-        .expect("Failed to create test database");
-    
-    // Run all our migrations on the test database
-    // This gives us the same table structure as our real database
-    sqlx::migrate!("../migrations")
-        .run(&pool)
-        .await
-        .expect("Failed to run migrations");
-    
-    pool  // Return the database connection for tests to use
-}
\ No newline at end of file
+// Fixtures shared by the test binaries: a migrated in-memory database, the
+// router over it, and requests against that router
+// Each binary uses only some of these
+#![allow(dead_code)]
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::ConnectInfo,
+    http::{header, Method, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use backend::{
+    api::routes::create_routes, config::Config, entities::users, graphql::Schemas,
+    migration::Migrator, storage::LocalDisk, AppState,
+};
+use sea_orm::{ActiveModelTrait, Database, DatabaseConnection, EntityTrait, Set};
+use sea_orm_migration::MigratorTrait;
+use serde_json::{json, Value};
+use std::{net::SocketAddr, sync::Arc};
+use tower::ServiceExt;
+
+pub const PASSWORD: &str = "correct horse";
+
+/// A fresh in-memory database with every migration applied.
+pub async fn setup_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    db
+}
+
+/// Inserts the author that seeded posts belong to, as user 1.
+pub async fn seed_author(db: &DatabaseConnection) {
+    users::ActiveModel {
+        email: Set("author@example.com".to_string()),
+        name: Set("Author".to_string()),
+        role: Set("author".to_string()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .unwrap();
+}
+
+/// The default configuration with `env` set on top.
+pub fn config(env: &[(&str, &str)]) -> Config {
+    Config::from_sources(None, |name| {
+        env.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+    })
+    .unwrap()
+}
+
+/// The router over `db`, storing media in the temp directory.
+pub fn app_with(db: &DatabaseConnection, env: &[(&str, &str)]) -> Router {
+    create_routes(AppState {
+        graphql: Schemas::new(db.clone()).unwrap(),
+        db: db.clone(),
+        storage: Arc::new(LocalDisk::new(std::env::temp_dir())),
+        config: Arc::new(config(env)),
+    })
+}
+
+/// The router over a fresh database.
+pub async fn app(env: &[(&str, &str)]) -> (Router, DatabaseConnection) {
+    let db = setup_db().await;
+    (app_with(&db, env), db)
+}
+
+/// Sends `request` from a fixed client address unless it names its own, as
+/// handlers that count views need one.
+pub async fn call(app: &Router, mut request: Request<Body>) -> Response {
+    if request.extensions().get::<ConnectInfo<SocketAddr>>().is_none() {
+        let peer: SocketAddr = "203.0.113.7:50000".parse().unwrap();
+        request.extensions_mut().insert(ConnectInfo(peer));
+    }
+    app.clone().oneshot(request).await.unwrap()
+}
+
+pub async fn body_text(response: Response) -> String {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+/// The body as JSON, or `Null` when it is empty or not JSON.
+pub async fn body_json(response: Response) -> Value {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap_or(Value::Null)
+}
+
+/// A JSON request, signed with `token` if given.
+pub fn json_request(method: Method, uri: &str, token: Option<&str>, body: Option<Value>) -> Request<Body> {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    request
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .unwrap()
+}
+
+/// Sends a JSON request and returns the status and the JSON body.
+pub async fn call_json(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    token: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let response = call(app, json_request(method, uri, token, body)).await;
+    (response.status(), body_json(response).await)
+}
+
+/// Registers `email` and returns the new session.
+pub async fn register(app: &Router, email: &str) -> Value {
+    let account = json!({"email": email, "name": email, "password": PASSWORD});
+    let (status, session) = call_json(app, Method::POST, "/api/auth/register", None, Some(account)).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", session);
+    session
+}
+
+/// Registers an account, gives it `role` and signs in again so the token
+/// carries it. Returns the user id and the token.
+pub async fn token_for(app: &Router, db: &DatabaseConnection, email: &str, role: &str) -> (i32, String) {
+    let id = register(app, email).await["user"]["id"].as_i64().unwrap() as i32;
+    let mut user: users::ActiveModel = users::Entity::find_by_id(id).one(db).await.unwrap().unwrap().into();
+    user.role = Set(role.to_string());
+    user.update(db).await.unwrap();
+    let credentials = json!({"email": email, "password": PASSWORD});
+    let (status, session) = call_json(app, Method::POST, "/api/auth/login", None, Some(credentials)).await;
+    assert_eq!(status, StatusCode::OK);
+    (id, session["token"].as_str().unwrap().to_string())
+}
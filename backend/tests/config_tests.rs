@@ -0,0 +1,101 @@
+// Tests for loading the typed configuration from TOML text and an environment map
+
+use backend::config::{Config, Environment};
+use std::collections::HashMap;
+
+const STRONG_SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+fn load(toml: Option<&str>, env: &[(&str, &str)]) -> Result<Config, String> {
+    let env: HashMap<String, String> = env
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    Config::from_sources(toml, |name| env.get(name).cloned()).map_err(|e| e.to_string())
+}
+
+#[test]
+fn defaults_suit_local_development() {
+    let config = load(None, &[]).unwrap();
+    assert_eq!(config.environment, Environment::Development);
+    assert_eq!((config.host.to_string(), config.port), ("0.0.0.0".to_string(), 3000));
+    assert_eq!((config.access_token_ttl_minutes, config.refresh_token_ttl_days), (15, 30));
+    assert!(config.cors_origins.is_empty());
+    assert_eq!(config.admin_config_path, "pro_admin");
+    assert_eq!(config.admin_email, None);
+    assert!(!config.jwt_secret.is_empty());
+}
+
+#[test]
+fn environment_overrides_the_settings_file() {
+    let toml = r#"
+        port = 8000
+        access_token_ttl_minutes = 5
+        cors_origins = ["https://blog.example.com"]
+    "#;
+    let config = load(Some(toml), &[("PORT", "9000"), ("HOST", "127.0.0.1")]).unwrap();
+    assert_eq!(config.port, 9000);
+    assert_eq!(config.host.to_string(), "127.0.0.1");
+    assert_eq!(config.access_token_ttl_minutes, 5);
+    assert_eq!(config.cors_origins, vec!["https://blog.example.com"]);
+
+    let config = load(Some(toml), &[("CORS_ORIGINS", "*")]).unwrap();
+    assert!(config.cors_origins.is_empty());
+}
+
+#[test]
+fn production_refuses_missing_or_placeholder_secrets() {
+    let production = [("APP_ENV", "production"), ("PUBLIC_URL", "https://api.example.com/")];
+    assert!(load(None, &production).unwrap_err().contains("JWT_SECRET"));
+    let placeholder = [production.as_slice(), &[("JWT_SECRET", "default_secret")]].concat();
+    assert!(load(None, &placeholder).unwrap_err().contains("JWT_SECRET"));
+    let strong = [production.as_slice(), &[("JWT_SECRET", STRONG_SECRET)]].concat();
+    let config = load(None, &strong).unwrap();
+    assert!(config.is_production());
+    assert_eq!(config.public_url.as_deref(), Some("https://api.example.com"));
+}
+
+#[test]
+fn production_needs_a_public_url() {
+    let error = load(None, &[("APP_ENV", "production"), ("JWT_SECRET", STRONG_SECRET)]).unwrap_err();
+    assert!(error.contains("PUBLIC_URL"));
+    assert!(load(None, &[("PUBLIC_URL", "api.example.com")]).unwrap_err().contains("PUBLIC_URL"));
+    assert_eq!(load(None, &[]).unwrap().public_url, None);
+}
+
+#[test]
+fn invalid_values_are_reported() {
+    assert!(load(None, &[("PORT", "eighty")]).unwrap_err().contains("PORT"));
+    assert!(load(None, &[("ACCESS_TOKEN_TTL_MINUTES", "0")]).is_err());
+    assert!(load(None, &[("REFRESH_TOKEN_TTL_DAYS", "400")]).is_err());
+    assert!(load(None, &[("CORS_ORIGINS", "example.com")]).is_err());
+    assert!(load(None, &[("SITE_URL", "example.com")]).unwrap_err().contains("SITE_URL"));
+    assert!(load(None, &[("MEDIA_MAX_BYTES", "0")]).is_err());
+    assert!(load(None, &[("SCHEDULER_INTERVAL_SECS", "0")]).is_err());
+    assert!(load(None, &[("VIEW_DEDUP_WINDOW_MINUTES", "-5")]).is_err());
+    assert!(load(Some("prot = 1"), &[]).unwrap_err().contains("settings file"));
+}
+
+#[test]
+fn feature_settings_load_like_the_rest() {
+    let config = load(None, &[]).unwrap();
+    assert_eq!(config.site_url, "http://127.0.0.1:8080");
+    assert_eq!(config.robots_disallow, vec!["/admin", "/api/", "/login"]);
+    assert_eq!((config.media_dir.as_str(), config.media_max_bytes), ("./media", 10 * 1024 * 1024));
+    assert_eq!((config.scheduler_interval_secs, config.analytics_rollup_interval_secs), (30, 60));
+    assert_eq!((config.view_dedup_window_minutes, config.view_retention_days), (30, None));
+    assert!(!config.trust_proxy);
+
+    let toml = r#"
+        site_url = "https://blog.example.com/"
+        robots_disallow = ["/drafts"]
+        view_retention_days = 90
+        trust_proxy = true
+    "#;
+    let config = load(Some(toml), &[("ROBOTS_DISALLOW", ""), ("MEDIA_DIR", "/srv/media")]).unwrap();
+    assert_eq!(config.site_url, "https://blog.example.com");
+    assert!(config.robots_disallow.is_empty());
+    assert_eq!(config.media_dir, "/srv/media");
+    assert_eq!(config.view_retention_days, Some(90));
+    assert!(config.trust_proxy);
+    assert_eq!(load(None, &[("VIEW_RETENTION_DAYS", "0")]).unwrap().view_retention_days, None);
+}
@@ -0,0 +1,81 @@
+// Tests for the API error envelope and request ids
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    middleware,
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use backend::{
+    api::request_id::{assign, REQUEST_ID_HEADER},
+    error::{ApiError, REQUEST_ID},
+};
+use common::body_json;
+use sea_orm::DbErr;
+use tower::ServiceExt;
+use validator::Validate;
+
+#[derive(Validate)]
+struct Signup {
+    #[validate(length(min = 8))]
+    password: String,
+}
+
+#[tokio::test]
+async fn errors_render_a_stable_envelope() {
+    let response = REQUEST_ID
+        .scope("req-1".to_string(), async {
+            ApiError::not_found("Blog not found").into_response()
+        })
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = body_json(response).await;
+    assert_eq!(body["error"]["code"], "not_found");
+    assert_eq!(body["error"]["message"], "Blog not found");
+    assert_eq!(body["error"]["request_id"], "req-1");
+    assert!(body["error"].get("details").is_none());
+}
+
+#[tokio::test]
+async fn validation_details_never_echo_submitted_values() {
+    let errors = Signup { password: "hunter2".to_string() }.validate().unwrap_err();
+    let response = ApiError::from(errors).into_response();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = body_json(response).await;
+    assert_eq!(body["error"]["code"], "validation_failed");
+    assert_eq!(body["error"]["details"]["password"][0]["code"], "length");
+    assert!(!body.to_string().contains("hunter2"));
+}
+
+#[tokio::test]
+async fn database_errors_hide_their_cause() {
+    let response = ApiError::from(DbErr::Custom("no such table: secrets".to_string())).into_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = body_json(response).await;
+    assert_eq!(body["error"]["code"], "database_error");
+    assert!(!body.to_string().contains("secrets"));
+}
+
+#[tokio::test]
+async fn request_ids_are_reused_or_generated_and_echoed() {
+    let app = Router::new()
+        .route("/", get(|| async { ApiError::forbidden("Nope") }))
+        .layer(middleware::from_fn(assign));
+
+    let request = Request::get("/").header(&REQUEST_ID_HEADER, "trace-42").body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.headers()[&REQUEST_ID_HEADER], "trace-42");
+    assert_eq!(body_json(response).await["error"]["request_id"], "trace-42");
+
+    // Ids that could smuggle anything into logs are replaced
+    let request = Request::get("/").header(&REQUEST_ID_HEADER, "a b\"c").body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let id = response.headers()[&REQUEST_ID_HEADER].to_str().unwrap().to_string();
+    assert_ne!(id, "a b\"c");
+    assert_eq!(body_json(response).await["error"]["request_id"], id.as_str());
+}
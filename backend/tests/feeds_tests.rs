@@ -0,0 +1,186 @@
+// Tests for the RSS, Atom and JSON feeds: formats, tag feeds, excerpt mode and caching
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use common::{app, body_text, call, json_request, token_for};
+use serde_json::{json, Value};
+
+async fn get(app: &Router, uri: &str) -> Response {
+    call(app, Request::get(uri).header(header::HOST, "blog.test").body(Body::empty()).unwrap()).await
+}
+
+async fn post(app: &Router, token: &str, title: &str, status: &str, tags: &[&str]) {
+    let body = json!({
+        "title": title,
+        "content": format!("The whole of {}", title),
+        "excerpt": format!("A taste of {}", title),
+        "status": status,
+        "tags": tags,
+    });
+    let request = json_request(Method::POST, "/api/blogs", Some(token), Some(body));
+    assert_eq!(call(app, request).await.status(), StatusCode::CREATED);
+}
+
+async fn blog() -> Router {
+    let (app, db) = app(&[("SITE_URL", "https://example.com")]).await;
+    let (_, token) = token_for(&app, &db, "ada@example.com", "admin").await;
+    post(&app, &token, "Rusty Pipes", "published", &["rust"]).await;
+    post(&app, &token, "Garden Notes", "published", &["garden"]).await;
+    post(&app, &token, "Half Written", "draft", &["rust"]).await;
+    app
+}
+
+fn content_type(response: &Response) -> &str {
+    response.headers()[header::CONTENT_TYPE].to_str().unwrap()
+}
+
+#[tokio::test]
+async fn feeds_list_published_posts_in_each_format() {
+    let app = blog().await;
+
+    let rss = get(&app, "/feed.xml").await;
+    assert_eq!(rss.status(), StatusCode::OK);
+    assert!(content_type(&rss).starts_with("application/rss+xml"));
+    let rss = body_text(rss).await;
+    assert!(rss.contains("<rss"));
+    assert!(rss.contains("https://example.com/blogs/rusty-pipes"));
+    assert!(rss.contains("Garden Notes"));
+    assert!(!rss.contains("Half Written"));
+
+    let atom = get(&app, "/atom.xml").await;
+    assert!(content_type(&atom).starts_with("application/atom+xml"));
+    let atom = body_text(atom).await;
+    assert!(atom.contains("<feed"));
+    assert_eq!(atom.matches("<entry>").count(), 2);
+
+    let json = get(&app, "/feed.json").await;
+    assert!(content_type(&json).starts_with("application/feed+json"));
+    let json: Value = serde_json::from_str(&body_text(json).await).unwrap();
+    assert_eq!(json["version"], "https://jsonfeed.org/version/1.1");
+    let titles: Vec<&str> = json["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles.len(), 2);
+    assert!(titles.contains(&"Rusty Pipes") && titles.contains(&"Garden Notes"));
+}
+
+#[tokio::test]
+async fn tag_feeds_only_list_posts_with_the_tag() {
+    let app = blog().await;
+
+    let json: Value = serde_json::from_str(&body_text(get(&app, "/tags/rust/feed.json").await).await).unwrap();
+    let items = json["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["title"], "Rusty Pipes");
+    assert_eq!(json["home_page_url"], "https://example.com/tags/rust");
+
+    let rss = body_text(get(&app, "/tags/garden/feed.xml").await).await;
+    assert!(rss.contains("Garden Notes") && !rss.contains("Rusty Pipes"));
+
+    assert_eq!(get(&app, "/tags/nope/atom.xml").await.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn excerpt_mode_leaves_out_the_body() {
+    let app = blog().await;
+
+    let full = get(&app, "/feed.json").await;
+    let full_tag = full.headers()[header::ETAG].clone();
+    let full: Value = serde_json::from_str(&body_text(full).await).unwrap();
+    assert!(full["items"][0]["content_html"].as_str().unwrap().contains("The whole of"));
+
+    let excerpt = get(&app, "/feed.json?content=excerpt").await;
+    assert_ne!(excerpt.headers()[header::ETAG], full_tag);
+    let excerpt: Value = serde_json::from_str(&body_text(excerpt).await).unwrap();
+    assert!(excerpt["items"][0].get("content_html").is_none());
+    assert!(excerpt["items"][0]["summary"].as_str().unwrap().starts_with("A taste of"));
+
+    let rss = body_text(get(&app, "/feed.xml?content=excerpt").await).await;
+    assert!(rss.contains("A taste of") && !rss.contains("The whole of"));
+}
+
+#[tokio::test]
+async fn unchanged_feeds_answer_304() {
+    let app = blog().await;
+    let etag = get(&app, "/atom.xml").await.headers()[header::ETAG].clone();
+
+    let conditional = |tag| {
+        Request::get("/atom.xml")
+            .header(header::HOST, "blog.test")
+            .header(header::IF_NONE_MATCH, tag)
+            .body(Body::empty())
+            .unwrap()
+    };
+    let cached = call(&app, conditional(etag.clone())).await;
+    assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(cached.headers()[header::ETAG], etag);
+    assert!(body_text(cached).await.is_empty());
+
+    let stale = call(&app, conditional("\"0123456789abcdef\"".parse().unwrap())).await;
+    assert_eq!(stale.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn removing_a_post_changes_the_etag() {
+    let (app, db) = app(&[]).await;
+    let (_, token) = token_for(&app, &db, "ada@example.com", "admin").await;
+    // One more than fits, so the feed stays full and keeps its newest post
+    for n in 1..=21 {
+        post(&app, &token, &format!("Post {}", n), "published", &[]).await;
+    }
+    let etag = get(&app, "/feed.xml").await.headers()[header::ETAG].clone();
+
+    let request = Request::delete("/api/blogs/10")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(call(&app, request).await.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::get("/feed.xml")
+        .header(header::HOST, "blog.test")
+        .header(header::IF_NONE_MATCH, etag.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = call(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers()[header::ETAG], etag);
+    let rss = body_text(response).await;
+    assert_eq!(rss.matches("<item>").count(), 20);
+    assert!(!rss.contains("Post 10<") && rss.contains("Post 1<"));
+}
+
+#[tokio::test]
+async fn links_use_the_public_url_over_the_request_host() {
+    let forwarded = |app: Router| async move {
+        let request = Request::get("/feed.json")
+            .header(header::HOST, "blog.test")
+            .header("x-forwarded-host", "evil.test")
+            .header("x-forwarded-proto", "https")
+            .body(Body::empty())
+            .unwrap();
+        let json: Value = serde_json::from_str(&body_text(call(&app, request).await).await).unwrap();
+        json["feed_url"].as_str().unwrap().to_string()
+    };
+
+    assert_eq!(forwarded(app(&[]).await.0).await, "http://blog.test/feed.json");
+    assert_eq!(
+        forwarded(app(&[("TRUST_PROXY", "true")]).await.0).await,
+        "https://evil.test/feed.json"
+    );
+    // A configured public URL wins over any header, so cached responses cannot
+    // be poisoned with another host
+    let configured = [("PUBLIC_URL", "https://blog.example.com/"), ("TRUST_PROXY", "true")];
+    assert_eq!(
+        forwarded(app(&configured).await.0).await,
+        "https://blog.example.com/feed.json"
+    );
+}
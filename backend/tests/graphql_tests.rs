@@ -0,0 +1,270 @@
+// Tests for the admin GraphQL schemas: operation classification, public
+// access to published posts, limits, and reusing the schemas built at startup
+
+mod common;
+
+use async_graphql::Request;
+use axum::{
+    body::Body,
+    http::{header, Request as HttpRequest},
+};
+use backend::{
+    api::routes::create_routes,
+    auth::{AuthUser, Role},
+    entities::{blogs, users},
+    graphql::{
+        self, classify, mutation_role, OperationKind, PublicReader, Schemas,
+        DEPTH_LIMIT, INTROSPECTION_DEPTH_LIMIT,
+    },
+    storage::LocalDisk,
+    AppState,
+};
+use common::{app_with, body_json, call, config, setup_db};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use serde_json::json;
+use std::sync::Arc;
+
+const QUERY: &str = "{ __typename }";
+const ROUNDS: usize = 20;
+
+async fn published_post(db: &DatabaseConnection, slug: &str) {
+    blogs::ActiveModel {
+        author_id: Set(1),
+        title: Set(slug.to_string()),
+        slug: Set(slug.to_string()),
+        content: Set("Body".to_string()),
+        status: Set(Some("published".to_string())),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .unwrap();
+}
+
+async fn author(db: &DatabaseConnection) {
+    users::ActiveModel {
+        email: Set("author@example.com".to_string()),
+        name: Set("Author".to_string()),
+        role: Set("author".to_string()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .unwrap();
+}
+
+#[test]
+fn operations_are_classified_from_the_document() {
+    let kind = |query: &str| classify(query, None).map(|op| op.kind);
+    assert_eq!(kind("{ __schema { types { name } } }"), Some(OperationKind::Introspection));
+    assert_eq!(
+        kind("query { ...Meta } fragment Meta on Query { __type(name: \"Blogs\") { name } }"),
+        Some(OperationKind::Introspection)
+    );
+    assert_eq!(kind("{ __typename blogs { nodes { id } } }"), Some(OperationKind::Query));
+    // Mentioning introspection fields no longer turns a mutation into one
+    assert_eq!(
+        kind("mutation { users_delete(filter: {}) __type: __typename }"),
+        Some(OperationKind::Mutation)
+    );
+    assert_eq!(kind("mutation {"), None);
+
+    let named = "query A { __typename } mutation B { blogs_delete(filter: {}) }";
+    assert_eq!(classify(named, None), None);
+    assert_eq!(classify(named, Some("B")).unwrap().kind, OperationKind::Mutation);
+}
+
+#[test]
+fn mutations_need_the_role_of_the_entities_they_touch() {
+    assert_eq!(mutation_role("blogs_update"), Role::Editor);
+    assert_eq!(mutation_role("blog_views_delete"), Role::Editor);
+    assert_eq!(mutation_role("users_create_one"), Role::Admin);
+
+    let mixed = classify("mutation { tags_delete(filter: {}) users_delete(filter: {}) }", None).unwrap();
+    assert_eq!(mixed.required_role(), Some(Role::Admin));
+    assert_eq!(classify("{ blogs { nodes { id } } }", None).unwrap().required_role(), None);
+}
+
+#[tokio::test]
+async fn public_readers_only_see_published_posts() {
+    let db = setup_db().await;
+    author(&db).await;
+    for (slug, status) in [("live", "published"), ("draft", "draft")] {
+        blogs::ActiveModel {
+            author_id: Set(1),
+            title: Set(slug.to_string()),
+            slug: Set(slug.to_string()),
+            content: Set("Body".to_string()),
+            status: Set(Some(status.to_string())),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    let schemas = Schemas::new(db).unwrap();
+
+    let published = r#"{ blogs(filters: { status: { eq: "published" } }) { nodes { title } } }"#;
+    let response = schemas.limited.execute(Request::new(published).data(PublicReader)).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert!(response.data.to_string().contains("live"));
+
+    let everything = "{ blogs { nodes { title } } }";
+    let response = schemas.limited.execute(Request::new(everything).data(PublicReader)).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(response.data.to_string(), r#"{blogs: {nodes: [{title: "live"}]}}"#);
+
+    // Filters and counts must not reveal that drafts exist, let alone their content
+    for (probe, live_rows) in [
+        (r#"{ blogs(filters: { status: { eq: "draft" } }) { pagination_info { total } } }"#, 0),
+        (
+            r#"{ blogs(filters: { content: { contains: "Body" } }, pagination: { page: { limit: 10, page: 0 } }) { pagination_info { total } } }"#,
+            1,
+        ),
+    ] {
+        let response = schemas.limited.execute(Request::new(probe).data(PublicReader)).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["blogs"]["pagination_info"]["total"], live_rows, "{}", probe);
+    }
+    let editor = AuthUser { id: 1, role: Role::Editor };
+    let response = schemas.limited.execute(Request::new(everything).data(editor)).await;
+    assert!(response.data.to_string().contains("draft"));
+
+    let users = "{ users { nodes { email } } }";
+    let response = schemas.limited.execute(Request::new(users).data(PublicReader)).await;
+    assert!(!response.errors.is_empty());
+    let anonymous = schemas.limited.execute(Request::new(published)).await;
+    assert!(!anonymous.errors.is_empty());
+}
+
+/// Names of the `blogs` fields on the `Query` or `Mutation` type.
+async fn blog_fields(schemas: &Schemas, root: &str) -> Vec<String> {
+    let query = format!("{{ __type(name: \"{}\") {{ fields {{ name }} }} }}", root);
+    let response = schemas.introspection.execute(Request::new(query)).await;
+    let data = response.data.into_json().unwrap();
+    let mut names: Vec<String> = data["__type"]["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|field| field["name"].as_str())
+        .filter(|name| name.starts_with("blogs"))
+        .map(String::from)
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn blogs_get_one_query_and_the_four_mutations() {
+    let schemas = Schemas::new(setup_db().await).unwrap();
+    assert_eq!(blog_fields(&schemas, "Query").await, vec!["blogs"]);
+    assert_eq!(
+        blog_fields(&schemas, "Mutation").await,
+        vec!["blogs_create_batch", "blogs_create_one", "blogs_delete", "blogs_update"]
+    );
+}
+
+/// The query GraphiQL sends to load the schema, with `ofType` unwrapped nine
+/// levels deep.
+const INTROSPECTION_QUERY: &str = "
+    query IntrospectionQuery {
+      __schema {
+        queryType { name } mutationType { name } subscriptionType { name }
+        types { ...FullType }
+        directives { name description locations args { ...InputValue } }
+      }
+    }
+    fragment FullType on __Type {
+      kind name description
+      fields(includeDeprecated: true) {
+        name description args { ...InputValue } type { ...TypeRef } isDeprecated deprecationReason
+      }
+      inputFields { ...InputValue }
+      interfaces { ...TypeRef }
+      enumValues(includeDeprecated: true) { name description isDeprecated deprecationReason }
+      possibleTypes { ...TypeRef }
+    }
+    fragment InputValue on __InputValue { name description type { ...TypeRef } defaultValue }
+    fragment TypeRef on __Type {
+      kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name
+      ofType { kind name ofType { kind name ofType { kind name ofType { kind name
+      ofType { kind name } } } } } } } } }
+    }
+";
+
+/// `__schema { types { fields { type { ofType { ... name } } } } }` nested so
+/// the document is `depth` fields deep.
+fn nested_introspection(depth: usize) -> String {
+    let of_types = depth - 5;
+    format!(
+        "{{ __schema {{ types {{ fields {{ type {{ {}name{} }} }} }} }} }}",
+        "ofType { ".repeat(of_types),
+        " }".repeat(of_types)
+    )
+}
+
+#[tokio::test]
+async fn deep_queries_are_rejected_even_for_introspection() {
+    let schemas = Schemas::new(setup_db().await).unwrap();
+
+    let limited = schemas.limited.execute(Request::new(nested_introspection(DEPTH_LIMIT + 1))).await;
+    assert!(!limited.errors.is_empty(), "depth limit of {} not applied", DEPTH_LIMIT);
+
+    // Introspection gets more room, enough for the admin panel's own query
+    let response = schemas.introspection.execute(Request::new(INTROSPECTION_QUERY)).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let allowed = nested_introspection(INTROSPECTION_DEPTH_LIMIT);
+    let response = schemas.introspection.execute(Request::new(allowed)).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+    // But not unlimited room: anyone may send these
+    let deep = nested_introspection(INTROSPECTION_DEPTH_LIMIT + 1);
+    let response = schemas.introspection.execute(Request::new(deep)).await;
+    assert!(!response.errors.is_empty(), "depth limit of {} not applied", INTROSPECTION_DEPTH_LIMIT);
+}
+
+/// Requests run on the schemas in `AppState`, built once at startup, rather
+/// than on one built per request from `AppState::db`: a post that only exists
+/// in the database the schemas were built with is what the endpoint returns.
+#[tokio::test]
+async fn requests_reuse_the_schemas_built_at_startup() {
+    let built_with = setup_db().await;
+    author(&built_with).await;
+    published_post(&built_with, "from-startup").await;
+    let app = create_routes(AppState {
+        graphql: Schemas::new(built_with).unwrap(),
+        db: setup_db().await,
+        storage: Arc::new(LocalDisk::new(std::env::temp_dir())),
+        config: Arc::new(config(&[])),
+    });
+
+    let request = HttpRequest::post("/api/graphql")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({"query": "{ blogs { nodes { slug } } }"}).to_string()))
+        .unwrap();
+    let body = body_json(call(&app, request).await).await;
+    assert_eq!(body["data"]["blogs"]["nodes"], json!([{"slug": "from-startup"}]));
+}
+
+/// Rebuilding the schema registers every entity again, which is what made
+/// requests slow; counting builds checks that none happen per request without
+/// depending on how fast the machine is.
+#[tokio::test]
+async fn requests_build_no_schemas() {
+    let db = setup_db().await;
+    let before = graphql::schemas_built();
+    let app = app_with(&db, &[]);
+    assert_eq!(graphql::schemas_built(), before + 2);
+
+    for _ in 0..ROUNDS {
+        for query in [QUERY, INTROSPECTION_QUERY, "{ blogs { nodes { slug } } }"] {
+            let request = HttpRequest::post("/api/graphql")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({"query": query}).to_string()))
+                .unwrap();
+            assert!(call(&app, request).await.status().is_success());
+        }
+    }
+    assert_eq!(graphql::schemas_built(), before + 2);
+}
@@ -4,20 +4,17 @@
 
 mod common;  // Import our shared test utilities
 
-use axum_test::TestServer;  // A tool to test web applications
+use axum::{body::Body, http::{Request, StatusCode}};
 
 // This test checks that our health endpoint works correctly
 #[tokio::test]  // This tells Rust this is an async test
 async fn test_health_check() {
-    // Create a test version of our web application
-    let app = backend::create_app().await;
-    
-    // Start a test server with our app
-    let server = TestServer::new(app).unwrap();
+    // Create a test version of our web application over an in-memory database
+    let (app, _db) = common::app(&[]).await;
     
     // Make a GET request to the /health endpoint
-    let response = server.get("/health").await;
+    let response = common::call(&app, Request::get("/health").body(Body::empty()).unwrap()).await;
     
     // Check that we get a successful response (status code 200)
-    assert_eq!(response.status_code(), 200);
-}
\ No newline at end of file
+    assert_eq!(response.status(), StatusCode::OK);
+}
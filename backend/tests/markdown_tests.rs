@@ -0,0 +1,32 @@
+// Tests for Markdown rendering of blog posts
+// The rendered HTML is injected into the page as-is, so these focus on
+// the GFM features authors rely on and on what the sanitiser strips
+
+use backend::markdown::render;
+
+#[test]
+fn renders_gfm_tables_and_task_lists() {
+    let html = render("| a | b |\n|---|---|\n| 1 | 2 |\n\n- [x] done\n- [ ] todo\n");
+
+    assert!(html.contains("<table>"));
+    assert!(html.contains("<td>1</td>"));
+    assert!(html.contains(r#"type="checkbox""#));
+}
+
+#[test]
+fn keeps_footnote_links_working() {
+    let html = render("Claim[^1]\n\n[^1]: Source\n");
+
+    assert!(html.contains(r##"href="#1""##));
+    assert!(html.contains(r#"id="1""#));
+    assert!(html.contains("footnote-definition"));
+}
+
+#[test]
+fn strips_scripts_and_event_handlers() {
+    let html = render("<script>alert(1)</script>\n\n<img src=x onerror=alert(1)>\n\n[x](javascript:alert(1))");
+
+    assert!(!html.contains("<script"));
+    assert!(!html.contains("onerror"));
+    assert!(!html.contains("javascript:"));
+}
@@ -0,0 +1,145 @@
+// Tests for media uploads: variant sizing, re-encoding, limits and local disk storage
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use backend::{
+    api::{
+        handlers::{
+            fit_within, render_variant, snap_size, MediaFormat, MAX_SOURCE_DIMENSION, MAX_VARIANTS,
+            VARIANT_SIZES,
+        },
+        routes::create_routes,
+    },
+    entities::media,
+    graphql::Schemas,
+    storage::{LocalDisk, Storage},
+    AppState,
+};
+use common::{call, config, setup_db};
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use sea_orm::{ActiveModelTrait, Set};
+use std::{io::Cursor, sync::Arc};
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(RgbaImage::new(width, height))
+        .write_to(&mut out, ImageFormat::Png)
+        .unwrap();
+    out.into_inner()
+}
+
+#[test]
+fn variants_keep_the_aspect_ratio_and_never_upscale() {
+    assert_eq!(fit_within(800, 400, Some(200), None), (200, 100));
+    assert_eq!(fit_within(800, 400, Some(200), Some(50)), (100, 50));
+    assert_eq!(fit_within(800, 400, Some(2000), None), (800, 400));
+    assert_eq!(fit_within(800, 400, None, None), (800, 400));
+}
+
+#[test]
+fn render_variant_resizes_and_converts() {
+    let webp = render_variant(&png(64, 32), 16, 8, MediaFormat::Webp).unwrap();
+    assert_eq!(MediaFormat::sniff(&webp), Some(MediaFormat::Webp));
+
+    let decoded = image::load_from_memory(&webp).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (16, 8));
+}
+
+#[tokio::test]
+async fn local_disk_round_trips_and_rejects_escaping_keys() {
+    let root = std::env::temp_dir().join(format!("media-tests-{}", uuid::Uuid::new_v4()));
+    let disk = LocalDisk::new(&root);
+
+    disk.put("variants/1/16x8.webp", b"bytes").await.unwrap();
+    assert_eq!(disk.get("variants/1/16x8.webp").await.unwrap(), Some(b"bytes".to_vec()));
+
+    disk.delete("variants/1").await.unwrap();
+    assert_eq!(disk.get("variants/1/16x8.webp").await.unwrap(), None);
+    assert!(disk.get("../secret").await.is_err());
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn requested_sizes_snap_to_the_allowed_list() {
+    assert_eq!(snap_size(1), VARIANT_SIZES[0]);
+    assert_eq!(snap_size(161), 320);
+    assert_eq!(snap_size(320), 320);
+    assert_eq!(snap_size(100_000), *VARIANT_SIZES.last().unwrap());
+}
+
+#[test]
+fn render_variant_refuses_oversized_sources() {
+    let wide = png(MAX_SOURCE_DIMENSION + 1, 1);
+    assert!(render_variant(&wide, 16, 1, MediaFormat::Png).is_err());
+}
+
+async fn app_with_image() -> (Router, Arc<LocalDisk>, std::path::PathBuf) {
+    let root = std::env::temp_dir().join(format!("media-tests-{}", uuid::Uuid::new_v4()));
+    let disk = Arc::new(LocalDisk::new(&root));
+    disk.put("original.png", &png(640, 320)).await.unwrap();
+
+    let db = setup_db().await;
+    media::ActiveModel {
+        filename: Set("original.png".to_string()),
+        mime_type: Set("image/png".to_string()),
+        size_bytes: Set(0),
+        width: Set(640),
+        height: Set(320),
+        storage_key: Set("original.png".to_string()),
+        created_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+
+    let app = create_routes(AppState {
+        graphql: Schemas::new(db.clone()).unwrap(),
+        db,
+        storage: disk.clone(),
+        config: Arc::new(config(&[])),
+    });
+    (app, disk, root)
+}
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, String) {
+    let response = call(app, Request::builder().uri(uri).body(Body::empty()).unwrap()).await;
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|v| v.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    (response.status(), content_type)
+}
+
+#[tokio::test]
+async fn nearby_sizes_share_one_cached_variant() {
+    let (app, disk, root) = app_with_image().await;
+
+    for w in [100, 150, 160] {
+        assert_eq!(get(&app, &format!("/media/1?w={}", w)).await.0, StatusCode::OK);
+    }
+    assert_eq!(disk.list("variants/1").await.unwrap(), vec!["variants/1/160x80.png"]);
+    assert_eq!(get(&app, "/media/1?w=0").await.0, StatusCode::BAD_REQUEST);
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[tokio::test]
+async fn uncached_variants_fall_back_to_the_original_past_the_cap() {
+    let (app, disk, root) = app_with_image().await;
+    for n in 0..MAX_VARIANTS {
+        disk.put(&format!("variants/1/filler{}.png", n), b"bytes").await.unwrap();
+    }
+
+    assert_eq!(get(&app, "/media/1?w=320&format=webp").await, (StatusCode::OK, "image/png".to_string()));
+    assert_eq!(disk.list("variants/1").await.unwrap().len(), MAX_VARIANTS);
+
+    std::fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,109 @@
+// Tests that the OpenAPI document covers every route and is served
+
+mod common;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Method, Request, StatusCode},
+};
+use backend::{
+    api::{
+        openapi::{ApiDoc, SPEC_PATH},
+        routes::api_routes,
+    },
+    slug::RESERVED_SLUGS,
+};
+use common::{app, call, config};
+use utoipa::{openapi::PathItem, OpenApi};
+
+fn has_operation(item: &PathItem, method: &Method) -> bool {
+    match *method {
+        Method::GET => item.get.is_some(),
+        Method::POST => item.post.is_some(),
+        Method::PUT => item.put.is_some(),
+        Method::PATCH => item.patch.is_some(),
+        Method::DELETE => item.delete.is_some(),
+        _ => false,
+    }
+}
+
+#[test]
+fn every_route_is_documented() {
+    let routes = api_routes(&config(&[]));
+    assert!(routes.len() > 40, "only {} routes", routes.len());
+
+    let spec = ApiDoc::openapi();
+    let missing: Vec<String> = routes
+        .iter()
+        .filter(|route| {
+            !spec
+                .paths
+                .paths
+                .get(route.path)
+                .is_some_and(|item| has_operation(item, &route.method))
+        })
+        .map(|route| format!("{} {}", route.method, route.path))
+        .collect();
+    assert!(missing.is_empty(), "missing from the OpenAPI document: {:?}", missing);
+}
+
+/// The table is what the router serves: every route in it reaches its handler,
+/// rather than axum's bare 404 or 405.
+#[tokio::test]
+async fn the_router_serves_the_route_table() {
+    let (app, _) = app(&[]).await;
+    for route in api_routes(&config(&[])) {
+        let uri = route
+            .path
+            .replace("{slug}", "1")
+            .replace("{id}", "1")
+            .replace("{revision}", "1")
+            .replace("{tag}", "rust")
+            .replace("{file}", "posts-1.xml");
+        let request = Request::builder()
+            .method(route.method.clone())
+            .uri(&uri)
+            .body(Body::empty())
+            .unwrap();
+        let response = call(&app, request).await;
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let unrouted = status == StatusCode::METHOD_NOT_ALLOWED
+            || (status == StatusCode::NOT_FOUND && body.is_empty());
+        assert!(!unrouted, "{} {} is not routed", route.method, uri);
+    }
+}
+
+/// A post whose slug matched a fixed route under `/api/blogs/` could never be
+/// fetched, so every such segment has to be reserved.
+#[test]
+fn fixed_blog_routes_are_reserved_slugs() {
+    for route in api_routes(&config(&[])) {
+        let Some(rest) = route.path.strip_prefix("/api/blogs/") else {
+            continue;
+        };
+        let segment = rest.split('/').next().unwrap();
+        if !segment.starts_with('{') {
+            assert!(RESERVED_SLUGS.contains(&segment), "{} is not reserved", segment);
+        }
+    }
+}
+
+#[test]
+fn protected_operations_declare_the_bearer_scheme() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    assert!(spec["components"]["securitySchemes"]["bearer_auth"].is_object());
+    let create = &spec["paths"]["/api/blogs"]["post"];
+    assert_eq!(create["security"][0]["bearer_auth"], serde_json::json!([]));
+    assert!(spec["paths"]["/api/blogs"]["get"]["responses"]["400"]["content"].is_object());
+}
+
+#[tokio::test]
+async fn document_is_served() {
+    let request = Request::get(SPEC_PATH).body(Body::empty()).unwrap();
+    let response = call(&app(&[]).await.0, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let spec: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(spec["paths"]["/api/auth/login"]["post"].is_object());
+}
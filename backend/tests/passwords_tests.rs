@@ -0,0 +1,25 @@
+// Tests for password hashing: round trips, and unknown accounts failing like wrong passwords
+
+use backend::auth::{hash_password, verify_password};
+use std::time::Instant;
+
+#[tokio::test]
+async fn hashes_verify_only_their_own_password() {
+    let hash = hash_password("correct horse").await.unwrap();
+    assert!(verify_password("correct horse", Some(&hash)).await);
+    assert!(!verify_password("battery staple", Some(&hash)).await);
+    assert!(!verify_password("correct horse", Some("not a hash")).await);
+}
+
+#[tokio::test]
+async fn a_missing_hash_never_matches_but_still_costs_a_check() {
+    let hash = hash_password("correct horse").await.unwrap();
+    let started = Instant::now();
+    assert!(!verify_password("battery staple", Some(&hash)).await);
+    let wrong_password = started.elapsed();
+
+    let started = Instant::now();
+    assert!(!verify_password("battery staple", None).await);
+    // Same cost factor, so well within an order of magnitude of each other
+    assert!(started.elapsed() * 4 > wrong_password);
+}
@@ -0,0 +1,114 @@
+// Tests for preview links to unpublished posts and the tokens behind them
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use backend::{
+    auth::{
+        create_jwt,
+        preview::{create_preview_token, PreviewClaims},
+        Role,
+    },
+    config::Config,
+    entities::{blog_views, blogs},
+};
+use chrono::{Duration, Utc};
+use common::{app_with, body_json, call, config, seed_author, setup_db};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, PaginatorTrait, Set};
+
+const FIREFOX: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:131.0) Gecko/20100101 Firefox/131.0";
+
+struct Blog {
+    app: Router,
+    db: DatabaseConnection,
+    config: Config,
+    draft: i32,
+}
+
+async fn setup() -> Blog {
+    let db = setup_db().await;
+    seed_author(&db).await;
+    let mut ids = Vec::new();
+    for (slug, status) in [("draft", "draft"), ("live", "published")] {
+        let post = blogs::ActiveModel {
+            author_id: Set(1),
+            title: Set(slug.to_string()),
+            slug: Set(slug.to_string()),
+            content: Set("Body".to_string()),
+            status: Set(Some(status.to_string())),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        ids.push(post.id);
+    }
+
+    Blog { app: app_with(&db, &[]), config: config(&[]), db, draft: ids[0] }
+}
+
+async fn preview(blog: &Blog, slug: &str, token: &str) -> Response {
+    let request = Request::get(format!("/api/blogs/{}?preview={}", slug, token))
+        .header(header::USER_AGENT, FIREFOX)
+        .body(Body::empty())
+        .unwrap();
+    call(&blog.app, request).await
+}
+
+fn token_for(blog: &Blog, expires_at: chrono::DateTime<Utc>) -> String {
+    create_preview_token(blog.draft, expires_at, &blog.config).unwrap()
+}
+
+#[tokio::test]
+async fn a_valid_token_shows_the_draft_without_counting_a_view() {
+    let blog = setup().await;
+    let token = token_for(&blog, Utc::now() + Duration::hours(1));
+
+    let response = preview(&blog, "draft", &token).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["slug"], "draft");
+    assert_eq!(blog_views::Entity::find().count(&blog.db).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn expired_tokens_are_refused() {
+    let blog = setup().await;
+    let token = token_for(&blog, Utc::now() - Duration::hours(1));
+    assert_eq!(preview(&blog, "draft", &token).await.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn tokens_for_another_audience_are_refused() {
+    let blog = setup().await;
+    let claims = PreviewClaims {
+        blog_id: blog.draft,
+        aud: "somewhere-else".to_string(),
+        exp: (Utc::now() + Duration::hours(1)).timestamp() as usize,
+    };
+    let key = EncodingKey::from_secret(blog.config.jwt_secret.as_ref());
+    let token = encode(&Header::default(), &claims, &key).unwrap();
+    assert_eq!(preview(&blog, "draft", &token).await.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn session_tokens_are_not_preview_tokens() {
+    let blog = setup().await;
+    let session = create_jwt(&blog.draft.to_string(), Role::Admin, "session", &blog.config).unwrap();
+    assert_eq!(preview(&blog, "draft", &session).await.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn tokens_only_open_their_own_post() {
+    let blog = setup().await;
+    let token = token_for(&blog, Utc::now() + Duration::hours(1));
+
+    // The draft must not show up at another post's address
+    assert_eq!(preview(&blog, "live", &token).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(preview(&blog, "made-up", &token).await.status(), StatusCode::FORBIDDEN);
+}
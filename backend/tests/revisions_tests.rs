@@ -0,0 +1,123 @@
+// Tests for post revision history
+// Snapshots are taken by database triggers, so these write through SeaORM
+// directly, the same way the admin GraphQL mutations do
+
+mod common;
+
+use async_graphql::Request;
+use backend::{
+    api::handlers::{line_diff, LineOp},
+    auth::{AuthUser, Role},
+    entities::{blog_revisions, blogs, users},
+    graphql::Schemas,
+};
+use common::{seed_author, setup_db};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+#[tokio::test]
+async fn every_change_to_a_post_is_snapshotted() {
+    let db = setup_db().await;
+    seed_author(&db).await;
+
+    let post = blogs::ActiveModel {
+        author_id: Set(1),
+        title: Set("First".to_string()),
+        slug: Set("first".to_string()),
+        content: Set("Draft".to_string()),
+        ..Default::default()
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+
+    let mut edit: blogs::ActiveModel = post.into();
+    edit.content = Set("Final".to_string());
+    let post = edit.update(&db).await.unwrap();
+
+    // Saving without changes must not add a revision
+    let mut noop: blogs::ActiveModel = post.clone().into();
+    noop.title = Set(post.title.clone());
+    noop.update(&db).await.unwrap();
+
+    let revisions = blog_revisions::Entity::find()
+        .filter(blog_revisions::Column::BlogId.eq(post.id))
+        .order_by_asc(blog_revisions::Column::Revision)
+        .all(&db)
+        .await
+        .unwrap();
+    let contents: Vec<(i32, &str)> = revisions
+        .iter()
+        .map(|r| (r.revision, r.content.as_str()))
+        .collect();
+    assert_eq!(contents, vec![(1, "Draft"), (2, "Final")]);
+}
+
+#[test]
+fn line_diff_numbers_lines_on_both_sides() {
+    let diff = line_diff("a\nb\nc", "a\nc\nd");
+    let ops: Vec<(LineOp, Option<usize>, Option<usize>)> =
+        diff.iter().map(|l| (l.op, l.old_line, l.new_line)).collect();
+
+    assert_eq!(
+        ops,
+        vec![
+            (LineOp::Context, Some(1), Some(1)),
+            (LineOp::Removed, Some(2), None),
+            (LineOp::Context, Some(3), Some(2)),
+            (LineOp::Added, None, Some(3)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn graphql_edits_are_credited_to_their_own_editor() {
+    let db = setup_db().await;
+    for name in ["ada", "bob"] {
+        users::ActiveModel {
+            email: Set(format!("{}@example.com", name)),
+            name: Set(name.to_string()),
+            role: Set("editor".to_string()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    for slug in ["first", "second"] {
+        blogs::ActiveModel {
+            author_id: Set(1),
+            title: Set(slug.to_string()),
+            slug: Set(slug.to_string()),
+            content: Set("v1".to_string()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+
+    let schema = Schemas::new(db.clone()).unwrap().limited;
+    let edit = |editor: i32, post: i32| {
+        let query = format!(
+            r#"mutation {{ blogs_update(data: {{content: "v2"}}, filter: {{id: {{eq: {}}}}}) {{ id }} }}"#,
+            post
+        );
+        schema.execute(Request::new(query).data(AuthUser { id: editor, role: Role::Editor }))
+    };
+    let (a, b) = tokio::join!(edit(1, 2), edit(2, 1));
+    assert!(a.errors.is_empty() && b.errors.is_empty(), "{:?} {:?}", a.errors, b.errors);
+
+    let credited: Vec<(i32, i32, Option<i32>)> = blog_revisions::Entity::find()
+        .order_by_asc(blog_revisions::Column::BlogId)
+        .order_by_asc(blog_revisions::Column::Revision)
+        .all(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.blog_id, r.revision, r.editor_id))
+        .collect();
+    assert_eq!(
+        credited,
+        vec![(1, 1, None), (1, 2, Some(2)), (2, 1, None), (2, 2, Some(1))]
+    );
+}
@@ -0,0 +1,65 @@
+// Tests for the post status lifecycle and the scheduled publishing job
+// `publish_due` runs against an in-memory database with all migrations applied
+
+mod common;
+
+use backend::{
+    entities::{blogs, blogs::BlogStatus},
+    scheduler::publish_due,
+};
+use chrono::{Duration, Utc};
+use common::seed_author;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+
+async fn setup_db() -> DatabaseConnection {
+    let db = common::setup_db().await;
+    seed_author(&db).await;
+    db
+}
+
+async fn insert_post(db: &DatabaseConnection, slug: &str, status: BlogStatus, offset: Duration) -> i32 {
+    blogs::ActiveModel {
+        author_id: Set(1),
+        title: Set(slug.to_string()),
+        slug: Set(slug.to_string()),
+        content: Set("Body".to_string()),
+        status: Set(Some(status.as_str().to_string())),
+        published_at: Set(Some(Utc::now() + offset)),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .unwrap()
+    .id
+}
+
+#[test]
+fn publishing_with_a_future_date_schedules_the_post() {
+    let now = Utc::now();
+    let later = now + Duration::hours(1);
+
+    assert_eq!(
+        BlogStatus::Published.resolve(Some(later), now),
+        Ok((BlogStatus::Scheduled, Some(later)))
+    );
+    assert_eq!(
+        BlogStatus::Published.resolve(None, now),
+        Ok((BlogStatus::Published, Some(now)))
+    );
+    assert!(BlogStatus::Scheduled.resolve(None, now).is_err());
+    assert!(BlogStatus::Scheduled.resolve(Some(now - Duration::hours(1)), now).is_err());
+}
+
+#[tokio::test]
+async fn publish_due_only_flips_posts_whose_time_has_come() {
+    let db = setup_db().await;
+    let due = insert_post(&db, "due", BlogStatus::Scheduled, Duration::minutes(-1)).await;
+    let pending = insert_post(&db, "pending", BlogStatus::Scheduled, Duration::hours(1)).await;
+
+    assert_eq!(publish_due(&db).await.unwrap(), 1);
+
+    let due = blogs::Entity::find_by_id(due).one(&db).await.unwrap().unwrap();
+    let pending = blogs::Entity::find_by_id(pending).one(&db).await.unwrap().unwrap();
+    assert_eq!(due.blog_status(), BlogStatus::Published);
+    assert_eq!(pending.blog_status(), BlogStatus::Scheduled);
+}
@@ -0,0 +1,119 @@
+// Tests for full-text search: index triggers, ranking, pagination and snippets
+
+mod common;
+
+use axum::{body::Body, http::Request, Router};
+use backend::entities::blogs;
+use common::{app_with, body_json, call, seed_author, setup_db};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Set};
+use serde_json::Value;
+
+async fn setup() -> (Router, DatabaseConnection) {
+    let db = setup_db().await;
+    seed_author(&db).await;
+    (app_with(&db, &[]), db)
+}
+
+async fn post(db: &DatabaseConnection, slug: &str, title: &str, content: &str) -> blogs::Model {
+    blogs::ActiveModel {
+        author_id: Set(1),
+        title: Set(title.to_string()),
+        slug: Set(slug.to_string()),
+        content: Set(content.to_string()),
+        status: Set(Some("published".to_string())),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .unwrap()
+}
+
+async fn search(app: &Router, query: &str) -> Value {
+    let uri = format!("/api/blogs/search?{}", query);
+    body_json(call(app, Request::get(uri).body(Body::empty()).unwrap()).await).await
+}
+
+fn slugs(results: &Value) -> Vec<&str> {
+    results["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["slug"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn the_index_follows_inserts_updates_and_deletes() {
+    let (app, db) = setup().await;
+    let created = post(&db, "bread", "Sourdough", "Feed the starter daily").await;
+    assert_eq!(slugs(&search(&app, "q=starter").await), vec!["bread"]);
+
+    let mut edited: blogs::ActiveModel = created.into();
+    edited.content = Set("Knead the dough gently".to_string());
+    let edited = edited.update(&db).await.unwrap();
+    assert!(slugs(&search(&app, "q=starter").await).is_empty());
+    assert_eq!(slugs(&search(&app, "q=knead").await), vec!["bread"]);
+
+    blogs::Entity::delete_by_id(edited.id).exec(&db).await.unwrap();
+    assert!(slugs(&search(&app, "q=knead").await).is_empty());
+}
+
+#[tokio::test]
+async fn title_matches_rank_above_body_matches() {
+    let (app, db) = setup().await;
+    post(&db, "body", "Weekend notes", "A short word on gardening").await;
+    post(&db, "title", "Gardening", "Notes from the allotment").await;
+    let mut draft = post(&db, "draft", "Gardening drafts", "Gardening").await.into_active_model();
+    draft.status = Set(Some("draft".to_string()));
+    draft.update(&db).await.unwrap();
+    // Published but not yet due, as between a schedule falling due and the job running
+    let mut due = post(&db, "due", "Gardening soon", "Gardening").await.into_active_model();
+    due.published_at = Set(Some(chrono::Utc::now() + chrono::Duration::hours(1)));
+    due.update(&db).await.unwrap();
+
+    // The last word is matched as a prefix while the reader is still typing
+    assert_eq!(slugs(&search(&app, "q=garden").await), vec!["title", "body"]);
+}
+
+#[tokio::test]
+async fn results_are_paginated() {
+    let (app, db) = setup().await;
+    for n in 1..=5 {
+        post(&db, &format!("tea-{}", n), &format!("Tea {}", n), "Steep for three minutes").await;
+    }
+
+    let first = search(&app, "q=steep&per_page=2").await;
+    assert_eq!((first["total"].as_u64(), first["total_pages"].as_u64()), (Some(5), Some(3)));
+    assert_eq!(slugs(&first).len(), 2);
+    let last = search(&app, "q=steep&per_page=2&page=3").await;
+    assert_eq!(slugs(&last).len(), 1);
+
+    let mut seen: Vec<String> = Vec::new();
+    for page in 1..=3 {
+        let results = search(&app, &format!("q=steep&per_page=2&page={}", page)).await;
+        seen.extend(slugs(&results).into_iter().map(String::from));
+    }
+    seen.sort();
+    seen.dedup();
+    assert_eq!(seen.len(), 5);
+    assert!(slugs(&search(&app, "q=").await).is_empty());
+}
+
+#[tokio::test]
+async fn snippets_escape_html_and_only_mark_matches() {
+    let (app, db) = setup().await;
+    post(
+        &db,
+        "markup",
+        "Markup",
+        "Use <script>alert('x')</script> & \u{2}fake\u{3} tags around the widget",
+    )
+    .await;
+
+    let results = search(&app, "q=widget").await;
+    let snippet = results["items"][0]["snippet"].as_str().unwrap();
+    assert!(snippet.contains("&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt; &amp;"), "{}", snippet);
+    assert!(snippet.contains("<mark>widget</mark>"), "{}", snippet);
+    assert_eq!(snippet.matches("<mark>").count(), 1, "{}", snippet);
+    assert!(!snippet.contains(['\u{2}', '\u{3}']), "{}", snippet);
+}
@@ -0,0 +1,121 @@
+// Tests for refresh token rotation, reuse detection, logout and password changes
+
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{app, call_json, PASSWORD};
+use serde_json::{json, Value};
+
+async fn call(app: &Router, method: Method, path: &str, token: Option<&Value>, body: Value) -> (StatusCode, Value) {
+    call_json(app, method, path, token.and_then(Value::as_str), Some(body)).await
+}
+
+/// Signs in as Ada, registering her the first time.
+async fn sign_in(app: &Router) -> Value {
+    let credentials = json!({"email": "ada@example.com", "password": PASSWORD});
+    let (status, body) = call(app, Method::POST, "/api/auth/login", None, credentials).await;
+    if status == StatusCode::OK {
+        return body;
+    }
+    common::register(app, "ada@example.com").await
+}
+
+async fn refresh(app: &Router, session: &Value) -> (StatusCode, Value) {
+    let body = json!({"refresh_token": session["refresh_token"]});
+    call(app, Method::POST, "/api/auth/refresh", None, body).await
+}
+
+async fn current_user(app: &Router, session: &Value) -> StatusCode {
+    call(app, Method::GET, "/api/user/current", Some(&session["token"]), Value::Null).await.0
+}
+
+#[tokio::test]
+async fn refresh_rotates_both_tokens() {
+    let (app, _) = app(&[]).await;
+    let first = sign_in(&app).await;
+    assert!(first["expires_in"].as_u64().unwrap() <= 15 * 60);
+    assert_eq!(current_user(&app, &first).await, StatusCode::OK);
+
+    let (status, second) = refresh(&app, &first).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(second["refresh_token"], first["refresh_token"]);
+    assert_eq!(second["user"]["email"], "ada@example.com");
+    assert_eq!(current_user(&app, &second).await, StatusCode::OK);
+    // The access token issued alongside a spent refresh token stops working
+    assert_eq!(current_user(&app, &first).await, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn reusing_a_refresh_token_revokes_the_session() {
+    let (app, _) = app(&[]).await;
+    let first = sign_in(&app).await;
+    let (_, second) = refresh(&app, &first).await;
+
+    let (status, body) = refresh(&app, &first).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(body["error"]["message"].as_str().unwrap().contains("already used"));
+    // The legitimate holder is signed out too
+    assert_eq!(refresh(&app, &second).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(current_user(&app, &second).await, StatusCode::UNAUTHORIZED);
+
+    assert_eq!(refresh(&app, &json!({"refresh_token": "forged"})).await.0, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn logout_ends_one_session_and_logout_all_ends_every_one() {
+    let (app, _) = app(&[]).await;
+    let laptop = sign_in(&app).await;
+    let phone = sign_in(&app).await;
+    let tablet = sign_in(&app).await;
+
+    let body = json!({"refresh_token": laptop["refresh_token"]});
+    let (status, _) = call(&app, Method::POST, "/api/auth/logout", None, body).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(current_user(&app, &laptop).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(current_user(&app, &phone).await, StatusCode::OK);
+
+    let (status, _) = call(&app, Method::POST, "/api/auth/logout-all", Some(&phone["token"]), Value::Null).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(current_user(&app, &phone).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(refresh(&app, &tablet).await.0, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn logout_needs_the_current_secret_not_just_the_family() {
+    let (app, _) = app(&[]).await;
+    let first = sign_in(&app).await;
+    let (_, current) = refresh(&app, &first).await;
+    let token = current["refresh_token"].as_str().unwrap();
+    let family = token.split_once('.').unwrap().0;
+
+    for guess in [format!("{}.wrong", family), format!("{}.", family), family.to_string()] {
+        let body = json!({"refresh_token": guess});
+        let (status, _) = call(&app, Method::POST, "/api/auth/logout", None, body).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+    // Nor does the secret of a token that was already exchanged
+    let body = json!({"refresh_token": first["refresh_token"]});
+    call(&app, Method::POST, "/api/auth/logout", None, body).await;
+    assert_eq!(current_user(&app, &current).await, StatusCode::OK);
+    assert_eq!(refresh(&app, &current).await.0, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn changing_the_password_ends_every_other_session() {
+    let (app, _) = app(&[]).await;
+    let stolen = sign_in(&app).await;
+    let current = sign_in(&app).await;
+
+    let body = json!({"current_password": PASSWORD, "new_password": "battery staple"});
+    let (status, renewed) = call(&app, Method::PUT, "/api/user/password", Some(&current["token"]), body).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(current_user(&app, &renewed).await, StatusCode::OK);
+
+    for old in [&stolen, &current] {
+        assert_eq!(current_user(&app, old).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(refresh(&app, old).await.0, StatusCode::UNAUTHORIZED);
+    }
+}
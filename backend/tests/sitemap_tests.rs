@@ -0,0 +1,111 @@
+// Tests for crawler files: the sitemap, its index past 50,000 URLs, and robots.txt
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use backend::entities::blogs;
+use chrono::SecondsFormat;
+use common::{app_with, body_text, call, seed_author, setup_db};
+use sea_orm::{ActiveModelTrait, ConnectionTrait, DatabaseConnection, Set};
+use shared::routes::STATIC_PAGES;
+
+async fn setup(env: &[(&str, &str)]) -> (Router, DatabaseConnection) {
+    let db = setup_db().await;
+    seed_author(&db).await;
+    (app_with(&db, env), db)
+}
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, String) {
+    let request = Request::get(uri)
+        .header(header::HOST, "blog.test")
+        .body(Body::empty())
+        .unwrap();
+    let response = call(app, request).await;
+    (response.status(), body_text(response).await)
+}
+
+#[tokio::test]
+async fn small_sites_get_one_sitemap_with_lastmod() {
+    let (app, db) = setup(&[("SITE_URL", "https://example.com")]).await;
+    let mut updated = Vec::new();
+    for (slug, status) in [("live", "published"), ("hidden", "draft")] {
+        let post = blogs::ActiveModel {
+            author_id: Set(1),
+            title: Set(slug.to_string()),
+            slug: Set(slug.to_string()),
+            content: Set("Body".to_string()),
+            status: Set(Some(status.to_string())),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        updated.push(post.updated_at.unwrap());
+    }
+
+    let (status, xml) = get(&app, "/sitemap.xml").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(xml.contains("<urlset"));
+    for page in STATIC_PAGES {
+        let entry = format!("<url><loc>https://example.com{}</loc></url>", page);
+        assert!(xml.contains(&entry), "{}", page);
+    }
+    let lastmod = updated[0].to_rfc3339_opts(SecondsFormat::Secs, true);
+    assert!(xml.contains(&format!(
+        "<url><loc>https://example.com/blogs/live</loc><lastmod>{}</lastmod></url>",
+        lastmod
+    )));
+    assert!(!xml.contains("hidden"));
+    assert_eq!(xml.matches("<url>").count(), STATIC_PAGES.len() + 1);
+}
+
+#[tokio::test]
+async fn large_sites_get_an_index_of_50k_url_files() {
+    let (app, db) = setup(&[("PUBLIC_URL", "https://api.example.com")]).await;
+    // One more URL than fits in a single file
+    let posts = 50_000 - STATIC_PAGES.len() + 1;
+    db.execute_unprepared(&format!(
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < {})
+         INSERT INTO blogs (author_id, title, slug, content, status)
+         SELECT 1, 'Post ' || i, 'post-' || i, 'Body', 'published' FROM n",
+        posts
+    ))
+    .await
+    .unwrap();
+
+    let (_, index) = get(&app, "/sitemap.xml").await;
+    assert!(index.contains("<sitemapindex"));
+    assert_eq!(index.matches("<sitemap>").count(), 2);
+    assert!(index.contains("<loc>https://api.example.com/sitemaps/posts-2.xml</loc>"));
+
+    let (_, first) = get(&app, "/sitemaps/posts-1.xml").await;
+    assert_eq!(first.matches("<url>").count(), 50_000);
+    assert!(first.contains(&format!("/blogs/post-{}<", posts - 1)));
+    let (_, second) = get(&app, "/sitemaps/posts-2.xml").await;
+    assert_eq!(second.matches("<url>").count(), 1);
+    assert!(second.contains(&format!("/blogs/post-{}<", posts)));
+
+    for missing in ["/sitemaps/posts-3.xml", "/sitemaps/posts-0.xml", "/sitemaps/pages.xml"] {
+        assert_eq!(get(&app, missing).await.0, StatusCode::NOT_FOUND, "{}", missing);
+    }
+}
+
+#[tokio::test]
+async fn robots_txt_lists_the_rules_and_the_sitemap() {
+    let (app, _) = setup(&[]).await;
+    let (status, robots) = get(&app, "/robots.txt").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        robots,
+        "User-agent: *\nDisallow: /admin\nDisallow: /api/\nDisallow: /login\n\nSitemap: http://blog.test/sitemap.xml\n"
+    );
+
+    let (app, _) = setup(&[("ROBOTS_DISALLOW", ""), ("PUBLIC_URL", "https://api.example.com")]).await;
+    let (_, robots) = get(&app, "/robots.txt").await;
+    assert!(robots.starts_with("User-agent: *\nDisallow:\n\n"));
+    assert!(robots.ends_with("\nSitemap: https://api.example.com/sitemap.xml\n"));
+}
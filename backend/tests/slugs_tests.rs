@@ -0,0 +1,104 @@
+// Tests for post slugs: generated slugs, retired slugs and the redirect from them
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use backend::entities::blogs;
+use common::{app, body_json, call, json_request, token_for};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Set};
+use serde_json::{json, Value};
+
+/// A fresh blog and an admin's token.
+async fn blog() -> (Router, DatabaseConnection, String) {
+    let (app, db) = app(&[]).await;
+    let (_, token) = token_for(&app, &db, "ada@example.com", "admin").await;
+    (app, db, token)
+}
+
+async fn create(app: &Router, token: &str, body: Value) -> Response {
+    call(app, json_request(Method::POST, "/api/blogs", Some(token), Some(body))).await
+}
+
+async fn created_slug(app: &Router, token: &str, title: &str) -> String {
+    let body = json!({"title": title, "content": "Body", "status": "published"});
+    let response = create(app, token, body).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    body_json(response).await["slug"].as_str().unwrap().to_string()
+}
+
+/// Renames the post directly, as the admin GraphQL API would.
+async fn rename(db: &DatabaseConnection, id: i32, slug: &str) {
+    let post = blogs::Entity::find_by_id(id).one(db).await.unwrap().unwrap();
+    let mut post = post.into_active_model();
+    post.slug = Set(slug.to_string());
+    post.update(db).await.unwrap();
+}
+
+async fn get(app: &Router, uri: &str) -> Response {
+    call(app, Request::get(uri).body(Body::empty()).unwrap()).await
+}
+
+#[tokio::test]
+async fn generated_slugs_skip_taken_and_retired_ones() {
+    let (app, db, token) = blog().await;
+
+    assert_eq!(created_slug(&app, &token, "Hello, World!").await, "hello-world");
+    assert_eq!(created_slug(&app, &token, "Hello World").await, "hello-world-2");
+
+    // `hello-world` still redirects to the renamed post, so it stays reserved
+    rename(&db, 1, "greetings").await;
+    assert_eq!(created_slug(&app, &token, "Hello world").await, "hello-world-3");
+    assert_eq!(created_slug(&app, &token, "?!").await, "post");
+
+    let taken = json!({"title": "Again", "slug": "hello-world", "content": "Body"});
+    assert_eq!(create(&app, &token, taken).await.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn old_slugs_redirect_and_keep_the_query_string() {
+    let (app, db, token) = blog().await;
+    created_slug(&app, &token, "First name").await;
+    rename(&db, 1, "second-name").await;
+    rename(&db, 1, "third-name").await;
+
+    let tracked = "?utm_source=news&referrer=https%3A%2F%2Fexample.org";
+    for (old, query) in [("first-name", tracked), ("second-name", "")] {
+        let response = get(&app, &format!("/api/blogs/{}{}", old, query)).await;
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        let location = format!("/api/blogs/third-name{}", query);
+        assert_eq!(response.headers()[header::LOCATION], location.as_str());
+        assert_eq!(body_json(response).await, json!({"slug": "third-name", "location": location}));
+    }
+    assert_eq!(get(&app, "/api/blogs/third-name").await.status(), StatusCode::OK);
+
+    // Taking an old slug back stops it redirecting
+    rename(&db, 1, "first-name").await;
+    assert_eq!(get(&app, "/api/blogs/first-name").await.status(), StatusCode::OK);
+    assert_eq!(get(&app, "/api/blogs/never-used").await.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn unpublished_posts_do_not_redirect() {
+    let (app, db, token) = blog().await;
+    let draft = json!({"title": "Secret plans", "content": "Body", "status": "draft"});
+    assert_eq!(create(&app, &token, draft).await.status(), StatusCode::CREATED);
+    rename(&db, 1, "public-plans").await;
+
+    assert_eq!(get(&app, "/api/blogs/secret-plans").await.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn posts_cannot_take_a_reserved_slug() {
+    let (app, _, token) = blog().await;
+
+    assert_eq!(created_slug(&app, &token, "Search").await, "search-2");
+    assert_eq!(get(&app, "/api/blogs/search-2").await.status(), StatusCode::OK);
+
+    let reserved = json!({"title": "Search tips", "slug": "search", "content": "Body"});
+    assert_eq!(create(&app, &token, reserved).await.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -0,0 +1,116 @@
+// Tests for tags and categories: slugs, filtered listing and the tag cloud
+
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use backend::slug::slugify;
+use chrono::{Duration, Utc};
+use common::{app, call_json, token_for};
+use serde_json::{json, Value};
+
+async fn get(app: &Router, uri: &str) -> Value {
+    let (status, body) = call_json(app, Method::GET, uri, None, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", uri);
+    body
+}
+
+/// Signs in an admin and writes the posts: two live ones, a draft and a
+/// scheduled one, all tagged `rust`.
+async fn blog() -> (Router, String) {
+    let (app, db) = app(&[]).await;
+    let (_, token) = token_for(&app, &db, "ada@example.com", "admin").await;
+
+    let tomorrow = Utc::now() + Duration::days(1);
+    for body in [
+        json!({"title": "Ownership", "status": "published", "category": "programming", "tags": ["Rust", "Web Dev"]}),
+        json!({"title": "Crab cakes", "status": "published", "category": "cooking", "tags": ["rust"]}),
+        json!({"title": "Unfinished", "status": "draft", "category": "cooking", "tags": ["rust", "web dev", "drafts"]}),
+        json!({"title": "Next week", "status": "published", "published_at": tomorrow, "tags": ["rust", "soon"]}),
+    ] {
+        let mut body = body;
+        body["content"] = json!("Body");
+        let (status, _) = call_json(&app, Method::POST, "/api/blogs", Some(&token), Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+    (app, token)
+}
+
+fn slugs(list: &Value) -> Vec<&str> {
+    list["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["slug"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn slugs_are_lowercase_ascii_words() {
+    assert_eq!(slugify("Rust & WebAssembly!"), "rust-webassembly");
+    assert_eq!(slugify("  --Web   Dev--  "), "web-dev");
+    assert_eq!(slugify("Rust 2024"), "rust-2024");
+
+    // Letters outside ASCII separate words rather than ending up in the URL
+    assert_eq!(slugify("Café Crème"), "caf-cr-me");
+    assert_eq!(slugify("ÅNGSTRÖM unit"), "ngstr-m-unit");
+    assert!(slugify("Ünïcödé ñame").is_ascii());
+
+    // Nothing usable leaves an empty slug, which callers must handle
+    for input in ["", "   ", "?!", "日本語", "Ωμέγα", "🦀🦀"] {
+        assert_eq!(slugify(input), "", "{:?}", input);
+    }
+}
+
+#[tokio::test]
+async fn tags_without_a_slug_are_rejected() {
+    let (app, token) = blog().await;
+    for tags in [json!(["日本語"]), json!(["rust", "?!"])] {
+        let body = json!({"title": "Tagged", "content": "Body", "tags": tags});
+        let (status, _) = call_json(&app, Method::POST, "/api/blogs", Some(&token), Some(body)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", tags);
+    }
+}
+
+#[tokio::test]
+async fn listing_filters_by_tag_and_category() {
+    let (app, _) = blog().await;
+
+    assert_eq!(
+        slugs(&get(&app, "/api/blogs?tag=rust").await),
+        vec!["crab-cakes", "ownership"]
+    );
+    assert_eq!(
+        slugs(&get(&app, "/api/blogs?tag=web-dev").await),
+        vec!["ownership"]
+    );
+    assert_eq!(
+        slugs(&get(&app, "/api/blogs?category=cooking").await),
+        vec!["crab-cakes"]
+    );
+    let both = get(&app, "/api/blogs?tag=web-dev&category=cooking").await;
+    assert!(slugs(&both).is_empty());
+    assert_eq!(both["total"], 0);
+
+    // Tags match by slug, and empty filters are ignored
+    assert!(slugs(&get(&app, "/api/blogs?tag=Web%20Dev").await).is_empty());
+    assert_eq!(
+        slugs(&get(&app, "/api/blogs?tag=&category=").await).len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn the_tag_cloud_only_counts_live_posts() {
+    let (app, _) = blog().await;
+    let tags = get(&app, "/api/tags").await;
+    assert_eq!(
+        tags,
+        json!([
+            {"name": "Rust", "slug": "rust", "post_count": 2},
+            {"name": "Web Dev", "slug": "web-dev", "post_count": 1},
+        ])
+    );
+}
@@ -7,8 +7,13 @@ use crate::pages::{
     BlogsListPage,
     BlogDetailPage,
     NotFoundPage,
+    TagsPage,
+    SearchPage,
+    DashboardPage,
 };
 
+// Parameterless public pages belong in `shared::routes::STATIC_PAGES`, which
+// the backend sitemap lists
 #[derive(Clone, Routable, PartialEq)]
 pub enum Route {
     #[at("/")]
@@ -19,6 +24,17 @@ pub enum Route {
     Blogs,
     #[at("/blogs/:slug")]
     BlogDetail { slug: String },
+    #[at("/search")]
+    Search,
+    #[at("/tags")]
+    Tags,
+    #[at("/tags/:tag")]
+    TagArchive { tag: String },
+    #[at("/categories/:category")]
+    CategoryArchive { category: String },
+    /// Analytics for signed-in authors; not in the sitemap
+    #[at("/dashboard")]
+    Dashboard,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -30,6 +46,11 @@ pub fn switch(routes: Route) -> Html {
         Route::Health => html! { <HealthPage /> },
         Route::Blogs => html! { <BlogsListPage /> },
         Route::BlogDetail { slug } => html! { <BlogDetailPage slug={slug} /> },
+        Route::Search => html! { <SearchPage /> },
+        Route::Tags => html! { <TagsPage /> },
+        Route::TagArchive { tag } => html! { <BlogsListPage tag={Some(tag)} /> },
+        Route::CategoryArchive { category } => html! { <BlogsListPage category={Some(category)} /> },
+        Route::Dashboard => html! { <DashboardPage /> },
         Route::NotFound => html! { <NotFoundPage /> },
     }
 }
@@ -0,0 +1,124 @@
+use yew::prelude::*;
+
+/// One labelled value of a chart.
+#[derive(Clone, PartialEq)]
+pub struct ChartPoint {
+    pub label: String,
+    pub value: i64,
+}
+
+impl ChartPoint {
+    pub fn new(label: impl Into<String>, value: i64) -> Self {
+        Self { label: label.into(), value }
+    }
+}
+
+// Line chart drawing area, in SVG user units; the SVG scales to its container
+const WIDTH: f64 = 600.0;
+const HEIGHT: f64 = 200.0;
+const PAD_LEFT: f64 = 40.0;
+const PAD_RIGHT: f64 = 10.0;
+const PAD_TOP: f64 = 10.0;
+const PAD_BOTTOM: f64 = 24.0;
+
+#[derive(Properties, PartialEq)]
+pub struct LineChartProps {
+    pub points: Vec<ChartPoint>,
+}
+
+/// Values over time as a filled line, labelled with the first, middle and
+/// last period. Hovering a point shows its exact value.
+#[function_component(LineChart)]
+pub fn line_chart(props: &LineChartProps) -> Html {
+    let points = &props.points;
+    if points.is_empty() {
+        return html! { <div class="text-gray-400">{ "No data" }</div> };
+    }
+
+    let max = points.iter().map(|p| p.value).max().unwrap_or(0).max(1) as f64;
+    let plot_width = WIDTH - PAD_LEFT - PAD_RIGHT;
+    let plot_height = HEIGHT - PAD_TOP - PAD_BOTTOM;
+    let step = plot_width / (points.len().max(2) - 1) as f64;
+    let x = |i: usize| PAD_LEFT + i as f64 * step;
+    let y = |value: i64| PAD_TOP + plot_height * (1.0 - value as f64 / max);
+    let baseline = PAD_TOP + plot_height;
+
+    let line: Vec<String> = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| format!("{:.1},{:.1}", x(i), y(p.value)))
+        .collect();
+    let area = format!(
+        "M{:.1},{:.1} L{} L{:.1},{:.1} Z",
+        x(0),
+        baseline,
+        line.join(" L"),
+        x(points.len() - 1),
+        baseline
+    );
+
+    let mut labelled = vec![0, points.len() / 2, points.len() - 1];
+    labelled.dedup();
+
+    html! {
+        <svg viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)} class="w-full h-auto" role="img">
+            <line x1={PAD_LEFT.to_string()} y1={baseline.to_string()} x2={(WIDTH - PAD_RIGHT).to_string()} y2={baseline.to_string()} stroke="#374151" />
+            <line x1={PAD_LEFT.to_string()} y1={PAD_TOP.to_string()} x2={(WIDTH - PAD_RIGHT).to_string()} y2={PAD_TOP.to_string()} stroke="#1f2937" stroke-dasharray="4 4" />
+            <text x={(PAD_LEFT - 6.0).to_string()} y={(PAD_TOP + 4.0).to_string()} text-anchor="end" font-size="11" fill="#9ca3af">{ max as i64 }</text>
+            <text x={(PAD_LEFT - 6.0).to_string()} y={baseline.to_string()} text-anchor="end" font-size="11" fill="#9ca3af">{ "0" }</text>
+            <path d={area} fill="rgba(34, 211, 238, 0.15)" />
+            <polyline points={line.join(" ")} fill="none" stroke="#22d3ee" stroke-width="2" stroke-linejoin="round" />
+            { for points.iter().enumerate().map(|(i, p)| html! {
+                <circle cx={format!("{:.1}", x(i))} cy={format!("{:.1}", y(p.value))} r="3" fill="#22d3ee">
+                    <title>{ format!("{}: {}", p.label, p.value) }</title>
+                </circle>
+            }) }
+            { for labelled.into_iter().map(|i| html! {
+                <text x={format!("{:.1}", x(i))} y={(HEIGHT - 6.0).to_string()} text-anchor="middle" font-size="11" fill="#9ca3af">
+                    { &points[i].label }
+                </text>
+            }) }
+        </svg>
+    }
+}
+
+// Bar chart rows, in SVG user units
+const ROW_HEIGHT: f64 = 28.0;
+const LABEL_WIDTH: f64 = 160.0;
+const VALUE_WIDTH: f64 = 60.0;
+
+#[derive(Properties, PartialEq)]
+pub struct BarChartProps {
+    pub items: Vec<ChartPoint>,
+}
+
+/// Horizontal bars, one row per item in the given order, scaled to the largest.
+#[function_component(BarChart)]
+pub fn bar_chart(props: &BarChartProps) -> Html {
+    let items = &props.items;
+    if items.is_empty() {
+        return html! { <div class="text-gray-400">{ "No data" }</div> };
+    }
+
+    let max = items.iter().map(|p| p.value).max().unwrap_or(0).max(1) as f64;
+    let bar_space = WIDTH - LABEL_WIDTH - VALUE_WIDTH;
+    let height = ROW_HEIGHT * items.len() as f64;
+
+    html! {
+        <svg viewBox={format!("0 0 {} {}", WIDTH, height)} class="w-full h-auto" role="img">
+            { for items.iter().enumerate().map(|(i, item)| {
+                let top = i as f64 * ROW_HEIGHT;
+                let width = (bar_space * item.value as f64 / max).max(1.0);
+                let label: String = item.label.chars().take(24).collect();
+                html! {
+                    <g>
+                        <title>{ format!("{}: {}", item.label, item.value) }</title>
+                        <text x="0" y={format!("{:.1}", top + 18.0)} font-size="13" fill="#d1d5db">{ label }</text>
+                        <rect x={LABEL_WIDTH.to_string()} y={format!("{:.1}", top + 6.0)} width={format!("{:.1}", width)} height="16" rx="3" fill="#a855f7" />
+                        <text x={format!("{:.1}", LABEL_WIDTH + width + 6.0)} y={format!("{:.1}", top + 18.0)} font-size="13" fill="#9ca3af">{ item.value }</text>
+                    </g>
+                }
+            }) }
+        </svg>
+    }
+}
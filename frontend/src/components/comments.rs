@@ -0,0 +1,226 @@
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use crate::services::auth::auth_token;
+use crate::services::comments::{create_comment, list_comments, Comment, NewComment};
+
+#[derive(Properties, PartialEq)]
+pub struct CommentsSectionProps {
+    pub slug: String,
+}
+
+/// Approved comments under a post, with a form for new comments and replies.
+#[function_component(CommentsSection)]
+pub fn comments_section(props: &CommentsSectionProps) -> Html {
+    let comments = use_state(Vec::<Comment>::new);
+    let error = use_state(|| None as Option<String>);
+    let notice = use_state(|| None as Option<String>);
+    let reply_to = use_state(|| None as Option<i32>);
+    // Bumped to refetch after a comment is published
+    let reload = use_state(|| 0u32);
+
+    {
+        let comments = comments.clone();
+        let error = error.clone();
+        use_effect_with((props.slug.clone(), *reload), move |(slug, _)| {
+            let slug = slug.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match list_comments(&slug).await {
+                    Ok(list) => {
+                        comments.set(list);
+                        error.set(None);
+                    }
+                    Err(e) => error.set(Some(e)),
+                }
+            });
+        });
+    }
+
+    let on_posted = {
+        let notice = notice.clone();
+        let reply_to = reply_to.clone();
+        let reload = reload.clone();
+        Callback::from(move |comment: Comment| {
+            reply_to.set(None);
+            if comment.status == "approved" {
+                notice.set(None);
+                reload.set(*reload + 1);
+            } else {
+                notice.set(Some("Thanks! Your comment will appear once it has been approved.".to_string()));
+            }
+        })
+    };
+    let on_reply = {
+        let reply_to = reply_to.clone();
+        Callback::from(move |id: Option<i32>| reply_to.set(id))
+    };
+
+    html! {
+        <section class="space-y-6 border-t border-gray-800 pt-6">
+            <h2 class="text-2xl font-semibold text-cyan-300">{ format!("Comments ({})", count(&comments)) }</h2>
+            if let Some(err) = &*error {
+                <div class="text-red-400">{ format!("Error: {}", err) }</div>
+            }
+            if let Some(text) = &*notice {
+                <div class="rounded border border-cyan-700 bg-cyan-500/10 px-4 py-2 text-cyan-200">{ text }</div>
+            }
+            <div class="space-y-4">
+                { for comments.iter().map(|c| html! {
+                    <CommentItem
+                        key={c.id}
+                        comment={c.clone()}
+                        slug={props.slug.clone()}
+                        reply_to={*reply_to}
+                        on_reply={on_reply.clone()}
+                        on_posted={on_posted.clone()}
+                    />
+                }) }
+            </div>
+            if reply_to.is_none() {
+                <CommentForm slug={props.slug.clone()} parent_id={None::<i32>} on_posted={on_posted.clone()} />
+            }
+        </section>
+    }
+}
+
+fn count(comments: &[Comment]) -> usize {
+    comments.iter().map(|c| 1 + count(&c.replies)).sum()
+}
+
+#[derive(Properties, PartialEq)]
+struct CommentItemProps {
+    comment: Comment,
+    slug: String,
+    /// Comment whose reply form is open, if any
+    reply_to: Option<i32>,
+    on_reply: Callback<Option<i32>>,
+    on_posted: Callback<Comment>,
+}
+
+#[function_component(CommentItem)]
+fn comment_item(props: &CommentItemProps) -> Html {
+    let c = &props.comment;
+    let replying = props.reply_to == Some(c.id);
+    let toggle_reply = {
+        let on_reply = props.on_reply.clone();
+        let id = c.id;
+        Callback::from(move |_| on_reply.emit(if replying { None } else { Some(id) }))
+    };
+
+    html! {
+        <div class="space-y-2">
+            <div class="rounded-lg bg-gray-900 p-4 ring-1 ring-white/5">
+                <div class="flex items-center justify-between text-sm text-gray-400">
+                    <span class="font-medium text-gray-200">{ &c.author_name }</span>
+                    <span>{ c.created_at.format("%Y-%m-%d %H:%M").to_string() }</span>
+                </div>
+                <p class="mt-2 whitespace-pre-line text-gray-300">{ &c.content }</p>
+                <button class="mt-2 text-sm text-cyan-400 hover:text-cyan-300" onclick={toggle_reply}>
+                    { if replying { "Cancel" } else { "Reply" } }
+                </button>
+            </div>
+            <div class="ml-6 space-y-2 border-l border-gray-800 pl-4">
+                if replying {
+                    <CommentForm slug={props.slug.clone()} parent_id={Some(c.id)} on_posted={props.on_posted.clone()} />
+                }
+                { for c.replies.iter().map(|r| html! {
+                    <CommentItem
+                        key={r.id}
+                        comment={r.clone()}
+                        slug={props.slug.clone()}
+                        reply_to={props.reply_to}
+                        on_reply={props.on_reply.clone()}
+                        on_posted={props.on_posted.clone()}
+                    />
+                }) }
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct CommentFormProps {
+    slug: String,
+    parent_id: Option<i32>,
+    on_posted: Callback<Comment>,
+}
+
+/// Signed-in readers post under their account; everyone else gives a name and email.
+#[function_component(CommentForm)]
+fn comment_form(props: &CommentFormProps) -> Html {
+    let signed_in = auth_token().is_some();
+    let name_ref = use_node_ref();
+    let email_ref = use_node_ref();
+    let content_ref = use_node_ref();
+    let submitting = use_state(|| false);
+    let error = use_state(|| None as Option<String>);
+
+    let on_submit = {
+        let name_ref = name_ref.clone();
+        let email_ref = email_ref.clone();
+        let content_ref = content_ref.clone();
+        let submitting = submitting.clone();
+        let error = error.clone();
+        let slug = props.slug.clone();
+        let parent_id = props.parent_id;
+        let on_posted = props.on_posted.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let Some(content) = content_ref.cast::<HtmlTextAreaElement>() else {
+                return;
+            };
+            let value = |node: &NodeRef| node.cast::<HtmlInputElement>().map(|i| i.value());
+            let comment = NewComment {
+                parent_id,
+                author_name: value(&name_ref),
+                author_email: value(&email_ref),
+                content: content.value(),
+            };
+            submitting.set(true);
+            let submitting = submitting.clone();
+            let error = error.clone();
+            let slug = slug.clone();
+            let on_posted = on_posted.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match create_comment(&slug, &comment).await {
+                    Ok(posted) => {
+                        content.set_value("");
+                        error.set(None);
+                        on_posted.emit(posted);
+                    }
+                    Err(e) => error.set(Some(e)),
+                }
+                submitting.set(false);
+            });
+        })
+    };
+
+    let input_class = "w-full rounded bg-gray-900 px-3 py-2 text-white ring-1 ring-gray-700 focus:outline-none focus:ring-cyan-500";
+    html! {
+        <form class="space-y-3" onsubmit={on_submit}>
+            if !signed_in {
+                <div class="grid gap-3 sm:grid-cols-2">
+                    <input ref={name_ref} type="text" placeholder="Name" required=true maxlength="100" class={input_class} />
+                    <input ref={email_ref} type="email" placeholder="Email (not published)" required=true class={input_class} />
+                </div>
+            }
+            <textarea
+                ref={content_ref}
+                rows="4"
+                required=true
+                maxlength="5000"
+                placeholder={if props.parent_id.is_some() { "Write a reply" } else { "Join the discussion" }}
+                class={input_class}
+            />
+            if let Some(err) = &*error {
+                <div class="text-sm text-red-400">{ err }</div>
+            }
+            <button
+                type="submit"
+                disabled={*submitting}
+                class="rounded-lg bg-gradient-to-r from-cyan-500 to-purple-600 px-4 py-2 font-semibold text-white disabled:opacity-50"
+            >
+                { if *submitting { "Posting..." } else { "Post comment" } }
+            </button>
+        </form>
+    }
+}
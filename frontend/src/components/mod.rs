@@ -2,6 +2,9 @@ pub mod header;
 pub mod footer;
 pub mod button;
 pub mod form;
+pub mod comments;
+pub mod chart;
 
 pub use header::Header;
 pub use footer::Footer;
+pub use comments::CommentsSection;
@@ -2,6 +2,7 @@ use yew::prelude::*;
 use yew_router::prelude::*;
 
 pub mod app_routes;
+pub mod components;
 pub mod pages;
 pub mod config;
 pub mod services;
@@ -1,5 +1,10 @@
+use serde::Deserialize;
 use yew::prelude::*;
 use yew::virtual_dom::AttrValue;
+use yew_router::prelude::*;
+use crate::app_routes::Route;
+use crate::components::CommentsSection;
+use crate::pages::blogs_list::TaxonomyLinks;
 use crate::services::blogs::{get_blog_by_slug, BlogDetailResponse};
 
 #[derive(Properties, PartialEq)]
@@ -7,8 +12,18 @@ pub struct BlogDetailProps {
     pub slug: String,
 }
 
+/// `?preview=<token>` from a shared draft preview link
+#[derive(Deserialize, Clone, PartialEq, Default)]
+struct PreviewQuery {
+    preview: Option<String>,
+}
+
 #[function_component(BlogDetailPage)]
 pub fn blog_detail_page(props: &BlogDetailProps) -> Html {
+    let preview = use_location()
+        .and_then(|location| location.query::<PreviewQuery>().ok())
+        .unwrap_or_default()
+        .preview;
     let loading = use_state(|| true);
     let error = use_state(|| None as Option<String>);
     let blog = use_state(|| None as Option<BlogDetailResponse>);
@@ -17,19 +32,31 @@ pub fn blog_detail_page(props: &BlogDetailProps) -> Html {
         let loading = loading.clone();
         let error = error.clone();
         let blog = blog.clone();
-        let slug = props.slug.clone();
-        use_effect_with((), move |_| {
+        let navigator = use_navigator();
+        use_effect_with((props.slug.clone(), preview.clone()), move |(slug, preview)| {
+            // Already showing this post, e.g. after moving to its canonical slug below
+            if blog.as_ref().is_some_and(|b| &b.slug == slug) {
+                return;
+            }
             loading.set(true);
             error.set(None);
             let slug = slug.clone();
+            let preview = preview.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                match get_blog_by_slug(&slug).await {
-                    Ok(resp) => blog.set(Some(resp)),
+                match get_blog_by_slug(&slug, preview.as_deref()).await {
+                    Ok(resp) => {
+                        // Old slugs are redirected by the API; show the canonical URL
+                        if preview.is_none() && resp.slug != slug {
+                            if let Some(navigator) = &navigator {
+                                navigator.replace(&Route::BlogDetail { slug: resp.slug.clone() });
+                            }
+                        }
+                        blog.set(Some(resp));
+                    }
                     Err(e) => error.set(Some(e)),
                 }
                 loading.set(false);
             });
-            || ()
         });
     }
 
@@ -48,8 +75,15 @@ pub fn blog_detail_page(props: &BlogDetailProps) -> Html {
                     <div class="text-red-400">{format!("Error: {}", err)}</div>
                 } else if let Some(b) = &*blog {
                     <>
+                        if preview.is_some() {
+                            <div class="rounded border border-yellow-500 bg-yellow-500/10 px-4 py-2 text-yellow-300">
+                                { format!("Preview: this post is {} and not visible to readers yet.", b.status.as_deref().unwrap_or("draft")) }
+                            </div>
+                        }
                         <h1 class="text-4xl font-bold text-transparent bg-clip-text bg-gradient-to-r from-cyan-400 to-purple-500">{ &b.title }</h1>
-                        <div class="text-sm text-cyan-300 font-medium">{ format!("Views: {}", b.views_count) }</div>
+                        if preview.is_none() {
+                            <div class="text-sm text-cyan-300 font-medium">{ format!("Views: {}", b.views_count) }</div>
+                        }
                         <div class="text-sm text-gray-400">
                             { match b.published_at {
                                 Some(dt) => format!("Published: {}", dt.format("%Y-%m-%d %H:%M")),
@@ -59,9 +93,14 @@ pub fn blog_detail_page(props: &BlogDetailProps) -> Html {
                         if let Some(excerpt) = &b.excerpt {
                             <p class="text-gray-300 italic">{ excerpt }</p>
                         }
+                        <TaxonomyLinks category={b.category.clone()} tags={b.tags.clone()} />
                         <article class="prose prose-invert max-w-none">
-                            { Html::from_html_unchecked(AttrValue::from(b.content.clone())) }
+                            // Rendered from Markdown and sanitised by the backend
+                            { Html::from_html_unchecked(AttrValue::from(b.content_html.clone())) }
                         </article>
+                        if preview.is_none() {
+                            <CommentsSection slug={b.slug.clone()} />
+                        }
                     </>
                 } else {
                     <div class="text-gray-300">{"Blog not found"}</div>
@@ -1,10 +1,20 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
-use crate::services::blogs::{list_blogs, BlogListItem, BlogsListResponse};
+use crate::services::blogs::{list_blogs, BlogListItem, BlogsListResponse, TagResponse};
 use crate::app_routes::Route;
 
+#[derive(Properties, PartialEq)]
+pub struct BlogsListProps {
+    /// Restrict the archive to a tag slug
+    #[prop_or_default]
+    pub tag: Option<String>,
+    /// Restrict the archive to a category slug
+    #[prop_or_default]
+    pub category: Option<String>,
+}
+
 #[function_component(BlogsListPage)]
-pub fn blogs_list_page() -> Html {
+pub fn blogs_list_page(props: &BlogsListProps) -> Html {
     let loading = use_state(|| true);
     let error = use_state(|| None as Option<String>);
     let data = use_state(|| None as Option<BlogsListResponse>);
@@ -15,15 +25,15 @@ pub fn blogs_list_page() -> Html {
         let loading = loading.clone();
         let error = error.clone();
         let data = data.clone();
-        use_effect_with(*page, move |p| {
+        use_effect_with((*page, props.tag.clone(), props.category.clone()), move |deps| {
             let loading = loading.clone();
             let error = error.clone();
             let data = data.clone();
-            let current_page = *p;
+            let (current_page, tag, category) = deps.clone();
             loading.set(true);
             error.set(None);
             wasm_bindgen_futures::spawn_local(async move {
-                match list_blogs(current_page, per_page).await {
+                match list_blogs(current_page, per_page, tag.as_deref(), category.as_deref()).await {
                     Ok(resp) => { data.set(Some(resp)); }
                     Err(e) => { error.set(Some(e)); }
                 }
@@ -50,10 +60,16 @@ pub fn blogs_list_page() -> Html {
         })
     };
 
+    let heading = match (&props.tag, &props.category) {
+        (Some(tag), _) => format!("Posts tagged #{}", tag),
+        (None, Some(category)) => format!("Category: {}", category),
+        (None, None) => "Blogs".to_string(),
+    };
+
     html! {
         <div class="min-h-screen bg-black text-white p-6">
             <div class="max-w-5xl mx-auto space-y-6">
-                <h1 class="text-4xl font-bold text-transparent bg-clip-text bg-gradient-to-r from-cyan-400 to-purple-500">{"Blogs"}</h1>
+                <h1 class="text-4xl font-bold text-transparent bg-clip-text bg-gradient-to-r from-cyan-400 to-purple-500">{ heading }</h1>
 
                 if *loading {
                     <div class="flex items-center gap-3 text-cyan-300">
@@ -104,6 +120,7 @@ pub fn blog_card(props: &BlogCardProps) -> Html {
             if let Some(excerpt) = &item.excerpt {
                 <p class="text-gray-300">{ excerpt }</p>
             }
+            <TaxonomyLinks category={item.category.clone()} tags={item.tags.clone()} />
             <div class="mt-3 text-sm text-gray-400">
                 { match item.published_at {
                     Some(dt) => format!("Published: {}", dt.format("%Y-%m-%d %H:%M")),
@@ -113,3 +130,33 @@ pub fn blog_card(props: &BlogCardProps) -> Html {
         </div>
     }
 }
+
+#[derive(Properties, PartialEq)]
+pub struct TaxonomyLinksProps {
+    pub category: Option<String>,
+    pub tags: Vec<TagResponse>,
+}
+
+#[function_component(TaxonomyLinks)]
+pub fn taxonomy_links(props: &TaxonomyLinksProps) -> Html {
+    if props.category.is_none() && props.tags.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class="mt-3 flex flex-wrap items-center gap-2 text-sm">
+            if let Some(category) = &props.category {
+                <Link<Route> to={Route::CategoryArchive { category: category.clone() }}
+                    classes="rounded bg-purple-500/20 px-2 py-0.5 text-purple-300 hover:bg-purple-500/30">
+                    { category }
+                </Link<Route>>
+            }
+            { for props.tags.iter().map(|tag| html! {
+                <Link<Route> to={Route::TagArchive { tag: tag.slug.clone() }}
+                    classes="rounded bg-cyan-500/10 px-2 py-0.5 text-cyan-300 hover:bg-cyan-500/20">
+                    { format!("#{}", tag.name) }
+                </Link<Route>>
+            }) }
+        </div>
+    }
+}
@@ -0,0 +1,218 @@
+use chrono::{Duration, Utc};
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::app_routes::Route;
+use crate::components::chart::{BarChart, ChartPoint, LineChart};
+use crate::services::analytics::{
+    breakdown, post_analytics, top_posts, BreakdownResponse, DateRange, Granularity, PostAnalytics,
+    TopPost,
+};
+use crate::services::auth::auth_token;
+
+const RANGES: [(i64, &str); 3] = [(7, "Last 7 days"), (30, "Last 30 days"), (90, "Last 90 days")];
+
+fn range_ending_today(days: i64) -> DateRange {
+    let to = Utc::now().date_naive();
+    DateRange {
+        from: Some(to - Duration::days(days - 1)),
+        to: Some(to),
+    }
+}
+
+fn breakdown_points(breakdown: &Option<BreakdownResponse>) -> Vec<ChartPoint> {
+    breakdown
+        .iter()
+        .flat_map(|b| b.items.iter().map(|i| ChartPoint::new(i.value.clone(), i.views)))
+        .collect()
+}
+
+/// Views over time, top posts, referrers and devices for signed-in authors.
+/// Selecting a top post narrows the chart and breakdowns to that post.
+#[function_component(DashboardPage)]
+pub fn dashboard_page() -> Html {
+    let days = use_state(|| 30i64);
+    let granularity = use_state(Granularity::default);
+    // Post picked from the top posts list; site-wide breakdowns otherwise
+    let selected = use_state(|| None as Option<i32>);
+    let top = use_state(Vec::<TopPost>::new);
+    let series = use_state(|| None as Option<PostAnalytics>);
+    let referrers = use_state(|| None as Option<BreakdownResponse>);
+    let devices = use_state(|| None as Option<BreakdownResponse>);
+    let error = use_state(|| None as Option<String>);
+
+    {
+        let top = top.clone();
+        let error = error.clone();
+        use_effect_with(*days, move |days| {
+            let range = range_ending_today(*days);
+            wasm_bindgen_futures::spawn_local(async move {
+                match top_posts(range, 10).await {
+                    Ok(resp) => top.set(resp.items),
+                    Err(e) => error.set(Some(e)),
+                }
+            });
+        });
+    }
+
+    // The chart follows the selected post, or the most viewed one
+    let charted = (*selected).or_else(|| top.first().map(|p| p.id));
+    {
+        let series = series.clone();
+        let error = error.clone();
+        use_effect_with((*days, *granularity, charted), move |(days, granularity, charted)| {
+            let range = range_ending_today(*days);
+            let granularity = *granularity;
+            let charted = *charted;
+            wasm_bindgen_futures::spawn_local(async move {
+                let Some(id) = charted else {
+                    series.set(None);
+                    return;
+                };
+                match post_analytics(id, range, granularity).await {
+                    Ok(resp) => series.set(Some(resp)),
+                    Err(e) => error.set(Some(e)),
+                }
+            });
+        });
+    }
+
+    {
+        let referrers = referrers.clone();
+        let devices = devices.clone();
+        let error = error.clone();
+        use_effect_with((*days, *selected), move |(days, selected)| {
+            let range = range_ending_today(*days);
+            let selected = *selected;
+            wasm_bindgen_futures::spawn_local(async move {
+                match breakdown("referrer", selected, range, 10).await {
+                    Ok(resp) => referrers.set(Some(resp)),
+                    Err(e) => error.set(Some(e)),
+                }
+                match breakdown("device", selected, range, 10).await {
+                    Ok(resp) => devices.set(Some(resp)),
+                    Err(e) => error.set(Some(e)),
+                }
+            });
+        });
+    }
+
+    if auth_token().is_none() {
+        return html! {
+            <div class="min-h-screen bg-black text-white p-6">
+                <div class="max-w-5xl mx-auto space-y-4">
+                    <h1 class="text-4xl font-bold text-cyan-300">{ "Dashboard" }</h1>
+                    <p class="text-gray-300">{ "Sign in on the admin login page to see analytics." }</p>
+                </div>
+            </div>
+        };
+    }
+
+    let on_range = {
+        let days = days.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Ok(value) = select.value().parse() {
+                days.set(value);
+            }
+        })
+    };
+    let on_granularity = {
+        let granularity = granularity.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Ok(value) = select.value().parse() {
+                granularity.set(value);
+            }
+        })
+    };
+    let select_post = |id: Option<i32>| {
+        let selected = selected.clone();
+        Callback::from(move |_: MouseEvent| selected.set(id))
+    };
+
+    let series_points: Vec<ChartPoint> = series
+        .iter()
+        .flat_map(|s| s.series.iter().map(|p| ChartPoint::new(p.period.format("%b %d").to_string(), p.views)))
+        .collect();
+    let top_points: Vec<ChartPoint> = top.iter().map(|p| ChartPoint::new(p.title.clone(), p.views)).collect();
+    let selected_title = (*selected).and_then(|id| top.iter().find(|p| p.id == id).map(|p| p.title.clone()));
+    let select_class = "rounded bg-gray-900 px-3 py-2 text-white ring-1 ring-gray-700";
+    let card_class = "rounded-lg bg-gray-900 p-4 ring-1 ring-white/5 space-y-3";
+
+    html! {
+        <div class="min-h-screen bg-black text-white p-6">
+            <div class="max-w-5xl mx-auto space-y-6">
+                <div class="flex flex-wrap items-center justify-between gap-4">
+                    <h1 class="text-4xl font-bold text-transparent bg-clip-text bg-gradient-to-r from-cyan-400 to-purple-500">{ "Dashboard" }</h1>
+                    <div class="flex gap-3">
+                        <select class={select_class} onchange={on_range}>
+                            { for RANGES.iter().map(|(value, label)| html! {
+                                <option value={value.to_string()} selected={*value == *days}>{ *label }</option>
+                            }) }
+                        </select>
+                        <select class={select_class} onchange={on_granularity}>
+                            { for Granularity::ALL.iter().map(|g| html! {
+                                <option value={g.as_str()} selected={g == &*granularity}>{ g.as_str() }</option>
+                            }) }
+                        </select>
+                    </div>
+                </div>
+                if let Some(err) = &*error {
+                    <div class="text-red-400">{ format!("Error: {}", err) }</div>
+                }
+
+                <section class={card_class}>
+                    <h2 class="text-xl font-semibold text-cyan-300">
+                        { match &*series {
+                            Some(s) => format!("Views of \"{}\" ({})", s.title, s.total),
+                            None => "Views over time".to_string(),
+                        } }
+                    </h2>
+                    <LineChart points={series_points} />
+                    if let Some(s) = &*series {
+                        <Link<Route> to={Route::BlogDetail { slug: s.slug.clone() }} classes="text-sm text-cyan-400 hover:underline">
+                            { "Open post" }
+                        </Link<Route>>
+                    }
+                </section>
+
+                <section class={card_class}>
+                    <h2 class="text-xl font-semibold text-cyan-300">{ "Top posts" }</h2>
+                    <BarChart items={top_points} />
+                    <div class="flex flex-wrap gap-2 text-sm">
+                        <button class="rounded px-2 py-1 ring-1 ring-gray-700 hover:ring-cyan-500" onclick={select_post(None)}>
+                            { "All posts" }
+                        </button>
+                        { for top.iter().map(|p| html! {
+                            <button
+                                class={classes!("rounded", "px-2", "py-1", "ring-1", "hover:ring-cyan-500",
+                                    if *selected == Some(p.id) { "ring-cyan-500" } else { "ring-gray-700" })}
+                                onclick={select_post(Some(p.id))}
+                            >
+                                { &p.title }
+                            </button>
+                        }) }
+                    </div>
+                </section>
+
+                <div class="grid gap-6 md:grid-cols-2">
+                    <section class={card_class}>
+                        <h2 class="text-xl font-semibold text-cyan-300">{ "Referrers" }</h2>
+                        if let Some(title) = &selected_title {
+                            <div class="text-sm text-gray-400">{ title }</div>
+                        }
+                        <BarChart items={breakdown_points(&referrers)} />
+                    </section>
+                    <section class={card_class}>
+                        <h2 class="text-xl font-semibold text-cyan-300">{ "Devices" }</h2>
+                        if let Some(title) = &selected_title {
+                            <div class="text-sm text-gray-400">{ title }</div>
+                        }
+                        <BarChart items={breakdown_points(&devices)} />
+                    </section>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -3,9 +3,15 @@ pub mod health;
 pub mod blogs_list;
 pub mod blog_detail;
 pub mod not_found;
+pub mod tags;
+pub mod search;
+pub mod dashboard;
 
 pub use landing::LandingPage;
 pub use health::HealthPage;
-pub use blogs_list::BlogsListPage;
+pub use blogs_list::{BlogsListPage, BlogsListProps};
 pub use blog_detail::{BlogDetailPage, BlogDetailProps};
 pub use not_found::NotFoundPage;
+pub use tags::TagsPage;
+pub use search::SearchPage;
+pub use dashboard::DashboardPage;
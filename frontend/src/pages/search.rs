@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+use yew_router::prelude::*;
+use crate::services::blogs::{search_blogs, BlogSearchResponse};
+use crate::app_routes::Route;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub q: String,
+    #[serde(default = "first_page")]
+    pub page: u64,
+}
+
+fn first_page() -> u64 {
+    1
+}
+
+#[function_component(SearchPage)]
+pub fn search_page() -> Html {
+    let navigator = use_navigator();
+    let query = use_location()
+        .and_then(|location| location.query::<SearchQuery>().ok())
+        .unwrap_or_default();
+    let input_ref = use_node_ref();
+    let loading = use_state(|| false);
+    let error = use_state(|| None as Option<String>);
+    let data = use_state(|| None as Option<BlogSearchResponse>);
+    let per_page: u64 = 10;
+
+    {
+        let loading = loading.clone();
+        let error = error.clone();
+        let data = data.clone();
+        use_effect_with(query.clone(), move |query| {
+            let query = query.clone();
+            if query.q.trim().is_empty() {
+                data.set(None);
+            } else {
+                loading.set(true);
+                error.set(None);
+                wasm_bindgen_futures::spawn_local(async move {
+                    match search_blogs(&query.q, query.page.max(1), per_page).await {
+                        Ok(resp) => data.set(Some(resp)),
+                        Err(e) => error.set(Some(e)),
+                    }
+                    loading.set(false);
+                });
+            }
+            || ()
+        });
+    }
+
+    // Keep the query in the URL so results can be shared and survive reloads
+    let go_to = {
+        let navigator = navigator.clone();
+        Callback::from(move |query: SearchQuery| {
+            if let Some(navigator) = &navigator {
+                let _ = navigator.push_with_query(&Route::Search, &query);
+            }
+        })
+    };
+
+    let on_submit = {
+        let input_ref = input_ref.clone();
+        let go_to = go_to.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            if let Some(input) = input_ref.cast::<HtmlInputElement>() {
+                go_to.emit(SearchQuery { q: input.value(), page: 1 });
+            }
+        })
+    };
+
+    let on_prev = {
+        let go_to = go_to.clone();
+        let query = query.clone();
+        Callback::from(move |_| {
+            if query.page > 1 {
+                go_to.emit(SearchQuery { q: query.q.clone(), page: query.page - 1 });
+            }
+        })
+    };
+
+    let on_next = {
+        let go_to = go_to.clone();
+        let query = query.clone();
+        Callback::from(move |_| {
+            go_to.emit(SearchQuery { q: query.q.clone(), page: query.page + 1 });
+        })
+    };
+
+    html! {
+        <div class="min-h-screen bg-black text-white p-6">
+            <div class="max-w-5xl mx-auto space-y-6">
+                <h1 class="text-4xl font-bold text-transparent bg-clip-text bg-gradient-to-r from-cyan-400 to-purple-500">{"Search"}</h1>
+                <form onsubmit={on_submit} class="flex gap-3">
+                    <input ref={input_ref} type="search" value={query.q.clone()} placeholder="Search posts..."
+                        class="flex-1 rounded bg-gray-900 border border-cyan-500/30 px-4 py-2 text-white focus:outline-none focus:border-cyan-400" />
+                    <button type="submit" class="px-4 py-2 rounded bg-gray-800 hover:bg-gray-700">{"Search"}</button>
+                </form>
+
+                if *loading {
+                    <div class="text-cyan-300">{"Searching..."}</div>
+                } else if let Some(err) = &*error {
+                    <div class="text-red-400">{format!("Error: {}", err)}</div>
+                } else if let Some(resp) = &*data {
+                    <>
+                        <div class="text-sm text-gray-400">{format!("{} result(s)", resp.total)}</div>
+                        <div class="grid grid-cols-1 gap-6">
+                            { for resp.items.iter().map(|result| html! {
+                                <div class="p-6 rounded-lg border border-cyan-500/30 bg-gray-900/40">
+                                    <h2 class="text-2xl font-semibold mb-2">
+                                        <Link<Route> to={Route::BlogDetail { slug: result.item.slug.clone() }} classes="text-cyan-300 hover:underline">{ &result.item.title }</Link<Route>>
+                                    </h2>
+                                    <p class="text-gray-300">
+                                        // Escaped server-side apart from the <mark> highlights
+                                        { Html::from_html_unchecked(AttrValue::from(result.snippet.clone())) }
+                                    </p>
+                                </div>
+                            }) }
+                        </div>
+                        if resp.total_pages > 1 {
+                            <div class="flex items-center justify-between mt-6">
+                                <button onclick={on_prev}
+                                    class="px-4 py-2 rounded bg-gray-800 hover:bg-gray-700 disabled:opacity-50"
+                                    disabled={resp.page <= 1}
+                                >{"Previous"}</button>
+                                <div class="text-sm text-cyan-300">{format!("Page {} of {}", resp.page, resp.total_pages)}</div>
+                                <button onclick={on_next}
+                                    class="px-4 py-2 rounded bg-gray-800 hover:bg-gray-700 disabled:opacity-50"
+                                    disabled={resp.page >= resp.total_pages}
+                                >{"Next"}</button>
+                            </div>
+                        }
+                    </>
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,61 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::services::tags::{list_tags, TagWithCount};
+use crate::app_routes::Route;
+
+#[function_component(TagsPage)]
+pub fn tags_page() -> Html {
+    let loading = use_state(|| true);
+    let error = use_state(|| None as Option<String>);
+    let tags = use_state(Vec::<TagWithCount>::new);
+
+    {
+        let loading = loading.clone();
+        let error = error.clone();
+        let tags = tags.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match list_tags().await {
+                    Ok(resp) => tags.set(resp),
+                    Err(e) => error.set(Some(e)),
+                }
+                loading.set(false);
+            });
+            || ()
+        });
+    }
+
+    // Scale font size linearly between the least and most used tags
+    let max_count = tags.iter().map(|t| t.post_count).max().unwrap_or(1).max(1);
+    let size_class = |count: i64| match count * 4 / max_count {
+        0 => "text-sm",
+        1 => "text-base",
+        2 => "text-lg",
+        3 => "text-xl",
+        _ => "text-2xl",
+    };
+
+    html! {
+        <div class="min-h-screen bg-black text-white p-6">
+            <div class="max-w-5xl mx-auto space-y-6">
+                <h1 class="text-4xl font-bold text-transparent bg-clip-text bg-gradient-to-r from-cyan-400 to-purple-500">{"Tags"}</h1>
+                if *loading {
+                    <div class="text-cyan-300">{"Loading..."}</div>
+                } else if let Some(err) = &*error {
+                    <div class="text-red-400">{format!("Error: {}", err)}</div>
+                } else if tags.is_empty() {
+                    <div class="text-gray-300">{"No tags yet"}</div>
+                } else {
+                    <div class="flex flex-wrap items-baseline gap-4">
+                        { for tags.iter().map(|tag| html! {
+                            <Link<Route> to={Route::TagArchive { tag: tag.slug.clone() }}
+                                classes={classes!("text-cyan-300", "hover:underline", size_class(tag.post_count))}>
+                                { format!("#{} ({})", tag.name, tag.post_count) }
+                            </Link<Route>>
+                        }) }
+                    </div>
+                }
+            </div>
+        </div>
+    }
+}
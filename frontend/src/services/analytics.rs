@@ -0,0 +1,92 @@
+use gloo_net::http::Request;
+use serde::{de::DeserializeOwned, Deserialize};
+use crate::config::API_BASE_URL;
+use crate::services::auth::{auth_token, send_authorized, with_token};
+use chrono::NaiveDate;
+
+pub use shared::analytics::{
+    BreakdownItem, BreakdownResponse, Granularity, PostAnalytics, SeriesPoint, TopPost,
+    TopPostsResponse,
+};
+
+/// Inclusive UTC date range; the backend defaults to the last 30 days.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub struct DateRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl DateRange {
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        if let Some(from) = self.from {
+            query.push(("from", from.to_string()));
+        }
+        if let Some(to) = self.to {
+            query.push(("to", to.to_string()));
+        }
+        query
+    }
+}
+
+/// Every analytics endpoint needs a signed-in author or above.
+async fn fetch<T: DeserializeOwned>(url: &str, query: &[(&'static str, String)]) -> Result<T, String> {
+    auth_token().ok_or("Sign in to see analytics")?;
+    let response = send_authorized(|| {
+        with_token(Request::get(url).query(query.iter().map(|(k, v)| (*k, v.as_str())))).build()
+    })
+    .await?;
+
+    if !response.ok() {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            message: String,
+        }
+        #[derive(Deserialize)]
+        struct ApiError {
+            error: ErrorBody,
+        }
+        return Err(match response.json::<ApiError>().await {
+            Ok(body) => body.error.message,
+            Err(_) => format!("Request failed with status: {}", response.status()),
+        });
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// Views of one post over time.
+pub async fn post_analytics(id: i32, range: DateRange, granularity: Granularity) -> Result<PostAnalytics, String> {
+    let url = format!("{}/api/analytics/posts/{}", API_BASE_URL, id);
+    let mut query = range.query();
+    query.push(("granularity", granularity.to_string()));
+    fetch(&url, &query).await
+}
+
+pub async fn top_posts(range: DateRange, limit: u64) -> Result<TopPostsResponse, String> {
+    let url = format!("{}/api/analytics/posts/top", API_BASE_URL);
+    let mut query = range.query();
+    query.push(("limit", limit.to_string()));
+    fetch(&url, &query).await
+}
+
+/// Views grouped by `dimension` (e.g. `referrer`, `device`), for one post or
+/// the whole site.
+pub async fn breakdown(
+    dimension: &str,
+    post_id: Option<i32>,
+    range: DateRange,
+    limit: u64,
+) -> Result<BreakdownResponse, String> {
+    let url = format!("{}/api/analytics/breakdown", API_BASE_URL);
+    let mut query = range.query();
+    query.push(("dimension", dimension.to_string()));
+    query.push(("limit", limit.to_string()));
+    if let Some(id) = post_id {
+        query.push(("post_id", id.to_string()));
+    }
+    fetch(&url, &query).await
+}
@@ -0,0 +1,88 @@
+use gloo_net::http::{Request, RequestBuilder, Response};
+use shared::auth::{LoginResponse, RefreshRequest};
+use crate::config::API_BASE_URL;
+use crate::services::storage::StorageService;
+
+const ACCESS_TOKEN_KEY: &str = "auth_token";
+const REFRESH_TOKEN_KEY: &str = "refresh_token";
+
+/// Token saved by the backend's `/login` page, if the reader has signed in.
+pub fn auth_token() -> Option<String> {
+    StorageService::get_item(ACCESS_TOKEN_KEY).ok().flatten()
+}
+
+fn refresh_token() -> Option<String> {
+    StorageService::get_item(REFRESH_TOKEN_KEY).ok().flatten()
+}
+
+fn store_session(session: &LoginResponse) {
+    let _ = StorageService::set_item(ACCESS_TOKEN_KEY, &session.token);
+    let _ = StorageService::set_item(REFRESH_TOKEN_KEY, &session.refresh_token);
+}
+
+fn clear_session() {
+    let _ = StorageService::remove_item(ACCESS_TOKEN_KEY);
+    let _ = StorageService::remove_item(REFRESH_TOKEN_KEY);
+}
+
+/// Adds the stored token, if any, as a Bearer `Authorization` header.
+pub fn with_token(request: RequestBuilder) -> RequestBuilder {
+    match auth_token() {
+        Some(token) => request.header("Authorization", &format!("Bearer {}", token)),
+        None => request,
+    }
+}
+
+/// Trades the stored refresh token for a new pair. `expired` is the access
+/// token that was refused; if another tab has replaced it meanwhile there is
+/// nothing to do, and refreshing again would spend a token twice, which ends
+/// the session. Returns whether a usable token is stored afterwards.
+async fn refresh_session(expired: &str) -> bool {
+    if auth_token().as_deref() != Some(expired) {
+        return auth_token().is_some();
+    }
+    let Some(refresh_token) = refresh_token() else {
+        return false;
+    };
+    let url = format!("{}/api/auth/refresh", API_BASE_URL);
+    let Ok(request) = Request::post(&url).json(&RefreshRequest { refresh_token }) else {
+        return false;
+    };
+    match request.send().await {
+        Ok(response) if response.ok() => match response.json::<LoginResponse>().await {
+            Ok(session) => {
+                store_session(&session);
+                true
+            }
+            Err(_) => false,
+        },
+        Ok(response) if response.status() == 401 => {
+            clear_session();
+            false
+        }
+        _ => false,
+    }
+}
+
+/// Sends the request `build` makes, normally with `with_token`. Access tokens
+/// are short-lived, so on a 401 the session is refreshed and the request built
+/// and sent once more.
+pub async fn send_authorized<F>(build: F) -> Result<Response, String>
+where
+    F: Fn() -> Result<Request, gloo_net::Error>,
+{
+    let token = auth_token();
+    let send = || async {
+        build()
+            .map_err(|e| format!("Failed to build request: {}", e))?
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))
+    };
+
+    let response = send().await?;
+    match token {
+        Some(token) if response.status() == 401 && refresh_session(&token).await => send().await,
+        _ => Ok(response),
+    }
+}
@@ -1,51 +1,69 @@
 use gloo_net::http::Request;
 use serde::Deserialize;
 use crate::config::API_BASE_URL;
-use chrono;
+
+pub use shared::blogs::{BlogDetailResponse, BlogListItem, BlogsListResponse, TagResponse};
+
+pub async fn list_blogs(
+    page: u64,
+    per_page: u64,
+    tag: Option<&str>,
+    category: Option<&str>,
+) -> Result<BlogsListResponse, String> {
+    let url = format!("{}/api/blogs", API_BASE_URL);
+    let mut request = Request::get(&url).query([
+        ("page", page.to_string()),
+        ("per_page", per_page.to_string()),
+    ]);
+    if let Some(tag) = tag {
+        request = request.query([("tag", tag)]);
+    }
+    if let Some(category) = category {
+        request = request.query([("category", category)]);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let data: BlogsListResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(data)
+}
 
 #[derive(Deserialize, Clone, PartialEq)]
-pub struct BlogListItem {
-    pub id: i32,
-    pub slug: String,
-    pub title: String,
-    pub excerpt: Option<String>,
-    pub status: Option<String>,
-    pub published_at: Option<chrono::DateTime<chrono::Utc>>,    
+pub struct BlogSearchItem {
+    #[serde(flatten)]
+    pub item: BlogListItem,
+    /// Escaped by the backend; only `<mark>` tags are markup
+    pub snippet: String,
 }
 
 #[derive(Deserialize, Clone)]
-pub struct BlogsListResponse {
-    pub items: Vec<BlogListItem>,
+pub struct BlogSearchResponse {
+    pub items: Vec<BlogSearchItem>,
     pub page: u64,
     pub per_page: u64,
     pub total: u64,
     pub total_pages: u64,
 }
 
-#[derive(Deserialize, Clone)]
-pub struct BlogDetailResponse {
-    pub id: i32,
-    pub author_id: i32,
-    pub title: String,
-    pub slug: String,
-    pub excerpt: Option<String>,
-    pub content: String,
-    pub status: Option<String>,
-    pub published_at: Option<chrono::DateTime<chrono::Utc>>,    
-    pub created_at: Option<chrono::DateTime<chrono::Utc>>,    
-    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,    
-    #[serde(default)]
-    pub views_count: u64,
-}
-
-pub async fn list_blogs(page: u64, per_page: u64) -> Result<BlogsListResponse, String> {
-    let url = format!("{}/api/blogs?page={}&per_page={}", API_BASE_URL, page, per_page);
+pub async fn search_blogs(q: &str, page: u64, per_page: u64) -> Result<BlogSearchResponse, String> {
+    let url = format!("{}/api/blogs/search", API_BASE_URL);
     let response = Request::get(&url)
+        .query([
+            ("q", q.to_string()),
+            ("page", page.to_string()),
+            ("per_page", per_page.to_string()),
+        ])
         .send()
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
 
-    let data: BlogsListResponse = response
+    let data: BlogSearchResponse = response
         .json()
         .await
         .map_err(|e| format!("Failed to parse response: {}", e))?;
@@ -53,9 +71,41 @@ pub async fn list_blogs(page: u64, per_page: u64) -> Result<BlogsListResponse, S
     Ok(data)
 }
 
-pub async fn get_blog_by_slug(slug: &str) -> Result<BlogDetailResponse, String> {
+/// Pass a `preview` token to load an unpublished post from a shared preview link.
+/// Where the reader came from: the page's referrer plus any `utm_*`
+/// parameters, passed on so the backend can attribute the view.
+fn view_source() -> Vec<(&'static str, String)> {
+    let Some(window) = web_sys::window() else {
+        return Vec::new();
+    };
+    let mut source = Vec::new();
+    if let Some(referrer) = window.document().map(|d| d.referrer()).filter(|r| !r.is_empty()) {
+        source.push(("referrer", referrer));
+    }
+    let params = window
+        .location()
+        .search()
+        .ok()
+        .and_then(|search| web_sys::UrlSearchParams::new_with_str(&search).ok());
+    if let Some(params) = params {
+        for key in ["utm_source", "utm_medium", "utm_campaign"] {
+            if let Some(value) = params.get(key).filter(|v| !v.is_empty()) {
+                source.push((key, value));
+            }
+        }
+    }
+    source
+}
+
+pub async fn get_blog_by_slug(slug: &str, preview: Option<&str>) -> Result<BlogDetailResponse, String> {
     let url = format!("{}/api/blogs/{}", API_BASE_URL, slug);
-    let response = Request::get(&url)
+    let mut request = Request::get(&url);
+    match preview {
+        Some(token) => request = request.query([("preview", token)]),
+        // Previews aren't counted as views, so there is nothing to attribute
+        None => request = request.query(view_source().iter().map(|(k, v)| (*k, v.as_str()))),
+    }
+    let response = request
         .send()
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
@@ -0,0 +1,71 @@
+use gloo_net::http::Request;
+use serde::{Deserialize, Serialize};
+use crate::config::API_BASE_URL;
+use crate::services::auth::{send_authorized, with_token};
+use chrono;
+
+#[derive(Deserialize, Clone, PartialEq)]
+pub struct Comment {
+    pub id: i32,
+    pub parent_id: Option<i32>,
+    pub author_name: String,
+    pub content: String,
+    /// `approved`, or `pending` for a just-posted comment awaiting moderation
+    pub status: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub replies: Vec<Comment>,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct NewComment {
+    pub parent_id: Option<i32>,
+    /// Only needed when not signed in
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    pub content: String,
+}
+
+/// Approved comments on a post, threaded.
+pub async fn list_comments(slug: &str) -> Result<Vec<Comment>, String> {
+    let url = format!("{}/api/blogs/{}/comments", API_BASE_URL, slug);
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if !response.ok() {
+        return Err(format!("Request failed with status: {}", response.status()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// Posts as the signed-in user when a token is stored, anonymously otherwise.
+pub async fn create_comment(slug: &str, comment: &NewComment) -> Result<Comment, String> {
+    let url = format!("{}/api/blogs/{}/comments", API_BASE_URL, slug);
+    let response = send_authorized(|| with_token(Request::post(&url)).json(comment)).await?;
+
+    if !response.ok() {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            message: String,
+        }
+        #[derive(Deserialize)]
+        struct ApiError {
+            error: ErrorBody,
+        }
+        return Err(match response.json::<ApiError>().await {
+            Ok(body) => body.error.message,
+            Err(_) => format!("Request failed with status: {}", response.status()),
+        });
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
@@ -1,3 +1,7 @@
 pub mod storage;
+pub mod auth;
 pub mod health;
 pub mod blogs;
+pub mod tags;
+pub mod comments;
+pub mod analytics;
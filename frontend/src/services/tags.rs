@@ -0,0 +1,25 @@
+use gloo_net::http::Request;
+use serde::Deserialize;
+use crate::config::API_BASE_URL;
+
+#[derive(Deserialize, Clone, PartialEq)]
+pub struct TagWithCount {
+    pub name: String,
+    pub slug: String,
+    pub post_count: i64,
+}
+
+pub async fn list_tags() -> Result<Vec<TagWithCount>, String> {
+    let url = format!("{}/api/tags", API_BASE_URL);
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let data: Vec<TagWithCount> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(data)
+}
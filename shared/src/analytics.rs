@@ -0,0 +1,124 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Length of the periods a view series is summed into.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl Granularity {
+    pub const ALL: [Granularity; 3] = [Granularity::Day, Granularity::Week, Granularity::Month];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Granularity::Day => "day",
+            Granularity::Week => "week",
+            Granularity::Month => "month",
+        }
+    }
+
+    /// First day of the period containing `day`; weeks start on Monday.
+    pub fn period_start(&self, day: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => day,
+            Granularity::Week => day - Duration::days(day.weekday().num_days_from_monday() as i64),
+            Granularity::Month => day.with_day(1).unwrap_or(day),
+        }
+    }
+
+    /// First day of the period after the one starting on `start`.
+    pub fn next_period(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => start + Duration::days(1),
+            Granularity::Week => start + Duration::weeks(1),
+            Granularity::Month => start
+                .checked_add_months(chrono::Months::new(1))
+                .unwrap_or(NaiveDate::MAX),
+        }
+    }
+}
+
+impl fmt::Display for Granularity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Granularity {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(Granularity::Day),
+            "week" => Ok(Granularity::Week),
+            "month" => Ok(Granularity::Month),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SeriesPoint {
+    /// First day of the period
+    pub period: NaiveDate,
+    pub views: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostAnalytics {
+    pub post_id: i32,
+    pub title: String,
+    pub slug: String,
+    pub granularity: Granularity,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Views within the range
+    pub total: i64,
+    pub series: Vec<SeriesPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TopPost {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub views: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TopPostsResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub items: Vec<TopPost>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BreakdownItem {
+    pub value: String,
+    pub views: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BreakdownResponse {
+    /// `referrer`, `utm_source`, `utm_medium`, `utm_campaign`, `device`, `browser` or `os`
+    pub dimension: String,
+    pub post_id: Option<i32>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Views across every value, including those beyond `limit`
+    pub total: i64,
+    pub items: Vec<BreakdownItem>,
+}
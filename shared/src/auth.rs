@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+#[cfg(feature = "validate")]
+use validator::Validate;
+
+/// User roles ordered by privilege, so `role >= Role::Editor` reads naturally.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    Reader,
+    Author,
+    Editor,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Reader => "reader",
+            Role::Author => "author",
+            Role::Editor => "editor",
+            Role::Admin => "admin",
+        }
+    }
+
+    /// Parses the `users.role` column, falling back to the least privileged
+    /// role for values edited into something unknown.
+    pub fn from_db(value: &str) -> Self {
+        value.parse().unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reader" => Ok(Role::Reader),
+            "author" => Ok(Role::Author),
+            "editor" => Ok(Role::Editor),
+            "admin" => Ok(Role::Admin),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(Validate))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RegisterRequest {
+    #[cfg_attr(feature = "validate", validate(email))]
+    pub email: String,
+    #[cfg_attr(feature = "validate", validate(length(min = 1, max = 255)))]
+    pub name: String,
+    #[cfg_attr(feature = "validate", validate(length(min = 8, max = 72)))]
+    pub password: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(Validate))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    #[cfg_attr(feature = "validate", validate(length(min = 8, max = 72)))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LoginResponse {
+    /// Short-lived access token for the `Authorization: Bearer` header
+    pub token: String,
+    /// Seconds until `token` expires
+    pub expires_in: u64,
+    /// Exchanged at `/api/auth/refresh` for a new pair; valid once
+    pub refresh_token: String,
+    pub user: UserResponse,
+}
+
+/// Body of `/api/auth/refresh` and `/api/auth/logout`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UserResponse {
+    pub id: i32,
+    pub email: String,
+    pub name: String,
+    pub role: Role,
+}
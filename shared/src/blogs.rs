@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TagResponse {
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BlogListItem {
+    pub id: i32,
+    pub slug: String,
+    pub title: String,
+    pub excerpt: Option<String>,
+    pub status: Option<String>,
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<TagResponse>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BlogsListResponse {
+    pub items: Vec<BlogListItem>,
+    pub page: u64,
+    pub per_page: u64,
+    pub total: u64,
+    pub total_pages: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BlogDetailResponse {
+    pub id: i32,
+    pub author_id: i32,
+    pub title: String,
+    pub slug: String,
+    pub excerpt: Option<String>,
+    /// Markdown source
+    pub content: String,
+    /// Sanitised HTML, safe to render directly
+    pub content_html: String,
+    pub status: Option<String>,
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<TagResponse>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// As of the last view rollup, so recent views show up after a short delay
+    #[serde(default)]
+    pub views_count: u64,
+}
@@ -0,0 +1,8 @@
+//! API types used by both `backend` (serialising) and `frontend`
+//! (deserialising). Field names and shapes here are the wire format. `routes`
+//! holds the frontend paths the backend links to.
+
+pub mod analytics;
+pub mod auth;
+pub mod blogs;
+pub mod routes;
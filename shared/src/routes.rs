@@ -0,0 +1,5 @@
+//! Frontend page paths the backend needs to know about.
+
+/// Public frontend pages that take no parameters, which the sitemap lists.
+/// Each is an `#[at]` path of the frontend `Route` enum.
+pub const STATIC_PAGES: &[&str] = &["/", "/blogs", "/tags", "/search"];
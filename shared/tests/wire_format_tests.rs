@@ -0,0 +1,57 @@
+// Tests for the JSON shape of the shared API types
+
+use shared::{
+    analytics::{BreakdownResponse, Granularity, PostAnalytics},
+    auth::{Role, UserResponse},
+    blogs::{BlogDetailResponse, BlogsListResponse},
+};
+
+#[test]
+fn roles_are_lowercase_and_ordered_by_privilege() {
+    let user: UserResponse =
+        serde_json::from_str(r#"{"id": 1, "email": "a@b.c", "name": "A", "role": "editor"}"#).unwrap();
+    assert_eq!(user.role, Role::Editor);
+    assert!(Role::Reader < Role::Author && Role::Editor < Role::Admin);
+    assert_eq!(Role::from_db("superuser"), Role::Reader);
+    assert_eq!(serde_json::to_value(Role::Admin).unwrap(), "admin");
+}
+
+#[test]
+fn posts_round_trip_and_tolerate_missing_extras() {
+    let json = r#"{
+        "id": 7, "author_id": 1, "title": "Hello", "slug": "hello", "excerpt": null,
+        "content": "*Hi*", "content_html": "<p><em>Hi</em></p>", "status": "published",
+        "category": null, "published_at": "2026-01-02T03:04:05Z",
+        "created_at": null, "updated_at": null
+    }"#;
+    let post: BlogDetailResponse = serde_json::from_str(json).unwrap();
+    assert!(post.tags.is_empty());
+    assert_eq!(post.views_count, 0);
+
+    let again: BlogDetailResponse =
+        serde_json::from_value(serde_json::to_value(&post).unwrap()).unwrap();
+    assert_eq!(again, post);
+
+    let page: BlogsListResponse = serde_json::from_str(
+        r#"{"items": [], "page": 1, "per_page": 10, "total": 0, "total_pages": 0}"#,
+    )
+    .unwrap();
+    assert!(page.items.is_empty());
+}
+
+#[test]
+fn analytics_granularity_and_ranges_are_typed() {
+    let post: PostAnalytics = serde_json::from_str(
+        r#"{"post_id": 1, "title": "T", "slug": "t", "granularity": "week",
+            "from": "2026-01-05", "to": "2026-01-18", "total": 3,
+            "series": [{"period": "2026-01-05", "views": 3}, {"period": "2026-01-12", "views": 0}]}"#,
+    )
+    .unwrap();
+    assert_eq!(post.granularity, Granularity::Week);
+    assert_eq!(post.granularity.next_period(post.from), post.series[1].period);
+    assert_eq!("month".parse(), Ok(Granularity::Month));
+
+    // Breakdowns always report the range they cover
+    let missing_range = r#"{"dimension": "device", "post_id": null, "total": 0, "items": []}"#;
+    assert!(serde_json::from_str::<BreakdownResponse>(missing_range).is_err());
+}